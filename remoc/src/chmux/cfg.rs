@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use super::msg::MAX_MSG_LENGTH;
+use super::{ChannelPriorities, Compression, QuotaPolicy, msg::MAX_MSG_LENGTH};
 
 /// Behavior when ports are exhausted and a connect is requested.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -30,6 +30,12 @@ pub struct Cfg {
     /// Pings are send automatically when this is enabled and no data is transmitted.
     /// By default this is 60 seconds.
     pub connection_timeout: Option<Duration>,
+    /// Time after which a ping requested by [Client::ping](super::Client::ping)
+    /// fails if it has not been answered by the remote endpoint.
+    ///
+    /// This applies regardless of the [connection timeout](Self::connection_timeout).
+    /// By default this is 60 seconds.
+    pub ping_timeout: Duration,
     /// Maximum number of open ports.
     ///
     /// This must not exceed 2^31 = 2147483648.
@@ -82,6 +88,8 @@ pub struct Cfg {
     /// Length of global send queue.
     /// Each element holds a chunk.
     ///
+    /// A separate queue of this length is used for each [send priority](super::Priority).
+    ///
     /// This limits the number of chunks sendable by using
     /// [Sender::try_send](super::Sender::try_send).
     /// It will not affect [remote channels](crate::rch).
@@ -95,6 +103,9 @@ pub struct Cfg {
     /// Raising this may improve performance but might incur a slight increase in latency.
     /// For minimum latency this should be set to 1.
     ///
+    /// A separate queue of this length is used for each [send priority](super::Priority),
+    /// so that queued data of a lower priority does not delay data of a higher priority.
+    ///
     /// By default this is 128.
     /// This must not be zero.
     pub transport_send_queue: usize,
@@ -112,6 +123,90 @@ pub struct Cfg {
     /// By default this is 128.
     /// This must not be zero.
     pub connect_queue: u16,
+    /// Enables resumption of the session after a failure of the transport.
+    ///
+    /// If enabled on both endpoints, sent messages are buffered until their reception
+    /// has been acknowledged by the remote endpoint.
+    /// When the transport fails, [ChMux::run_with_reconnect](super::ChMux::run_with_reconnect)
+    /// obtains a new transport, resumes the session and resends lost messages.
+    /// Thus all open ports and channels survive the transport failure.
+    ///
+    /// The reconnect function of each endpoint must provide a new transport to the same
+    /// remote endpoint.
+    /// A new transport leading to a different session is dropped and the reconnect function
+    /// is called again.
+    /// Since incoming connections are not routed to the multiplexer owning their session,
+    /// a shared accept loop cannot be used for obtaining new transports.
+    /// Instead the accepting endpoint should use a listener dedicated to the connection
+    /// or route new transports to the right connection by other means.
+    ///
+    /// By default this is false.
+    pub session_resumption: bool,
+    /// Maximum number of sent messages buffered for session resumption.
+    ///
+    /// Sent messages are kept until their reception has been acknowledged by the remote endpoint.
+    /// If this limit is exceeded, the session fails with [SessionLost](super::ChMuxError::SessionLost).
+    ///
+    /// By default this is 16384.
+    /// This must not be zero.
+    pub session_buffer: usize,
+    /// Send priorities of kinds of [remote channels](crate::rch).
+    ///
+    /// By default all channels inherit the send priority of the channel they were sent over.
+    pub channel_priorities: ChannelPriorities,
+    /// Compression algorithm for sent data.
+    ///
+    /// Data is only compressed if the remote endpoint supports the algorithm.
+    /// Received data is decompressed using any algorithm enabled by crate features,
+    /// independently of this setting.
+    /// Compression applies to each chunk of data and is transparent to
+    /// [codecs](crate::codec).
+    ///
+    /// By default this is `None`, i.e. no compression.
+    pub compression: Option<Compression>,
+    /// Minimum size of a chunk of data in bytes for it to be compressed.
+    ///
+    /// Smaller chunks are sent uncompressed.
+    /// A chunk is also sent uncompressed, if compression does not reduce its size.
+    ///
+    /// By default this is 512 bytes.
+    pub compression_threshold: u32,
+    /// Maximum total size in bytes of the [receive buffers](Self::receive_buffer)
+    /// of all open ports and pending port requests.
+    ///
+    /// This limits the memory the remote endpoint can make us commit for receiving data.
+    /// The memory is estimated as the number of open ports and pending port requests
+    /// multiplied by the size of the receive buffer.
+    /// This is an upper bound, since receive buffers are only filled as data arrives
+    /// and the actual memory usage is usually much lower.
+    /// It is checked when the remote endpoint requests to open a port.
+    /// When exceeded, the [quota policy](Self::quota_policy) applies.
+    ///
+    /// By default this is `None`, i.e. unlimited.
+    pub max_receive_buffer_memory: Option<u64>,
+    /// Maximum number of running helper tasks spawned on behalf of the remote endpoint.
+    ///
+    /// Helper tasks are spawned for each port request from the remote endpoint
+    /// and by [remote channels](crate::rch) for each received channel, for example
+    /// to forward its data.
+    /// This is checked when the remote endpoint requests to open a port.
+    /// When exceeded, the [quota policy](Self::quota_policy) applies.
+    ///
+    /// By default this is `None`, i.e. unlimited.
+    pub max_helper_tasks: Option<usize>,
+    /// Maximum number of port requests from the remote endpoint that have been
+    /// neither accepted nor rejected.
+    ///
+    /// Unlike [connect_queue](Self::connect_queue), this includes ports sent over
+    /// established ports, i.e. channels sent over remote channels.
+    /// When exceeded, the [quota policy](Self::quota_policy) applies.
+    ///
+    /// By default this is `None`, i.e. unlimited.
+    pub max_pending_port_requests: Option<usize>,
+    /// Behavior when the remote endpoint exceeds a quota.
+    ///
+    /// By default port requests exceeding a quota are rejected.
+    pub quota_policy: QuotaPolicy,
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
@@ -122,6 +217,7 @@ impl Default for Cfg {
     fn default() -> Self {
         Self {
             connection_timeout: Some(Duration::from_secs(60)),
+            ping_timeout: Duration::from_secs(60),
             max_ports: 16_384,
             ports_exhausted: PortsExhausted::Wait(Some(Duration::from_secs(60))),
             max_data_size: 524_288,
@@ -132,6 +228,15 @@ impl Default for Cfg {
             transport_send_queue: 128,
             transport_receive_queue: 128,
             connect_queue: 128,
+            session_resumption: false,
+            session_buffer: 16_384,
+            channel_priorities: ChannelPriorities::default(),
+            compression: None,
+            compression_threshold: 512,
+            max_receive_buffer_memory: None,
+            max_helper_tasks: None,
+            max_pending_port_requests: None,
+            quota_policy: QuotaPolicy::Reject,
             _non_exhaustive: (),
         }
     }
@@ -170,6 +275,16 @@ impl Cfg {
         if self.connect_queue == 0 {
             panic!("connect queue length must not be zero");
         }
+
+        if self.session_buffer == 0 {
+            panic!("session buffer length must not be zero");
+        }
+
+        if let Some(compression) = self.compression
+            && !compression.is_enabled()
+        {
+            panic!("compression algorithm {compression:?} is not enabled by crate features");
+        }
     }
 
     /// Returns the maximum size of a frame that can be received by a
@@ -181,6 +296,23 @@ impl Cfg {
         (MAX_MSG_LENGTH as u32).checked_add(self.chunk_size).expect("maximum frame size exceeds u32::MAX")
     }
 
+    /// Returns the WebSocket configuration with message and frame size limits
+    /// matching the [maximum frame length](Self::max_frame_length).
+    ///
+    /// Use it for the WebSocket handshake of a stream passed to
+    /// [ConnectOptions::websocket](crate::ConnectOptions::websocket).
+    ///
+    /// # Panics
+    /// Panics if the configuration is invalid.
+    #[cfg(all(feature = "websocket", not(target_family = "wasm")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub fn websocket_config(&self) -> tokio_tungstenite::tungstenite::protocol::WebSocketConfig {
+        let max_size = Some(self.max_frame_length() as usize);
+        tokio_tungstenite::tungstenite::protocol::WebSocketConfig::default()
+            .max_message_size(max_size)
+            .max_frame_size(max_size)
+    }
+
     /// Configuration that is balanced between memory usage, latency and throughput.
     pub fn balanced() -> Self {
         Self::default()
@@ -9,16 +9,22 @@ use std::{
         atomic::{AtomicBool, Ordering},
     },
     task::{Context, Poll},
+    time::Duration,
 };
 use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc, oneshot};
 
 use super::{
-    PortReq,
+    PortReq, Priority,
+    mux::ShutdownReq,
+    ping::PingRequest,
     port_allocator::{PortAllocator, PortNumber},
     receiver::Receiver,
     sender::Sender,
 };
-use crate::{exec, exec::task::JoinHandle};
+use crate::{
+    exec,
+    exec::{task::JoinHandle, time::timeout},
+};
 
 /// An error occurred during connecting to a remote service.
 #[derive(Debug, Clone)]
@@ -63,6 +69,30 @@ impl From<ConnectError> for std::io::Error {
     }
 }
 
+/// An error occurred during pinging the remote endpoint.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PingError {
+    /// The remote endpoint does not answer pings.
+    Unsupported,
+    /// The remote endpoint did not answer the ping in time.
+    Timeout,
+    /// A multiplexer error has occurred or it has been terminated.
+    ChMux,
+}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "remote endpoint does not answer pings"),
+            Self::Timeout => write!(f, "ping timed out"),
+            Self::ChMux => write!(f, "multiplexer error"),
+        }
+    }
+}
+
+impl Error for PingError {}
+
 /// Accounts connection request credits.
 #[derive(Debug, Clone)]
 struct ConnectRequestCrediter(Arc<Semaphore>);
@@ -104,6 +134,8 @@ pub(crate) struct ConnectRequest {
     pub local_port: PortNumber,
     /// Port id.
     pub id: u32,
+    /// Send priority of port.
+    pub priority: Priority,
     /// Notification that request has been queued for sending.
     pub sent_tx: mpsc::Sender<()>,
     /// Response channel sender.
@@ -162,10 +194,12 @@ impl Future for Connect {
 #[derive(Clone)]
 pub struct Client {
     tx: mpsc::UnboundedSender<ConnectRequest>,
+    ping_tx: Option<mpsc::UnboundedSender<PingRequest>>,
+    ping_timeout: Duration,
     crediter: ConnectRequestCrediter,
     port_allocator: PortAllocator,
     listener_dropped: Arc<AtomicBool>,
-    terminate_tx: mpsc::UnboundedSender<()>,
+    terminate_tx: mpsc::UnboundedSender<ShutdownReq>,
 }
 
 impl fmt::Debug for Client {
@@ -176,11 +210,14 @@ impl fmt::Debug for Client {
 
 impl Client {
     pub(crate) fn new(
-        tx: mpsc::UnboundedSender<ConnectRequest>, limit: u16, port_allocator: PortAllocator,
-        listener_dropped: Arc<AtomicBool>, terminate_tx: mpsc::UnboundedSender<()>,
+        tx: mpsc::UnboundedSender<ConnectRequest>, ping_tx: Option<mpsc::UnboundedSender<PingRequest>>,
+        ping_timeout: Duration, limit: u16, port_allocator: PortAllocator, listener_dropped: Arc<AtomicBool>,
+        terminate_tx: mpsc::UnboundedSender<ShutdownReq>,
     ) -> Client {
         Client {
             tx,
+            ping_tx,
+            ping_timeout,
             crediter: ConnectRequestCrediter::new(limit),
             port_allocator,
             listener_dropped,
@@ -202,8 +239,9 @@ impl Client {
 
     /// Start opening a new port to the remote endpoint with extended options.
     ///
-    /// If `local_port` is [None] a new local port number is allocated.
-    /// Otherwise the specified port is used.
+    /// If `local_port` is [None] a new local port number with [normal priority](Priority::Normal)
+    /// is allocated.
+    /// Otherwise the specified port and its [send priority](PortReq::priority) are used.
     ///
     /// If `wait` is true, this function waits until a local and remote port become available.
     /// Otherwise it returns the appropriate [ConnectError] if no ports are available.
@@ -237,8 +275,8 @@ impl Client {
         // Build and send request.
         let (sent_tx, sent_rx) = mpsc::channel(1);
         let (response_tx, response_rx) = oneshot::channel();
-        let PortReq { port: local_port, id } = local_port;
-        let req = ConnectRequest { local_port, id, sent_tx, response_tx, wait };
+        let PortReq { port: local_port, id, priority } = local_port;
+        let req = ConnectRequest { local_port, id, priority, sent_tx, response_tx, wait };
         let _ = self.tx.send(req);
 
         let listener_dropped = self.listener_dropped.clone();
@@ -269,8 +307,42 @@ impl Client {
         Ok(Connect { sent_rx, response })
     }
 
+    /// Pings the remote endpoint and returns the round-trip time.
+    ///
+    /// The ping is sent immediately and does not wait for queued data.
+    /// Thus this measures the latency of the transport and can be used as a
+    /// liveness check of the connection.
+    ///
+    /// If the session is resumed after a transport failure, the ping is sent again.
+    /// If the ping is not answered within the [ping timeout](super::Cfg::ping_timeout),
+    /// it fails with [PingError::Timeout].
+    pub async fn ping(&self) -> Result<Duration, PingError> {
+        let ping_tx = self.ping_tx.as_ref().ok_or(PingError::Unsupported)?;
+        let (tx, rx) = oneshot::channel();
+        ping_tx.send(tx).map_err(|_| PingError::ChMux)?;
+        match timeout(self.ping_timeout, rx).await {
+            Ok(res) => res.map_err(|_| PingError::ChMux)?,
+            Err(_) => Err(PingError::Timeout),
+        }
+    }
+
     /// Terminates the multiplexer, forcibly closing all open ports.
     pub fn terminate(&self) {
-        let _ = self.terminate_tx.send(());
+        let _ = self.terminate_tx.send(ShutdownReq::Terminate);
+    }
+
+    /// Gracefully shuts down the multiplexer.
+    ///
+    /// The multiplexer stops accepting new ports, both from the remote endpoint
+    /// and from local clients, and notifies the remote endpoint that no more connect
+    /// requests will be handled.
+    /// Ports that are already open, including ports sent over them, continue to work.
+    /// Once all ports have been closed the multiplexer terminates and
+    /// [ChMux::run](super::ChMux::run) returns successfully.
+    ///
+    /// If ports are still open after `timeout` has elapsed, the multiplexer is
+    /// [terminated](Self::terminate) forcibly.
+    pub fn drain(&self, timeout: Duration) {
+        let _ = self.terminate_tx.send(ShutdownReq::Drain(timeout));
     }
 }
@@ -0,0 +1,129 @@
+//! Compression of transmitted data.
+
+use std::io;
+
+/// Compression algorithm for data transmitted over the transport.
+///
+/// Each algorithm requires the corresponding crate feature.
+/// An algorithm is only used if the remote endpoint also supports it,
+/// otherwise data is sent uncompressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Compression {
+    /// Deflate compression.
+    ///
+    /// Requires the `compress-deflate` crate feature.
+    Deflate,
+    /// LZ4 compression.
+    ///
+    /// This is fast but achieves a lower compression ratio than other algorithms.
+    ///
+    /// Requires the `compress-lz4` crate feature.
+    Lz4,
+    /// Zstandard compression.
+    ///
+    /// Requires the `compress-zstd` crate feature.
+    Zstd,
+}
+
+impl Compression {
+    /// All compression algorithms.
+    const ALL: [Compression; 3] = [Compression::Deflate, Compression::Lz4, Compression::Zstd];
+
+    /// Whether support for the compression algorithm is enabled by crate features.
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            Self::Deflate => cfg!(feature = "compress-deflate"),
+            Self::Lz4 => cfg!(feature = "compress-lz4"),
+            Self::Zstd => cfg!(feature = "compress-zstd"),
+        }
+    }
+
+    /// Identifier of the algorithm within the protocol.
+    ///
+    /// This is never zero, since zero indicates uncompressed data.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Self::Deflate => 1,
+            Self::Lz4 => 2,
+            Self::Zstd => 3,
+        }
+    }
+
+    /// Algorithm from its identifier.
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.id() == id)
+    }
+
+    /// Bit mask of all enabled algorithms, announced to the remote endpoint.
+    pub(crate) fn enabled_mask() -> u8 {
+        Self::ALL.into_iter().filter(|c| c.is_enabled()).fold(0, |mask, c| mask | c.mask())
+    }
+
+    /// Bit of the algorithm within a mask.
+    pub(crate) fn mask(self) -> u8 {
+        1 << self.id()
+    }
+
+    /// Compresses data.
+    ///
+    /// # Panics
+    /// Panics if the algorithm is not enabled.
+    #[allow(unused_variables)]
+    pub(crate) fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "compress-deflate")]
+            Self::Deflate => {
+                use std::io::Write;
+                let mut enc = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+                enc.write_all(data).expect("deflate compression failed");
+                enc.finish().expect("deflate compression failed")
+            }
+            #[cfg(feature = "compress-lz4")]
+            Self::Lz4 => lz4_flex::block::compress_prepend_size(data),
+            #[cfg(feature = "compress-zstd")]
+            Self::Zstd => zstd::bulk::compress(data, 1).expect("zstd compression failed"),
+            #[allow(unreachable_patterns)]
+            _ => panic!("compression algorithm {self:?} is not enabled"),
+        }
+    }
+
+    /// Decompresses data.
+    ///
+    /// Fails if the decompressed data would exceed `max_len` bytes.
+    #[allow(unused_variables)]
+    pub(crate) fn decompress(self, data: &[u8], max_len: usize) -> Result<Vec<u8>, io::Error> {
+        let too_long = || io::Error::new(io::ErrorKind::InvalidData, "decompressed data too long");
+
+        match self {
+            #[cfg(feature = "compress-deflate")]
+            Self::Deflate => {
+                use std::io::Read;
+                let mut buf = Vec::new();
+                flate2::read::DeflateDecoder::new(data).take(max_len as u64 + 1).read_to_end(&mut buf)?;
+                if buf.len() > max_len {
+                    return Err(too_long());
+                }
+                Ok(buf)
+            }
+            #[cfg(feature = "compress-lz4")]
+            Self::Lz4 => {
+                let (len, data) = data.split_at_checked(4).ok_or(io::ErrorKind::UnexpectedEof)?;
+                let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+                if len > max_len {
+                    return Err(too_long());
+                }
+                lz4_flex::block::decompress(data, len)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+            #[cfg(feature = "compress-zstd")]
+            Self::Zstd => zstd::bulk::decompress(data, max_len),
+            #[allow(unreachable_patterns)]
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("compression algorithm {self:?} is not enabled"),
+            )),
+        }
+    }
+}
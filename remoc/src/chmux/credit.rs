@@ -8,7 +8,7 @@ use tokio::sync::{
     oneshot,
 };
 
-use super::{ChMuxError, SendError, mux::PortEvt};
+use super::{ChMuxError, SendError, mux::PortEvt, stats::PortCounters};
 
 // ===========================================================================
 // Credit accounting for sending data
@@ -116,6 +116,8 @@ impl CreditProvider {
 /// Requests and consumes credits for sending over a channel.
 pub(crate) struct CreditUser {
     channel: Weak<Mutex<ChannelCreditsInner>>,
+    /// Port counters for recording credit stalls.
+    counters: Arc<PortCounters>,
     /// Whether data is sent anyway, when remote endpoint closed channel gracefully.
     pub(crate) override_graceful_close: bool,
 }
@@ -126,6 +128,7 @@ impl CreditUser {
     pub async fn request(&self, req: u32, min_req: u32) -> Result<AssignedCredits, SendError> {
         debug_assert!(req > 0);
 
+        let mut stalled = false;
         loop {
             let rx_channel = {
                 let channel = match self.channel.upgrade() {
@@ -152,6 +155,11 @@ impl CreditUser {
                 }
             };
 
+            if !stalled {
+                self.counters.credit_stall();
+                stalled = true;
+            }
+
             tracing::trace!("waiting for at least {min_req} credits, but want {req} credits");
             let _ = rx_channel.await;
         }
@@ -184,11 +192,15 @@ impl CreditUser {
 
 /// Creates a pair of credit provider and credit user, initially filled
 /// with the specified number of credits.
-pub(crate) fn credit_send_pair(initial_credits: u32) -> (CreditProvider, CreditUser) {
+///
+/// Credit stalls are recorded in the specified port counters.
+pub(crate) fn credit_send_pair(
+    initial_credits: u32, counters: Arc<PortCounters>,
+) -> (CreditProvider, CreditUser) {
     let inner =
         Arc::new(Mutex::new(ChannelCreditsInner { credits: initial_credits, closed: None, notify: Vec::new() }));
 
-    let user = CreditUser { channel: Arc::downgrade(&inner), override_graceful_close: false };
+    let user = CreditUser { channel: Arc::downgrade(&inner), counters, override_graceful_close: false };
     let provider = CreditProvider(inner);
     (provider, user)
 }
@@ -0,0 +1,166 @@
+//! Handshake with the remote endpoint before ports are accepted.
+
+use bytes::Bytes;
+use futures::{Future, FutureExt, future::BoxFuture};
+use std::{error::Error, fmt, sync::Arc};
+use tokio::sync::mpsc;
+
+/// Identity of the remote endpoint established by a [handshake](Handshake).
+///
+/// Cloning is cheap.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "String", into = "String"))]
+pub struct PeerIdentity(Arc<str>);
+
+impl PeerIdentity {
+    /// Creates a peer identity.
+    pub fn new(identity: impl AsRef<str>) -> Self {
+        Self(identity.as_ref().into())
+    }
+
+    /// The identity as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for PeerIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for PeerIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for PeerIdentity {
+    fn from(identity: String) -> Self {
+        Self(identity.into())
+    }
+}
+
+impl From<PeerIdentity> for String {
+    fn from(identity: PeerIdentity) -> Self {
+        identity.0.to_string()
+    }
+}
+
+impl From<&str> for PeerIdentity {
+    fn from(identity: &str) -> Self {
+        Self(identity.into())
+    }
+}
+
+/// A handshake error.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandshakeError {
+    /// A handshake is only configured on one endpoint.
+    Mismatch,
+    /// The handshake failed, for example because authentication was refused.
+    Failed(String),
+    /// The handshake failed on the remote endpoint.
+    RemoteFailed,
+    /// The remote endpoint has finished the handshake and sends no more data.
+    Closed,
+}
+
+impl HandshakeError {
+    /// Creates a handshake failure with the specified reason.
+    pub fn failed(reason: impl fmt::Display) -> Self {
+        Self::Failed(reason.to_string())
+    }
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mismatch => write!(f, "handshake is not configured on both endpoints"),
+            Self::Failed(reason) => write!(f, "handshake failed: {reason}"),
+            Self::RemoteFailed => write!(f, "handshake failed on remote endpoint"),
+            Self::Closed => write!(f, "handshake channel closed"),
+        }
+    }
+}
+
+impl Error for HandshakeError {}
+
+impl From<HandshakeError> for std::io::Error {
+    fn from(err: HandshakeError) -> Self {
+        use std::io::ErrorKind;
+        match err {
+            HandshakeError::Closed => Self::new(ErrorKind::UnexpectedEof, err.to_string()),
+            _ => Self::new(ErrorKind::PermissionDenied, err.to_string()),
+        }
+    }
+}
+
+/// Channel for exchanging handshake messages with the remote endpoint.
+///
+/// Messages are delivered in order and without loss.
+/// Since the remote endpoint is not yet authenticated, only one incoming message is buffered;
+/// further messages are not read from the transport until the handshake receives them.
+pub struct HandshakeChannel {
+    tx: mpsc::Sender<Bytes>,
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl fmt::Debug for HandshakeChannel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HandshakeChannel").finish()
+    }
+}
+
+impl HandshakeChannel {
+    /// Creates a handshake channel together with the receiver of outgoing messages and
+    /// the sender of incoming messages.
+    pub(crate) fn new() -> (Self, mpsc::Receiver<Bytes>, mpsc::Sender<Bytes>) {
+        let (tx, out_rx) = mpsc::channel(1);
+        let (in_tx, rx) = mpsc::channel(1);
+        (Self { tx, rx }, out_rx, in_tx)
+    }
+
+    /// Sends a message to the remote endpoint.
+    pub async fn send(&mut self, msg: impl Into<Bytes>) -> Result<(), HandshakeError> {
+        self.tx.send(msg.into()).await.map_err(|_| HandshakeError::Closed)
+    }
+
+    /// Receives a message from the remote endpoint.
+    ///
+    /// Fails with [HandshakeError::Closed] when the remote endpoint has finished
+    /// its handshake and will send no more messages.
+    pub async fn recv(&mut self) -> Result<Bytes, HandshakeError> {
+        self.rx.recv().await.ok_or(HandshakeError::Closed)
+    }
+}
+
+/// A handshake performed with the remote endpoint after the multiplexer configuration
+/// has been exchanged, but before any ports are accepted.
+///
+/// It exchanges messages over the provided [channel](HandshakeChannel), for example
+/// to perform a challenge/response authentication, and returns the identity of the remote endpoint.
+/// If it fails, the connection is not established.
+/// Both endpoints must be configured with a handshake.
+///
+/// When a session is [resumed](super::ChMux::run_with_reconnect) over a new transport,
+/// the handshake is performed again and must return the same identity.
+///
+/// This is implemented for async closures taking a [HandshakeChannel].
+pub trait Handshake: Send + Sync + 'static {
+    /// Performs the handshake and returns the identity of the remote endpoint.
+    fn handshake(&self, channel: HandshakeChannel) -> BoxFuture<'static, Result<PeerIdentity, HandshakeError>>;
+}
+
+impl<F, Fut> Handshake for F
+where
+    F: Fn(HandshakeChannel) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<PeerIdentity, HandshakeError>> + Send + 'static,
+{
+    fn handshake(&self, channel: HandshakeChannel) -> BoxFuture<'static, Result<PeerIdentity, HandshakeError>> {
+        self(channel).boxed()
+    }
+}
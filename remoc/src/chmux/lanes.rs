@@ -0,0 +1,278 @@
+//! Spreading of multiplexer messages over multiple independent transport lanes.
+//!
+//! Each lane is an ordered framed transport, for example a QUIC stream.
+//! Messages concerning a port are always sent over the same lane, so that ordering
+//! is preserved per port, while a stalled lane does not block the ports of other lanes.
+//! Messages that are not specific to a port are sent over the first lane.
+//! A Goodbye message is sent over all lanes and only delivered once it has been received
+//! on every lane, ensuring that all preceding port messages have been processed.
+
+use bytes::Bytes;
+use futures::{Sink, SinkExt, Stream, StreamExt, ready};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::msg::MultiplexMsg;
+
+/// Lanes a message must be sent over.
+enum Route {
+    /// Single lane.
+    Lane(usize),
+    /// All lanes.
+    All,
+}
+
+impl Route {
+    /// Determines the route of an encoded message.
+    fn of(msg: &MultiplexMsg, lanes: usize) -> Self {
+        let port = match msg {
+            MultiplexMsg::PortOpened { client_port, .. } | MultiplexMsg::Rejected { client_port, .. } => {
+                *client_port
+            }
+            MultiplexMsg::Data { port, .. }
+            | MultiplexMsg::PortData { port, .. }
+            | MultiplexMsg::PortCredits { port, .. }
+            | MultiplexMsg::SendFinish { port }
+            | MultiplexMsg::ReceiveClose { port }
+            | MultiplexMsg::ReceiveFinish { port } => *port,
+            MultiplexMsg::Goodbye => return Self::All,
+            _ => return Self::Lane(0),
+        };
+
+        match lanes {
+            0 | 1 => Self::Lane(0),
+            lanes => Self::Lane(1 + port as usize % (lanes - 1)),
+        }
+    }
+}
+
+/// Number of frames that may wait for a lane before sending over it blocks.
+const LANE_QUEUE: usize = 16;
+
+/// Sink distributing messages over multiple lanes.
+///
+/// Each lane has its own queue of frames waiting to be sent, so that a stalled lane
+/// does not delay frames for other lanes.
+/// Sending blocks only once a frame has been queued for a lane whose queue is full,
+/// until that lane has accepted enough frames.
+pub(crate) struct LaneSink<S> {
+    /// Lanes.
+    lanes: Vec<S>,
+    /// Lane of the data packet following the last sent message.
+    data_lane: Option<usize>,
+    /// Frames waiting to be sent over each lane.
+    pending: Vec<VecDeque<Bytes>>,
+}
+
+impl<S> LaneSink<S> {
+    /// Creates a new sink distributing messages over the specified lanes.
+    ///
+    /// # Panics
+    /// Panics if no lanes are specified.
+    pub(crate) fn new(lanes: Vec<S>) -> Self {
+        assert!(!lanes.is_empty(), "at least one lane is required");
+        let pending = lanes.iter().map(|_| VecDeque::new()).collect();
+        Self { lanes, data_lane: None, pending }
+    }
+}
+
+impl<S> LaneSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+{
+    /// Sends pending frames to their lanes.
+    ///
+    /// Completes once the pending frames of all lanes have been sent.
+    fn poll_pending(&mut self, cx: &mut Context) -> Poll<Result<(), S::Error>> {
+        let mut sent = true;
+        for (lane, pending) in self.lanes.iter_mut().zip(&mut self.pending) {
+            while !pending.is_empty() {
+                if lane.poll_ready_unpin(cx)?.is_pending() {
+                    sent = false;
+                    break;
+                }
+                lane.start_send_unpin(pending.pop_front().unwrap())?;
+            }
+        }
+
+        if sent { Poll::Ready(Ok(())) } else { Poll::Pending }
+    }
+}
+
+impl<S> Sink<Bytes> for LaneSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = Pin::into_inner(self);
+        if this.poll_pending(cx)?.is_ready() || this.pending.iter().all(|pending| pending.len() <= LANE_QUEUE) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, frame: Bytes) -> Result<(), Self::Error> {
+        let this = Pin::into_inner(self);
+
+        if let Some(lane) = this.data_lane.take() {
+            this.pending[lane].push_back(frame);
+            return Ok(());
+        }
+
+        // Frames are produced by the multiplexer and thus always decodable.
+        let Ok(msg) = MultiplexMsg::read(&frame[..]) else {
+            this.pending[0].push_back(frame);
+            return Ok(());
+        };
+
+        match Route::of(&msg, this.lanes.len()) {
+            Route::Lane(lane) => {
+                if msg.has_data() {
+                    this.data_lane = Some(lane);
+                }
+                this.pending[lane].push_back(frame);
+            }
+            Route::All => {
+                for pending in &mut this.pending {
+                    pending.push_back(frame.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = Pin::into_inner(self);
+        let mut flushed = this.poll_pending(cx)?.is_ready();
+
+        // Flush lanes without pending frames, even if other lanes are stalled.
+        for (lane, pending) in this.lanes.iter_mut().zip(&this.pending) {
+            if pending.is_empty() {
+                flushed &= lane.poll_flush_unpin(cx)?.is_ready();
+            }
+        }
+
+        if flushed { Poll::Ready(Ok(())) } else { Poll::Pending }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = Pin::into_inner(self);
+        ready!(this.poll_pending(cx))?;
+        for lane in &mut this.lanes {
+            ready!(lane.poll_close_unpin(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Receiving lane.
+struct RecvLane<S> {
+    /// Framed transport.
+    stream: S,
+    /// Received message waiting for its data packet.
+    msg: Option<Bytes>,
+    /// Goodbye message has been received and lane is paused until it
+    /// has been received on all lanes.
+    goodbye: Option<Bytes>,
+    /// Transport has ended.
+    ended: bool,
+}
+
+/// Stream merging messages received over multiple lanes.
+pub(crate) struct LaneStream<S> {
+    /// Lanes.
+    lanes: Vec<RecvLane<S>>,
+    /// Lane to poll first for fairness.
+    next: usize,
+    /// Data packet to return next.
+    data: Option<Bytes>,
+}
+
+impl<S> LaneStream<S> {
+    /// Creates a new stream merging messages received over the specified lanes.
+    ///
+    /// # Panics
+    /// Panics if no lanes are specified.
+    pub(crate) fn new(lanes: Vec<S>) -> Self {
+        assert!(!lanes.is_empty(), "at least one lane is required");
+        Self {
+            lanes: lanes
+                .into_iter()
+                .map(|stream| RecvLane { stream, msg: None, goodbye: None, ended: false })
+                .collect(),
+            next: 0,
+            data: None,
+        }
+    }
+
+    /// Returns the Goodbye message, if it has been received on all lanes.
+    fn take_goodbye(&mut self) -> Option<Bytes> {
+        if !self.lanes.iter().all(|lane| lane.goodbye.is_some() || lane.ended) {
+            return None;
+        }
+
+        self.lanes.iter_mut().filter_map(|lane| lane.goodbye.take()).last()
+    }
+}
+
+impl<S, E> Stream for LaneStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = Pin::into_inner(self);
+
+        if let Some(data) = this.data.take() {
+            return Poll::Ready(Some(Ok(data)));
+        }
+
+        let n_lanes = this.lanes.len();
+        for i in 0..n_lanes {
+            let idx = (this.next + i) % n_lanes;
+            let lane = &mut this.lanes[idx];
+
+            while !lane.ended && lane.goodbye.is_none() {
+                let frame = match lane.stream.poll_next_unpin(cx) {
+                    Poll::Ready(Some(Ok(frame))) => frame,
+                    Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                    Poll::Ready(None) => {
+                        lane.ended = true;
+                        break;
+                    }
+                    Poll::Pending => break,
+                };
+
+                // Return message together with its data packet.
+                if let Some(msg) = lane.msg.take() {
+                    this.data = Some(frame);
+                    this.next = (idx + 1) % n_lanes;
+                    return Poll::Ready(Some(Ok(msg)));
+                }
+
+                match MultiplexMsg::read(&frame[..]) {
+                    Ok(msg) if msg.has_data() => lane.msg = Some(frame),
+                    Ok(MultiplexMsg::Goodbye) => lane.goodbye = Some(frame),
+                    _ => {
+                        this.next = (idx + 1) % n_lanes;
+                        return Poll::Ready(Some(Ok(frame)));
+                    }
+                }
+            }
+        }
+
+        if let Some(goodbye) = this.take_goodbye() {
+            return Poll::Ready(Some(Ok(goodbye)));
+        }
+
+        if this.lanes.iter().all(|lane| lane.ended) { Poll::Ready(None) } else { Poll::Pending }
+    }
+}
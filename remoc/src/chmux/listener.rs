@@ -3,17 +3,18 @@ use futures::{
     stream::Stream,
     task::{Context, Poll},
 };
-use std::{error::Error, fmt, pin::Pin, sync::Arc};
+use std::{error::Error, fmt, pin::Pin, sync::Arc, time::Duration};
 use tokio::sync::{Mutex, mpsc, oneshot};
 use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
-    mux::PortEvt,
+    PeerIdentity, Priority,
+    mux::{PortEvt, ShutdownReq},
     port_allocator::{PortAllocator, PortNumber},
+    quota::HelperTasks,
     receiver::Receiver,
     sender::Sender,
 };
-use crate::exec;
 
 /// An multiplexer listener error.
 #[derive(Debug, Clone)]
@@ -53,6 +54,8 @@ pub struct Request {
     remote_port: u32,
     id: u32,
     wait: bool,
+    priority: Priority,
+    peer_identity: Option<PeerIdentity>,
     allocator: PortAllocator,
     tx: mpsc::Sender<PortEvt>,
     done_tx: Option<oneshot::Sender<()>>,
@@ -64,23 +67,27 @@ impl fmt::Debug for Request {
             .field("remote_port", &self.remote_port)
             .field("id", &self.id)
             .field("wait", &self.wait)
+            .field("priority", &self.priority)
+            .field("peer_identity", &self.peer_identity)
             .finish()
     }
 }
 
 impl Request {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        remote_port: u32, id: u32, wait: bool, allocator: PortAllocator, tx: mpsc::Sender<PortEvt>,
+        remote_port: u32, id: u32, wait: bool, priority: Priority, peer_identity: Option<PeerIdentity>,
+        allocator: PortAllocator, tx: mpsc::Sender<PortEvt>, helper_tasks: &HelperTasks,
     ) -> Self {
         let (done_tx, done_rx) = oneshot::channel();
         let drop_tx = tx.clone();
-        exec::spawn(async move {
+        helper_tasks.spawn(async move {
             if done_rx.await.is_err() {
                 let _ = drop_tx.send(PortEvt::Rejected { remote_port, no_ports: false }).await;
             }
         });
 
-        Self { remote_port, id, wait, allocator, tx, done_tx: Some(done_tx) }
+        Self { remote_port, id, wait, priority, peer_identity, allocator, tx, done_tx: Some(done_tx) }
     }
 
     /// The remote port number.
@@ -101,6 +108,26 @@ impl Request {
         self.wait
     }
 
+    /// Send priority of the port when it is accepted.
+    ///
+    /// For requests received via the [listener](Listener) this is [normal priority](Priority::Normal).
+    /// For requests received over a port this is the priority of that port.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Sets the send priority of the port when it is accepted.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+
+    /// Identity of the remote endpoint established by the handshake.
+    ///
+    /// Returns `None` if no handshake was performed.
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.peer_identity.as_ref()
+    }
+
     /// Accepts the request using a newly allocated local port.
     pub async fn accept(self) -> Result<(Sender, Receiver), ListenerError> {
         let local_port = if self.wait {
@@ -121,7 +148,15 @@ impl Request {
     /// Accepts the request using the specified local port.
     pub async fn accept_from(mut self, local_port: PortNumber) -> Result<(Sender, Receiver), ListenerError> {
         let (port_tx, port_rx) = oneshot::channel();
-        let _ = self.tx.send(PortEvt::Accepted { local_port, remote_port: self.remote_port, port_tx }).await;
+        let _ = self
+            .tx
+            .send(PortEvt::Accepted {
+                local_port,
+                remote_port: self.remote_port,
+                priority: self.priority,
+                port_tx,
+            })
+            .await;
         let _ = self.done_tx.take().unwrap().send(());
 
         port_rx.await.map_err(|_| ListenerError::MultiplexerError)
@@ -156,22 +191,27 @@ pub struct Listener {
     wait_rx: mpsc::Receiver<RemoteConnectMsg>,
     no_wait_rx: mpsc::Receiver<RemoteConnectMsg>,
     port_allocator: PortAllocator,
-    terminate_tx: mpsc::UnboundedSender<()>,
+    terminate_tx: mpsc::UnboundedSender<ShutdownReq>,
+    peer_identity: Option<PeerIdentity>,
     closed: bool,
 }
 
 impl fmt::Debug for Listener {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("Listener").field("port_allocator", &self.port_allocator).finish()
+        f.debug_struct("Listener")
+            .field("port_allocator", &self.port_allocator)
+            .field("peer_identity", &self.peer_identity)
+            .finish()
     }
 }
 
 impl Listener {
     pub(crate) fn new(
         wait_rx: mpsc::Receiver<RemoteConnectMsg>, no_wait_rx: mpsc::Receiver<RemoteConnectMsg>,
-        port_allocator: PortAllocator, terminate_tx: mpsc::UnboundedSender<()>,
+        port_allocator: PortAllocator, terminate_tx: mpsc::UnboundedSender<ShutdownReq>,
+        peer_identity: Option<PeerIdentity>,
     ) -> Self {
-        Self { wait_rx, no_wait_rx, port_allocator, terminate_tx, closed: false }
+        Self { wait_rx, no_wait_rx, port_allocator, terminate_tx, peer_identity, closed: false }
     }
 
     /// Obtains the port allocator.
@@ -179,6 +219,13 @@ impl Listener {
         self.port_allocator.clone()
     }
 
+    /// Identity of the remote endpoint established by the handshake.
+    ///
+    /// Returns `None` if no handshake was performed.
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.peer_identity.as_ref()
+    }
+
     /// Accept a connection returning the sender and receiver for the opened port.
     ///
     /// Returns [None] when the client of the remote endpoint has been dropped and
@@ -252,7 +299,22 @@ impl Listener {
 
     /// Terminates the multiplexer, forcibly closing all open ports.
     pub fn terminate(&self) {
-        let _ = self.terminate_tx.send(());
+        let _ = self.terminate_tx.send(ShutdownReq::Terminate);
+    }
+
+    /// Gracefully shuts down the multiplexer.
+    ///
+    /// The multiplexer stops accepting new ports, both from the remote endpoint
+    /// and from local clients, and notifies the remote endpoint that no more connect
+    /// requests will be handled.
+    /// Ports that are already open, including ports sent over them, continue to work.
+    /// Once all ports have been closed the multiplexer terminates and
+    /// [ChMux::run](super::ChMux::run) returns successfully.
+    ///
+    /// If ports are still open after `timeout` has elapsed, the multiplexer is
+    /// [terminated](Self::terminate) forcibly.
+    pub fn drain(&self, timeout: Duration) {
+        let _ = self.terminate_tx.send(ShutdownReq::Drain(timeout));
     }
 }
 
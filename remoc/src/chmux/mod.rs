@@ -18,24 +18,42 @@ use std::{error::Error, fmt};
 mod any_storage;
 mod cfg;
 mod client;
+mod compress;
 mod credit;
 mod forward;
+mod handshake;
+#[cfg(all(feature = "quic", not(target_family = "wasm")))]
+mod lanes;
 mod listener;
 mod msg;
 mod mux;
+mod peer_info;
+mod ping;
 mod port_allocator;
+mod priority;
+mod quota;
 mod receiver;
 mod sender;
+mod session;
+mod stats;
 
 pub use any_storage::{AnyBox, AnyEntry, AnyStorage};
 pub use cfg::{Cfg, PortsExhausted};
-pub use client::{Client, Connect, ConnectError};
+pub use client::{Client, Connect, ConnectError, PingError};
+pub use compress::Compression;
 pub use forward::ForwardError;
+pub use handshake::{Handshake, HandshakeChannel, HandshakeError, PeerIdentity};
+#[cfg(all(feature = "quic", not(target_family = "wasm")))]
+pub(crate) use lanes::{LaneSink, LaneStream};
 pub use listener::{Listener, ListenerError, ListenerStream, Request};
 pub use mux::ChMux;
+pub use peer_info::PeerInfo;
 pub use port_allocator::{PortAllocator, PortNumber, PortReq};
+pub use priority::{ChannelPriorities, Priority};
+pub use quota::{Quota, QuotaPolicy};
 pub use receiver::{DataBuf, Received, Receiver, ReceiverStream, RecvAnyError, RecvChunkError, RecvError};
 pub use sender::{ChunkSender, Closed, SendError, Sender, SenderSink, TrySendError};
+pub use stats::{PortStats, Stats};
 
 /// Channel multiplexer protocol version.
 pub const PROTOCOL_VERSION: u8 = 3;
@@ -59,6 +77,20 @@ pub enum ChMuxError<SinkError, StreamError> {
     Timeout,
     /// A multiplex protocol error occurred.
     Protocol(String),
+    /// The session could not be resumed after the transport failed.
+    SessionLost,
+    /// The handshake with the remote endpoint failed.
+    Handshake(HandshakeError),
+    /// The remote endpoint exceeded a resource quota and the
+    /// [quota policy](Cfg::quota_policy) is to terminate the connection.
+    QuotaExceeded(Quota),
+}
+
+impl<SinkError, StreamError> ChMuxError<SinkError, StreamError> {
+    /// True, if the error is caused by a failure of the underlying transport.
+    pub(crate) fn is_transport_failure(&self) -> bool {
+        matches!(self, Self::SinkError(_) | Self::StreamError(_) | Self::StreamClosed | Self::Timeout)
+    }
 }
 
 impl<SinkError, StreamError> fmt::Display for ChMuxError<SinkError, StreamError>
@@ -74,6 +106,9 @@ where
             Self::Reset => write!(f, "connection reset"),
             Self::Timeout => write!(f, "connection timeout"),
             Self::Protocol(err) => write!(f, "protocol error: {err}"),
+            Self::SessionLost => write!(f, "session lost"),
+            Self::Handshake(err) => write!(f, "{err}"),
+            Self::QuotaExceeded(quota) => write!(f, "quota for {quota} exceeded by remote endpoint"),
         }
     }
 }
@@ -95,6 +130,9 @@ impl From<ChMuxError<std::io::Error, std::io::Error>> for std::io::Error {
             ChMuxError::Reset => std::io::Error::new(ErrorKind::ConnectionReset, err.to_string()),
             ChMuxError::Timeout => std::io::Error::new(ErrorKind::TimedOut, err.to_string()),
             ChMuxError::Protocol(_) => std::io::Error::new(ErrorKind::InvalidData, err.to_string()),
+            ChMuxError::SessionLost => std::io::Error::new(ErrorKind::ConnectionReset, err.to_string()),
+            ChMuxError::Handshake(err) => err.into(),
+            ChMuxError::QuotaExceeded(_) => std::io::Error::new(ErrorKind::QuotaExceeded, err.to_string()),
         }
     }
 }
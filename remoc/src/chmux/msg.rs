@@ -4,7 +4,7 @@ use std::{
     time::Duration,
 };
 
-use super::{Cfg, ChMuxError};
+use super::{Cfg, ChMuxError, Compression, session::SessionHello};
 
 fn invalid_data(msg: &str) -> io::Error {
     io::Error::new(io::ErrorKind::InvalidData, format!("invalid value for {msg} received"))
@@ -27,7 +27,14 @@ pub enum MultiplexMsg {
         cfg: ExchangedCfg,
     },
     /// Ping to keep connection alive when there is no data to send.
-    Ping,
+    ///
+    /// Also used to measure the round-trip time.
+    Ping {
+        /// Nonce that is returned in the answering pong.
+        ///
+        /// Only present if the remote endpoint announced that it answers pings.
+        nonce: Option<u64>,
+    },
     /// Open connection on specified client port and assign a server port.
     OpenPort {
         /// Requesting client port.
@@ -67,6 +74,8 @@ pub enum MultiplexMsg {
         first: bool,
         /// Last chunk of data.
         last: bool,
+        /// Compression algorithm of data.
+        compression: Option<Compression>,
     },
     /// Ports sent over a port.
     PortData {
@@ -116,6 +125,28 @@ pub enum MultiplexMsg {
     ListenerFinish,
     /// Terminate connection.
     Goodbye,
+    /// Acknowledges received messages for session resumption.
+    Ack {
+        /// Number of messages received since session start.
+        received: u64,
+    },
+    /// Answer to a ping.
+    ///
+    /// Only sent if the remote endpoint announced support for it.
+    Pong {
+        /// Nonce of the answered ping.
+        nonce: u64,
+    },
+    /// Handshake data.
+    ///
+    /// This is followed by one data packet.
+    HandshakeData,
+    /// Sending side has finished its handshake.
+    HandshakeDone {
+        // Flags u8.
+        /// Handshake succeeded.
+        success: bool,
+    },
 }
 
 pub const MSG_RESET: u8 = 1;
@@ -133,20 +164,43 @@ pub const MSG_RECEIVE_FINISH: u8 = 12;
 pub const MSG_CLIENT_FINISH: u8 = 13;
 pub const MSG_LISTENER_FINISH: u8 = 14;
 pub const MSG_GOODBYE: u8 = 15;
+pub const MSG_ACK: u8 = 16;
+pub const MSG_PONG: u8 = 17;
+pub const MSG_HANDSHAKE_DATA: u8 = 18;
+pub const MSG_HANDSHAKE_DONE: u8 = 19;
 
 pub const MSG_OPEN_PORT_FLAG_WAIT: u8 = 0b0000_0001;
 pub const MSG_OPEN_PORT_FLAG_ID: u8 = 0b0000_0010;
 
 pub const MSG_REJECTED_FLAG_NO_PORTS: u8 = 0b0000_0001;
 
+pub const MSG_HANDSHAKE_DONE_FLAG_SUCCESS: u8 = 0b0000_0001;
+
 pub const MSG_DATA_FLAG_FIRST: u8 = 0b0000_0001;
 pub const MSG_DATA_FLAG_LAST: u8 = 0b0000_0010;
+pub const MSG_DATA_COMPRESSION_MASK: u8 = 0b0000_1100;
+pub const MSG_DATA_COMPRESSION_SHIFT: u8 = 2;
 
 pub const MSG_PORT_DATA_FLAG_FIRST: u8 = 0b0000_0001;
 pub const MSG_PORT_DATA_FLAG_LAST: u8 = 0b0000_0010;
 pub const MSG_PORT_DATA_FLAG_WAIT: u8 = 0b0000_0100;
 pub const MSG_PORT_DATA_FLAG_IDS: u8 = 0b0000_1000;
 
+/// Hello extension: session resumption.
+pub const HELLO_EXT_SESSION: u8 = 1;
+
+/// Hello extension: pings carry a nonce and are answered by pongs.
+pub const HELLO_EXT_PONG: u8 = 2;
+
+/// Hello extension: supported compression algorithms.
+pub const HELLO_EXT_COMPRESSION: u8 = 3;
+
+/// Hello extension: a handshake is performed after the Hello exchange.
+pub const HELLO_EXT_HANDSHAKE: u8 = 4;
+
+/// Hello extension: span contexts are transmitted along with values.
+pub const HELLO_EXT_TRACE_CONTEXT: u8 = 5;
+
 /// Maximum message length.
 ///
 /// Currently this is 16 to reserve space for further use.
@@ -165,8 +219,11 @@ impl MultiplexMsg {
                 writer.write_u8(*version)?;
                 cfg.write(&mut writer)?;
             }
-            MultiplexMsg::Ping => {
+            MultiplexMsg::Ping { nonce } => {
                 writer.write_u8(MSG_PING)?;
+                if let Some(nonce) = nonce {
+                    writer.write_u64::<LE>(*nonce)?;
+                }
             }
             MultiplexMsg::OpenPort { client_port, wait, id } => {
                 writer.write_u8(MSG_OPEN_PORT)?;
@@ -193,7 +250,7 @@ impl MultiplexMsg {
                 writer.write_u32::<LE>(*client_port)?;
                 writer.write_u8(if *no_ports { MSG_REJECTED_FLAG_NO_PORTS } else { 0 })?;
             }
-            MultiplexMsg::Data { port, first, last } => {
+            MultiplexMsg::Data { port, first, last, compression } => {
                 writer.write_u8(MSG_DATA)?;
                 writer.write_u32::<LE>(*port)?;
                 let mut flags = 0;
@@ -203,6 +260,9 @@ impl MultiplexMsg {
                 if *last {
                     flags |= MSG_DATA_FLAG_LAST;
                 }
+                if let Some(compression) = compression {
+                    flags |= compression.id() << MSG_DATA_COMPRESSION_SHIFT;
+                }
                 writer.write_u8(flags)?;
             }
             MultiplexMsg::PortData { port, first, last, wait, ports, ids } => {
@@ -263,6 +323,21 @@ impl MultiplexMsg {
             MultiplexMsg::Goodbye => {
                 writer.write_u8(MSG_GOODBYE)?;
             }
+            MultiplexMsg::Ack { received } => {
+                writer.write_u8(MSG_ACK)?;
+                writer.write_u64::<LE>(*received)?;
+            }
+            MultiplexMsg::Pong { nonce } => {
+                writer.write_u8(MSG_PONG)?;
+                writer.write_u64::<LE>(*nonce)?;
+            }
+            MultiplexMsg::HandshakeData => {
+                writer.write_u8(MSG_HANDSHAKE_DATA)?;
+            }
+            MultiplexMsg::HandshakeDone { success } => {
+                writer.write_u8(MSG_HANDSHAKE_DONE)?;
+                writer.write_u8(if *success { MSG_HANDSHAKE_DONE_FLAG_SUCCESS } else { 0 })?;
+            }
         }
         Ok(())
     }
@@ -278,7 +353,13 @@ impl MultiplexMsg {
                 }
                 Self::Hello { version: reader.read_u8()?, cfg: ExchangedCfg::read(&mut reader)? }
             }
-            MSG_PING => Self::Ping,
+            MSG_PING => Self::Ping {
+                nonce: match reader.read_u64::<LE>() {
+                    Ok(nonce) => Some(nonce),
+                    Err(err) if err.kind() == ErrorKind::UnexpectedEof => None,
+                    Err(err) => return Err(err),
+                },
+            },
             MSG_OPEN_PORT => {
                 let client_port = reader.read_u32::<LE>()?;
                 let flags = reader.read_u8()?;
@@ -303,6 +384,10 @@ impl MultiplexMsg {
                     port,
                     first: flags & MSG_DATA_FLAG_FIRST != 0,
                     last: flags & MSG_DATA_FLAG_LAST != 0,
+                    compression: match (flags & MSG_DATA_COMPRESSION_MASK) >> MSG_DATA_COMPRESSION_SHIFT {
+                        0 => None,
+                        id => Some(Compression::from_id(id).ok_or_else(|| invalid_data("compression"))?),
+                    },
                 }
             }
             MSG_PORT_DATA => {
@@ -334,11 +419,36 @@ impl MultiplexMsg {
             MSG_CLIENT_FINISH => Self::ClientFinish,
             MSG_LISTENER_FINISH => Self::ListenerFinish,
             MSG_GOODBYE => Self::Goodbye,
+            MSG_ACK => Self::Ack { received: reader.read_u64::<LE>()? },
+            MSG_PONG => Self::Pong { nonce: reader.read_u64::<LE>()? },
+            MSG_HANDSHAKE_DATA => Self::HandshakeData,
+            MSG_HANDSHAKE_DONE => {
+                Self::HandshakeDone { success: reader.read_u8()? & MSG_HANDSHAKE_DONE_FLAG_SUCCESS != 0 }
+            }
             _ => return Err(invalid_data("invalid message id")),
         };
         Ok(msg)
     }
 
+    /// Whether the message is followed by one data packet.
+    pub(crate) fn has_data(&self) -> bool {
+        matches!(self, Self::Data { .. } | Self::HandshakeData)
+    }
+
+    /// Whether the message must be resent when resuming a session.
+    pub(crate) fn is_session_tracked(&self) -> bool {
+        !matches!(
+            self,
+            Self::Reset
+                | Self::Hello { .. }
+                | Self::Ping { .. }
+                | Self::Ack { .. }
+                | Self::Pong { .. }
+                | Self::HandshakeData
+                | Self::HandshakeDone { .. }
+        )
+    }
+
     pub(crate) fn to_vec(&self) -> Vec<u8> {
         let mut data = Vec::with_capacity(MAX_MSG_LENGTH);
         self.write(&mut data).expect("message serialization failed");
@@ -363,6 +473,38 @@ pub struct ExchangedCfg {
     pub port_receive_buffer: u32,
     /// Length of connection request queue.
     pub connect_queue: u16,
+    /// Session resumption information.
+    pub session: Option<SessionHello>,
+    /// Pings carry a nonce and are answered by pongs.
+    pub pong: bool,
+    /// Bit mask of supported compression algorithms.
+    pub compression: u8,
+    /// A handshake is performed after the Hello exchange.
+    pub handshake: bool,
+    /// Span contexts are transmitted along with values.
+    pub trace_context: bool,
+}
+
+/// Reads the next Hello extension.
+///
+/// Returns `None` when no more extensions are present.
+fn read_ext(mut reader: impl io::Read) -> Result<Option<(u8, Vec<u8>)>, io::Error> {
+    let tag = match reader.read_u8() {
+        Ok(tag) => tag,
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let len = reader.read_u16::<LE>()?;
+    let mut data = vec![0; len.into()];
+    reader.read_exact(&mut data)?;
+    Ok(Some((tag, data)))
+}
+
+/// Writes a Hello extension.
+fn write_ext(mut writer: impl io::Write, tag: u8, data: &[u8]) -> Result<(), io::Error> {
+    writer.write_u8(tag)?;
+    writer.write_u16::<LE>(data.len().try_into().map_err(|_| invalid_data("extension length"))?)?;
+    writer.write_all(data)
 }
 
 impl ExchangedCfg {
@@ -373,11 +515,31 @@ impl ExchangedCfg {
         writer.write_u32::<LE>(self.chunk_size)?;
         writer.write_u32::<LE>(self.port_receive_buffer)?;
         writer.write_u16::<LE>(self.connect_queue)?;
+
+        // Extensions are appended and ignored by endpoints that do not know them.
+        if let Some(session) = &self.session {
+            let mut data = Vec::new();
+            session.write(&mut data)?;
+            write_ext(&mut writer, HELLO_EXT_SESSION, &data)?;
+        }
+        if self.pong {
+            write_ext(&mut writer, HELLO_EXT_PONG, &[])?;
+        }
+        if self.compression != 0 {
+            write_ext(&mut writer, HELLO_EXT_COMPRESSION, &[self.compression])?;
+        }
+        if self.handshake {
+            write_ext(&mut writer, HELLO_EXT_HANDSHAKE, &[])?;
+        }
+        if self.trace_context {
+            write_ext(&mut writer, HELLO_EXT_TRACE_CONTEXT, &[])?;
+        }
+
         Ok(())
     }
 
     pub(crate) fn read(mut reader: impl io::Read) -> Result<Self, io::Error> {
-        let this = Self {
+        let mut this = Self {
             connection_timeout: match reader.read_u64::<LE>()? {
                 0 => None,
                 millis => Some(Duration::from_millis(millis)),
@@ -394,7 +556,24 @@ impl ExchangedCfg {
                 cq if cq >= 1 => cq,
                 _ => return Err(invalid_data("connect_queue must not be zero")),
             },
+            session: None,
+            pong: false,
+            compression: 0,
+            handshake: false,
+            trace_context: false,
         };
+
+        while let Some((tag, data)) = read_ext(&mut reader)? {
+            match tag {
+                HELLO_EXT_SESSION => this.session = Some(SessionHello::read(data.as_slice())?),
+                HELLO_EXT_PONG => this.pong = true,
+                HELLO_EXT_COMPRESSION => this.compression = data.first().copied().unwrap_or_default(),
+                HELLO_EXT_HANDSHAKE => this.handshake = true,
+                HELLO_EXT_TRACE_CONTEXT => this.trace_context = true,
+                _ => (),
+            }
+        }
+
         Ok(this)
     }
 }
@@ -406,6 +585,11 @@ impl From<&Cfg> for ExchangedCfg {
             chunk_size: cfg.chunk_size,
             port_receive_buffer: cfg.receive_buffer,
             connect_queue: cfg.connect_queue,
+            session: None,
+            pong: true,
+            compression: Compression::enabled_mask(),
+            handshake: false,
+            trace_context: cfg!(feature = "trace-context"),
         }
     }
 }
@@ -5,7 +5,7 @@ use futures::{
     stream::{Stream, StreamExt},
 };
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     convert::TryFrom,
     error::Error,
     fmt,
@@ -24,18 +24,26 @@ use tokio::{
     try_join,
 };
 use tokio_util::sync::ReusableBoxFuture;
+use uuid::Uuid;
 
 use super::{
-    AnyStorage, Cfg, ChMuxError, PROTOCOL_VERSION, PROTOCOL_VERSION_PORT_ID, PortReq,
+    AnyStorage, Cfg, ChMuxError, Compression, PROTOCOL_VERSION, PROTOCOL_VERSION_PORT_ID, PortReq, Priority,
+    Quota, QuotaPolicy,
     client::{Client, ConnectRequest, ConnectResponse},
     credit::{ChannelCreditMonitor, CreditProvider, credit_monitor_pair, credit_send_pair},
+    handshake::{Handshake, HandshakeChannel, HandshakeError, PeerIdentity},
     listener::{Listener, RemoteConnectMsg, Request},
     msg::{ExchangedCfg, MultiplexMsg},
+    peer_info::PeerInfo,
+    ping::{PingRequest, Pinger},
     port_allocator::{PortAllocator, PortNumber},
+    quota::HelperTasks,
     receiver::{PortReceiveMsg, ReceivedData, ReceivedPortRequests, Receiver},
     sender::Sender,
+    session::{Frames, Session, SessionHello},
+    stats::{PortCounters, Stats},
 };
-use crate::exec::time::{sleep, timeout};
+use crate::exec::time::{Instant, sleep, timeout};
 
 /// Multiplexer protocol error.
 fn protocol_err<SinkError, StreamError>(msg: impl AsRef<str>) -> super::ChMuxError<SinkError, StreamError> {
@@ -50,11 +58,17 @@ enum PortState {
     Connecting {
         /// Channel for providing the response to the local requester.
         response_tx: oneshot::Sender<ConnectResponse>,
+        /// Send priority.
+        priority: Priority,
     },
     /// Port is connected.
     Connected {
         /// Remote port.
         remote_port: u32,
+        /// Send priority.
+        priority: Priority,
+        /// Traffic counters.
+        counters: Arc<PortCounters>,
         /// Credit provider for sending.
         /// Initially present, None when Hangup message has been received.
         sender_credit_provider: CreditProvider,
@@ -89,6 +103,8 @@ pub(crate) enum PortEvt {
         local_port: PortNumber,
         /// Remote port.
         remote_port: u32,
+        /// Send priority.
+        priority: Priority,
         /// Reply with port sender and receiver.
         port_tx: oneshot::Sender<(Sender, Receiver)>,
     },
@@ -101,6 +117,8 @@ pub(crate) enum PortEvt {
     },
     /// Send message with content.
     SendData {
+        /// Local port that sends data.
+        local_port: u32,
         /// Remote port that will receive data.
         remote_port: u32,
         /// Data to send.
@@ -160,6 +178,24 @@ enum GlobalEvt {
     Port(PortEvt),
     /// Send Goodbye message.
     SendGoodbye,
+    /// Terminate forcibly once all queued messages have been sent.
+    Terminate,
+    /// Reject a port request from the remote endpoint that exceeded a quota.
+    QuotaReject,
+    /// Start draining the connection.
+    Drain {
+        /// Time to wait for open ports to finish.
+        timeout: Duration,
+    },
+}
+
+/// Request to shut down the multiplexer.
+#[derive(Debug)]
+pub(crate) enum ShutdownReq {
+    /// Terminate forcibly.
+    Terminate,
+    /// Stop accepting new ports and terminate once all open ports have finished.
+    Drain(Duration),
 }
 
 /// Message with optionally associated data.
@@ -174,15 +210,43 @@ struct TransportMsg {
 impl TransportMsg {
     /// Message only.
     fn new(msg: MultiplexMsg) -> Self {
-        assert!(!matches!(&msg, &MultiplexMsg::Data { .. }), "MultiplexMsg::Data with missing data");
+        assert!(!msg.has_data(), "MultiplexMsg with missing data");
         Self { msg, data: None }
     }
 
     /// Message with data.
     fn with_data(msg: MultiplexMsg, data: Bytes) -> Self {
-        assert!(matches!(&msg, &MultiplexMsg::Data { .. }), "MultiplexMsg with unexpected data");
+        assert!(msg.has_data(), "MultiplexMsg with unexpected data");
         Self { msg, data: Some(data) }
     }
+
+    /// Compresses the data of a data message, if it is at least `threshold` bytes long
+    /// and compression reduces its size.
+    fn compress(mut self, compression: Compression, threshold: u32) -> Self {
+        if let (MultiplexMsg::Data { compression: msg_compression @ None, .. }, Some(data)) =
+            (&mut self.msg, &mut self.data)
+            && data.len() >= threshold as usize
+        {
+            let compressed = compression.compress(data);
+            if compressed.len() < data.len() {
+                *msg_compression = Some(compression);
+                *data = compressed.into();
+            }
+        }
+        self
+    }
+
+    /// Decompresses the data of a compressed data message.
+    ///
+    /// Fails if the decompressed data would exceed `max_len` bytes.
+    fn decompress(mut self, max_len: usize) -> Result<Self, std::io::Error> {
+        if let (MultiplexMsg::Data { compression: msg_compression @ Some(_), .. }, Some(data)) =
+            (&mut self.msg, &mut self.data)
+        {
+            *data = msg_compression.take().unwrap().decompress(data, max_len)?.into();
+        }
+        Ok(self)
+    }
 }
 
 /// Channel multiplexer.
@@ -196,6 +260,8 @@ pub struct ChMux<TransportSink, TransportStream> {
     remote_protocol_version: u8,
     /// Channel for connection requests from local client.
     connect_rx: Option<mpsc::UnboundedReceiver<ConnectRequest>>,
+    /// Channel for ping requests from local client.
+    ping_rx: Option<mpsc::UnboundedReceiver<PingRequest>>,
     /// Channels for connection requests from remote endpoint with wait set and not set.
     listen_tx: Option<(mpsc::Sender<RemoteConnectMsg>, mpsc::Sender<RemoteConnectMsg>)>,
     /// Port allocator.
@@ -204,18 +270,33 @@ pub struct ChMux<TransportSink, TransportStream> {
     ports: HashMap<PortNumber, PortState>,
     /// Outstanding requests by the remote endpoint for connecting ports.
     outstanding_remote_port_requests: HashSet<u32>,
+    /// Requests by the remote endpoint for connecting ports that must be rejected
+    /// because they exceeded a quota.
+    quota_rejects: VecDeque<u32>,
+    /// Helper tasks spawned on behalf of the remote endpoint.
+    helper_tasks: HelperTasks,
     /// Sender from channels to event loop.
     channel_tx: mpsc::Sender<PortEvt>,
     /// Channel receiver of event loop.
     channel_rx: Option<mpsc::Receiver<PortEvt>>,
-    /// Force termination request.
-    terminate_rx: Option<mpsc::UnboundedReceiver<()>>,
+    /// Senders from port senders to event loop, indexed by priority.
+    sender_tx: Vec<mpsc::Sender<PortEvt>>,
+    /// Port sender receivers of event loop, indexed by priority.
+    sender_rx: Option<Vec<mpsc::Receiver<PortEvt>>>,
+    /// Termination or drain request.
+    terminate_rx: Option<mpsc::UnboundedReceiver<ShutdownReq>>,
     /// All user clients have been dropped.
     all_clients_dropped: bool,
     /// Remote client has been dropped.
     remote_client_dropped: bool,
     /// Remote listener has been dropped.
     remote_listener_dropped: Arc<AtomicBool>,
+    /// Connection is draining, i.e. no new ports are accepted.
+    draining: bool,
+    /// Time at which a draining connection is terminated forcibly.
+    drain_deadline: Option<Instant>,
+    /// Forcible termination has been requested locally.
+    terminate_requested: bool,
     /// Goodbye message has been sent.
     goodbye_sent: bool,
     /// Goodbye message has been received.
@@ -224,6 +305,14 @@ pub struct ChMux<TransportSink, TransportStream> {
     transport_sink: Option<TransportSink>,
     /// Transport receiver.
     transport_stream: Option<TransportStream>,
+    /// Resumable session.
+    session: Option<Session>,
+    /// Handshake, repeated when resuming the session.
+    handshake: Option<Arc<dyn Handshake>>,
+    /// Information about the remote endpoint.
+    peer_info: PeerInfo,
+    /// Connection statistics.
+    stats: Stats,
     /// Storage.
     storage: AnyStorage,
 }
@@ -235,6 +324,8 @@ impl<TransportSink, TransportStream> fmt::Debug for ChMux<TransportSink, Transpo
             .field("remote_cfg", &self.remote_cfg)
             .field("local_protocol_version", &PROTOCOL_VERSION)
             .field("remote_protocol_version", &self.remote_protocol_version)
+            .field("session_id", &self.session.as_ref().map(|session| session.id()))
+            .field("peer_info", &self.peer_info)
             .finish()
     }
 }
@@ -255,70 +346,193 @@ where
     /// Panics if specified configuration does not obey limits documented in [Cfg].
     #[tracing::instrument(level = "trace", skip_all, fields(cfg))]
     pub async fn new(
+        cfg: Cfg, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<(Self, Client, Listener), ChMuxError<TransportSinkError, TransportStreamError>> {
+        Self::new_impl(cfg, transport_sink, transport_stream, None).await
+    }
+
+    /// Creates a new multiplexer that performs the specified handshake with the remote endpoint
+    /// before any ports are accepted.
+    ///
+    /// The remote endpoint must also be configured with a handshake.
+    /// The identity of the remote endpoint established by the handshake is available
+    /// from the multiplexer, listener and received connection requests.
+    ///
+    /// After creation use the `run` method of the multiplexer to launch the dispatch task.
+    ///
+    /// # Panics
+    /// Panics if specified configuration does not obey limits documented in [Cfg].
+    #[tracing::instrument(level = "trace", skip_all, fields(cfg))]
+    pub async fn new_with_handshake(
+        cfg: Cfg, transport_sink: TransportSink, transport_stream: TransportStream, handshake: impl Handshake,
+    ) -> Result<(Self, Client, Listener), ChMuxError<TransportSinkError, TransportStreamError>> {
+        Self::new_impl(cfg, transport_sink, transport_stream, Some(Arc::new(handshake))).await
+    }
+
+    pub(crate) async fn new_impl(
         cfg: Cfg, mut transport_sink: TransportSink, mut transport_stream: TransportStream,
+        handshake: Option<Arc<dyn Handshake>>,
     ) -> Result<(Self, Client, Listener), ChMuxError<TransportSinkError, TransportStreamError>> {
         // Check configuration.
         cfg.check();
 
         // Say hello to remote endpoint and exchange configurations.
-        let fut = Self::exchange_hello(&cfg, &mut transport_sink, &mut transport_stream);
+        let local_session = cfg.session_resumption.then(SessionHello::new);
+        let fut = Self::exchange_hello(
+            &cfg,
+            local_session.clone(),
+            handshake.is_some(),
+            &mut transport_sink,
+            &mut transport_stream,
+        );
         let (remote_protocol_version, remote_cfg) = match cfg.connection_timeout {
             Some(dur) => timeout(dur, fut).await.map_err(|_| ChMuxError::Timeout)??,
             None => fut.await?,
         };
 
+        // Perform handshake before any ports are accepted.
+        let peer_identity =
+            Self::handshake(&cfg, handshake.as_deref(), &remote_cfg, &mut transport_sink, &mut transport_stream)
+                .await?;
+
+        // Establish session if supported by both endpoints.
+        let session = match (local_session, &remote_cfg.session) {
+            (Some(_), Some(SessionHello { resume: true, .. })) => return Err(ChMuxError::SessionLost),
+            (Some(local), Some(remote)) => Some(Session::new(local.id, remote.id, cfg.session_buffer)),
+            (Some(_), None) => {
+                tracing::debug!("remote endpoint does not support session resumption");
+                None
+            }
+            (None, _) => None,
+        };
+
         // Create channels.
         let (channel_tx, channel_rx) = mpsc::channel(cfg.shared_send_queue);
+        let (sender_tx, sender_rx) = Priority::ALL.iter().map(|_| mpsc::channel(cfg.shared_send_queue)).unzip();
         let (listen_wait_tx, listen_wait_rx) = mpsc::channel(usize::from(cfg.connect_queue) + 1);
         let (listen_no_wait_tx, listen_no_wait_rx) = mpsc::channel(usize::from(cfg.connect_queue) + 1);
         let (connect_tx, connect_rx) = mpsc::unbounded_channel();
+        let (ping_tx, ping_rx) = mpsc::unbounded_channel();
         let (terminate_tx, terminate_rx) = mpsc::unbounded_channel();
 
         // Create user objects.
         let port_allocator = PortAllocator::new(cfg.max_ports);
         let remote_listener_dropped = Arc::new(AtomicBool::new(false));
+        let helper_tasks = HelperTasks::default();
         let multiplexer = ChMux {
             remote_protocol_version,
             local_cfg: cfg,
             remote_cfg: remote_cfg.clone(),
             connect_rx: Some(connect_rx),
+            ping_rx: Some(ping_rx),
             listen_tx: Some((listen_wait_tx, listen_no_wait_tx)),
             port_allocator: port_allocator.clone(),
             ports: HashMap::new(),
             outstanding_remote_port_requests: HashSet::new(),
+            quota_rejects: VecDeque::new(),
+            helper_tasks: helper_tasks.clone(),
             channel_tx,
             channel_rx: Some(channel_rx),
+            sender_tx,
+            sender_rx: Some(sender_rx),
             terminate_rx: Some(terminate_rx),
             remote_client_dropped: false,
             remote_listener_dropped: remote_listener_dropped.clone(),
             all_clients_dropped: false,
+            draining: false,
+            drain_deadline: None,
+            terminate_requested: false,
             goodbye_sent: false,
             goodbye_received: false,
             transport_sink: Some(transport_sink),
             transport_stream: Some(transport_stream),
+            session,
+            handshake,
+            peer_info: PeerInfo::new(
+                peer_identity.clone(),
+                cfg!(feature = "trace-context") && remote_cfg.trace_context,
+            ),
+            stats: Stats::new(helper_tasks),
             storage: AnyStorage::new(),
         };
 
         let client = Client::new(
             connect_tx,
+            remote_cfg.pong.then_some(ping_tx),
+            multiplexer.local_cfg.ping_timeout,
             remote_cfg.connect_queue,
             port_allocator.clone(),
             remote_listener_dropped,
             terminate_tx.clone(),
         );
-        let listener = Listener::new(listen_wait_rx, listen_no_wait_rx, port_allocator, terminate_tx);
+        let listener =
+            Listener::new(listen_wait_rx, listen_no_wait_rx, port_allocator, terminate_tx, peer_identity);
 
         Ok((multiplexer, client, listener))
     }
 
+    /// Id of the resumable session.
+    ///
+    /// The id is the same on both endpoints.
+    /// Returns `None` if [session resumption](Cfg::session_resumption) is not enabled
+    /// on both endpoints.
+    pub fn session_id(&self) -> Option<Uuid> {
+        self.session.as_ref().map(|session| session.id())
+    }
+
+    /// Identity of the remote endpoint established by the handshake.
+    ///
+    /// Returns `None` if no handshake was performed.
+    /// See [new_with_handshake](Self::new_with_handshake).
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.peer_info.identity()
+    }
+
+    /// Information about the remote endpoint of this connection.
+    ///
+    /// The returned handle can be cloned and is shared with all [receivers](Receiver)
+    /// of this multiplexer.
+    /// Set the address and metadata of the remote endpoint on it before running the
+    /// multiplexer.
+    pub fn peer_info(&self) -> PeerInfo {
+        self.peer_info.clone()
+    }
+
+    /// Statistics of this connection.
+    ///
+    /// The returned handle can be cloned and stays valid after the multiplexer
+    /// has terminated.
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
     /// Feed transport message to sink and log it.
+    ///
+    /// If a session is specified, the message is recorded for possible resending.
     #[tracing::instrument(level = "trace", skip_all, fields(msg=?msg.msg, data=?msg.data))]
     async fn feed_msg(
-        msg: TransportMsg, sink: &mut TransportSink,
+        msg: TransportMsg, sink: &mut TransportSink, session: Option<&Session>,
     ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
-        sink.feed(msg.msg.to_vec().into()).await.map_err(ChMuxError::SinkError)?;
+        let frames = (msg.msg.to_vec().into(), msg.data);
 
-        if let Some(data) = msg.data {
+        if let Some(session) = session
+            && msg.msg.is_session_tracked()
+            && !session.sent(frames.clone())
+        {
+            tracing::warn!("too many sent messages not acknowledged by remote endpoint");
+            return Err(ChMuxError::SessionLost);
+        }
+
+        Self::feed_frames(frames, sink).await
+    }
+
+    /// Feed encoded message to sink.
+    async fn feed_frames(
+        (msg, data): Frames, sink: &mut TransportSink,
+    ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
+        sink.feed(msg).await.map_err(ChMuxError::SinkError)?;
+
+        if let Some(data) = data {
             sink.feed(data).await.map_err(ChMuxError::SinkError)?;
         }
 
@@ -344,7 +558,7 @@ where
 
         let msg = MultiplexMsg::from_slice(&msg_data)?;
 
-        let data = if let MultiplexMsg::Data { .. } = &msg {
+        let data = if msg.has_data() {
             match stream.next().await {
                 Some(Ok(data)) => Some(data),
                 Some(Err(err)) => return Err(ChMuxError::StreamError(err)),
@@ -365,15 +579,21 @@ where
     /// Exchange Hello message with remote endpoint.
     #[tracing::instrument(level = "trace", skip_all)]
     async fn exchange_hello(
-        cfg: &Cfg, sink: &mut TransportSink, stream: &mut TransportStream,
+        cfg: &Cfg, session: Option<SessionHello>, handshake: bool, sink: &mut TransportSink,
+        stream: &mut TransportStream,
     ) -> Result<(u8, ExchangedCfg), ChMuxError<TransportSinkError, TransportStreamError>> {
+        let mut exchanged_cfg = ExchangedCfg::from(cfg);
+        exchanged_cfg.session = session;
+        exchanged_cfg.handshake = handshake;
+
         // Say hello to remote endpoint and send our configuration.
         let send_task = async {
-            Self::feed_msg(TransportMsg::new(MultiplexMsg::Reset), sink).await?;
+            Self::feed_msg(TransportMsg::new(MultiplexMsg::Reset), sink, None).await?;
             Self::flush(sink).await?;
             Self::feed_msg(
-                TransportMsg::new(MultiplexMsg::Hello { version: PROTOCOL_VERSION, cfg: cfg.into() }),
+                TransportMsg::new(MultiplexMsg::Hello { version: PROTOCOL_VERSION, cfg: exchanged_cfg }),
                 sink,
+                None,
             )
             .await?;
             Self::flush(sink).await?;
@@ -397,6 +617,94 @@ where
         Ok(try_join!(send_task, recv_task)?.1)
     }
 
+    /// Performs the handshake with the remote endpoint, if configured, and
+    /// returns the identity of the remote endpoint.
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn handshake(
+        cfg: &Cfg, handshake: Option<&dyn Handshake>, remote_cfg: &ExchangedCfg, sink: &mut TransportSink,
+        stream: &mut TransportStream,
+    ) -> Result<Option<PeerIdentity>, ChMuxError<TransportSinkError, TransportStreamError>> {
+        let handshake = match (handshake, remote_cfg.handshake) {
+            (Some(handshake), true) => handshake,
+            (None, false) => return Ok(None),
+            _ => return Err(ChMuxError::Handshake(HandshakeError::Mismatch)),
+        };
+
+        let (channel, mut out_rx, in_tx) = HandshakeChannel::new();
+
+        // Run local handshake and send its messages to remote endpoint.
+        let local_task = async {
+            let hs = handshake.handshake(channel).fuse();
+            pin_mut!(hs);
+
+            let result = loop {
+                tokio::select! {
+                    biased;
+                    Some(data) = out_rx.recv() => {
+                        Self::feed_msg(TransportMsg::with_data(MultiplexMsg::HandshakeData, data), sink, None)
+                            .await?;
+                        Self::flush(sink).await?;
+                    }
+                    result = &mut hs => break result,
+                }
+            };
+
+            while let Ok(data) = out_rx.try_recv() {
+                Self::feed_msg(TransportMsg::with_data(MultiplexMsg::HandshakeData, data), sink, None).await?;
+            }
+            Self::feed_msg(
+                TransportMsg::new(MultiplexMsg::HandshakeDone { success: result.is_ok() }),
+                sink,
+                None,
+            )
+            .await?;
+            Self::flush(sink).await?;
+
+            result.map_err(ChMuxError::Handshake)
+        };
+
+        // Forward messages of remote handshake until it is done.
+        let remote_task = async move {
+            loop {
+                match Self::recv_msg(stream).await? {
+                    TransportMsg { msg: MultiplexMsg::HandshakeData, data: Some(data) } => {
+                        let _ = in_tx.send(data).await;
+                    }
+                    TransportMsg { msg: MultiplexMsg::HandshakeDone { success: true }, .. } => break Ok(()),
+                    TransportMsg { msg: MultiplexMsg::HandshakeDone { success: false }, .. } => {
+                        break Err(ChMuxError::Handshake(HandshakeError::RemoteFailed));
+                    }
+                    _ => break Err(protocol_err("received unexpected message during handshake")),
+                }
+            }
+        };
+
+        // A local failure takes precedence, since the remote endpoint may close the transport
+        // as soon as it receives it. It also ends the handshake without waiting for the remote endpoint.
+        let fut = async {
+            pin_mut!(local_task, remote_task);
+            tokio::select! {
+                biased;
+                result = &mut local_task => {
+                    let identity = result?;
+                    remote_task.await?;
+                    Ok(identity)
+                }
+                result = &mut remote_task => {
+                    result?;
+                    local_task.await
+                }
+            }
+        };
+        let identity = match cfg.connection_timeout {
+            Some(dur) => timeout(dur, fut).await.map_err(|_| ChMuxError::Timeout)??,
+            None => fut.await?,
+        };
+
+        tracing::debug!(%identity, "handshake completed");
+        Ok(Some(identity))
+    }
+
     /// Returns true, when multiplexer task should terminate because no more
     /// requests are possible.
     fn should_terminate(&self) -> bool {
@@ -404,12 +712,16 @@ where
 
         // Ensures that all ports are closed on both sides.
         terminate &= self.ports.is_empty();
-        // Ensures that local clients are all dropped or remote listener is dropped.
-        terminate &= self.all_clients_dropped || self.remote_listener_dropped.load(Ordering::Relaxed);
+        // Ensures that local clients are all dropped or remote listener is dropped
+        // or no more local connect requests are processed due to draining.
+        terminate &=
+            self.all_clients_dropped || self.remote_listener_dropped.load(Ordering::Relaxed) || self.draining;
         // Ensures that local listener or all remote clients are dropped.
         terminate &= self.listen_tx.is_none() || self.remote_client_dropped;
         // No remote port requests are outstanding.
-        terminate &= self.outstanding_remote_port_requests.is_empty();
+        terminate &= self.outstanding_remote_port_requests.is_empty() && self.quota_rejects.is_empty();
+        // Forcible termination has been requested locally.
+        terminate |= self.terminate_requested;
         // If goodbye has been sent, we request connection termination,
         // possibly even with still connected ports.
         terminate |= self.goodbye_sent;
@@ -426,11 +738,16 @@ where
 
     /// Create port in port registry and return associated sender and receiver.
     #[tracing::instrument(level = "trace", skip(self))]
-    fn create_port(&mut self, local_port: PortNumber, remote_port: u32) -> (Sender, Receiver) {
+    fn create_port(
+        &mut self, local_port: PortNumber, remote_port: u32, priority: Priority,
+    ) -> (Sender, Receiver) {
         let local_port_num = *local_port;
 
-        let sender_tx = self.channel_tx.clone();
-        let (sender_credit_provider, sender_credit_user) = credit_send_pair(self.remote_cfg.port_receive_buffer);
+        let counters = self.stats.add_port(local_port_num, remote_port, priority);
+
+        let sender_tx = self.sender_tx[priority.index()].clone();
+        let (sender_credit_provider, sender_credit_user) =
+            credit_send_pair(self.remote_cfg.port_receive_buffer, counters.clone());
 
         let receiver_tx = self.channel_tx.clone();
         let (receiver_tx_data, receiver_rx_data) = mpsc::unbounded_channel();
@@ -444,6 +761,8 @@ where
             local_port,
             PortState::Connected {
                 remote_port,
+                priority,
+                counters,
                 sender_credit_provider,
                 receiver_tx_data: Some(receiver_tx_data),
                 receiver_credit_monitor,
@@ -465,12 +784,14 @@ where
             remote_port,
             self.remote_cfg.chunk_size as usize,
             self.local_cfg.max_data_size,
+            priority,
             sender_tx,
             sender_credit_user,
             Arc::downgrade(&hangup_recved),
             Arc::downgrade(&hangup_notify),
             self.port_allocator.clone(),
             self.storage.clone(),
+            self.local_cfg.channel_priorities,
         );
 
         let receiver = Receiver::new(
@@ -478,16 +799,60 @@ where
             remote_port,
             self.local_cfg.max_data_size,
             self.local_cfg.max_received_ports,
+            priority,
             receiver_tx,
             receiver_rx_data,
             receiver_credit_returner,
             self.port_allocator.clone(),
             self.storage.clone(),
+            self.local_cfg.channel_priorities,
+            self.peer_info.clone(),
+            self.helper_tasks.clone(),
         );
 
         (sender, receiver)
     }
 
+    /// Checks whether the specified number of additional port requests from the
+    /// remote endpoint would exceed a quota.
+    fn check_quotas(&self, requests: usize) -> Result<(), Quota> {
+        let cfg = &self.local_cfg;
+
+        let pending = self.outstanding_remote_port_requests.len() + requests;
+        if cfg.max_pending_port_requests.is_some_and(|max| pending > max) {
+            return Err(Quota::PendingPortRequests);
+        }
+
+        // Upper bound assuming that the receive buffer of each port is completely filled.
+        let memory = (self.ports.len() + pending) as u64 * u64::from(cfg.receive_buffer);
+        if cfg.max_receive_buffer_memory.is_some_and(|max| memory > max) {
+            return Err(Quota::ReceiveBufferMemory);
+        }
+
+        if cfg.max_helper_tasks.is_some_and(|max| self.helper_tasks.count() + requests > max) {
+            return Err(Quota::HelperTasks);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the quota policy to port requests from the remote endpoint that exceeded a quota.
+    fn quota_exceeded(
+        &mut self, quota: Quota, remote_ports: impl IntoIterator<Item = u32>,
+    ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
+        match self.local_cfg.quota_policy {
+            QuotaPolicy::Reject => {
+                tracing::warn!(%quota, "rejecting port requests exceeding quota");
+                self.quota_rejects.extend(remote_ports);
+                Ok(())
+            }
+            QuotaPolicy::Terminate => {
+                tracing::warn!(%quota, "terminating connection since remote endpoint exceeded quota");
+                Err(ChMuxError::QuotaExceeded(quota))
+            }
+        }
+    }
+
     /// Releases a port if no more local requests to it are possible
     /// and no more messages from the remote endpoint can reference it.
     fn maybe_free_port(&mut self, local_port: u32) {
@@ -520,14 +885,23 @@ where
         if free {
             tracing::trace!(local_port, "freed port");
             self.ports.remove(&local_port);
+            self.stats.remove_port(local_port);
         }
     }
 
     /// Sends data over the transport sink.
     ///
+    /// Queued messages with a higher priority are sent first.
     /// Automatically sends pings if no data is to be transmitted.
+    /// When a session is active, acknowledgements are sent instead of pings.
+    /// If the remote endpoint answers pings, a ping is sent every ping interval to
+    /// measure the round-trip time.
+    /// Explicitly requested pings and pongs required by the receive task are sent immediately.
+    /// Data is compressed using the specified algorithm, if it exceeds the specified threshold.
     async fn send_task(
-        mut sink: &mut TransportSink, ping_interval: Option<Duration>, mut rx: mpsc::Receiver<TransportMsg>,
+        mut sink: &mut TransportSink, ping_interval: Option<Duration>, rx: &mut [mpsc::Receiver<TransportMsg>],
+        session: Option<&Session>, pinger: &Pinger, ping_rx: &mut mpsc::UnboundedReceiver<PingRequest>,
+        compression: Option<(Compression, u32)>,
     ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
         async fn get_next_ping(ping_interval: Option<Duration>) {
             match ping_interval {
@@ -536,21 +910,51 @@ where
             }
         }
 
+        let keep_alive_msg = || match session {
+            Some(session) => MultiplexMsg::Ack { received: session.ack() },
+            None => pinger.ping(Vec::new()),
+        };
+
         let mut next_ping = ReusableBoxFuture::new(get_next_ping(ping_interval));
+        let mut next_rtt_ping =
+            ReusableBoxFuture::new(get_next_ping(ping_interval.filter(|_| pinger.remote_pong())));
         let mut need_flush = false;
 
+        // Resend pings that were lost due to a transport failure.
+        let lost = pinger.take_lost();
+        if !lost.is_empty() {
+            Self::feed_msg(TransportMsg::new(pinger.ping(lost)), sink, None).await?;
+            need_flush = true;
+        }
+
         loop {
             SinkReady::new(&mut sink).await.map_err(ChMuxError::SinkError)?;
 
             tokio::select! {
                 biased;
 
-                msg_opt = rx.recv() => {
+                pongs = pinger.pongs() => {
+                    for pong in pongs {
+                        Self::feed_msg(TransportMsg::new(pong), sink, None).await?;
+                    }
+                    need_flush = true;
+                }
+
+                Some(req) = ping_rx.recv() => {
+                    Self::feed_msg(TransportMsg::new(pinger.ping(vec![req])), sink, None).await?;
+                    need_flush = true;
+                }
+
+                msg_opt = future::poll_fn(|cx| Self::poll_recv_prioritized(rx, cx)) => {
                     match msg_opt {
-                        Some(msg) => {
+                        Some((_, msg)) => {
                             let is_goodbye = matches!(&msg, TransportMsg {msg: MultiplexMsg::Goodbye, ..});
 
-                            Self::feed_msg(msg, sink).await?;
+                            let msg = match compression {
+                                Some((compression, threshold)) => msg.compress(compression, threshold),
+                                None => msg,
+                            };
+                            Self::feed_msg(msg, sink, session).await?;
 
                             if is_goodbye {
                                 break;
@@ -563,12 +967,26 @@ where
                     }
                 }
 
+                () = async { match session {
+                    Some(session) => session.ack_required().await,
+                    None => future::pending().await,
+                }} => {
+                    Self::feed_msg(TransportMsg::new(keep_alive_msg()), sink, None).await?;
+                    need_flush = true;
+                }
+
                 () = &mut next_ping => {
-                    Self::feed_msg(TransportMsg::new(MultiplexMsg::Ping), sink).await?;
+                    Self::feed_msg(TransportMsg::new(keep_alive_msg()), sink, None).await?;
                     next_ping.set(get_next_ping(ping_interval));
                     need_flush = true;
                 }
 
+                () = &mut next_rtt_ping => {
+                    Self::feed_msg(TransportMsg::new(pinger.ping(Vec::new())), sink, None).await?;
+                    next_rtt_ping.set(get_next_ping(ping_interval));
+                    need_flush = true;
+                }
+
                 () = future::ready(()), if need_flush => {
                     Self::flush(sink).await?;
                     need_flush = false;
@@ -586,8 +1004,13 @@ where
     /// Receives data over the transport sink.
     ///
     /// Watches the connection timeout.
+    /// When a session is active, acknowledgements are processed and not forwarded.
+    /// Pongs are processed and not forwarded.
+    /// Pings with a nonce are answered by the send task.
+    /// Compressed data is decompressed, limited to the specified chunk size.
     async fn recv_task(
-        stream: &mut TransportStream, connection_timeout: Option<Duration>, tx: mpsc::Sender<TransportMsg>,
+        stream: &mut TransportStream, connection_timeout: Option<Duration>, tx: &mpsc::Sender<TransportMsg>,
+        session: Option<&Session>, pinger: &Pinger, chunk_size: u32,
     ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
         async fn get_connection_timeout(connection_timeout: Option<Duration>) {
             match connection_timeout {
@@ -603,14 +1026,36 @@ where
                 biased;
 
                 msg = Self::recv_msg(stream) => {
-                    let msg = msg?;
-                    let is_goodbye = matches!(&msg, TransportMsg {msg: MultiplexMsg::Goodbye, ..});
-                    tx_permit.send(msg);
-                    if is_goodbye {
-                        break;
-                    }
-
+                    let msg = msg?
+                        .decompress(chunk_size as usize)
+                        .map_err(|err| protocol_err(format!("decompressing received data failed: {err}")))?;
                     next_timeout.set(get_connection_timeout(connection_timeout));
+
+                    match (session, &msg.msg) {
+                        (Some(session), MultiplexMsg::Ack { received }) => {
+                            if !session.acknowledged(*received) {
+                                return Err(protocol_err("remote endpoint acknowledged unsent messages"));
+                            }
+                        }
+                        (_, MultiplexMsg::Pong { nonce }) => pinger.pong_received(*nonce),
+                        _ => {
+                            if let MultiplexMsg::Ping { nonce } = &msg.msg {
+                                pinger.ping_received(*nonce);
+                            }
+
+                            if let Some(session) = session
+                                && msg.msg.is_session_tracked()
+                            {
+                                session.received();
+                            }
+
+                            let is_goodbye = matches!(&msg, TransportMsg {msg: MultiplexMsg::Goodbye, ..});
+                            tx_permit.send(msg);
+                            if is_goodbye {
+                                break;
+                            }
+                        }
+                    }
                 },
 
                 () = &mut next_timeout => return Err(ChMuxError::Timeout),
@@ -620,79 +1065,304 @@ where
         Ok(())
     }
 
+    /// Resumes the session over a new transport.
+    ///
+    /// Returns the messages that must be resent, since they were not received by the remote endpoint.
+    /// Returns `None` if the new transport does not lead to the remote endpoint of the session.
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn resume(
+        cfg: &Cfg, session: &Session, handshake: Option<&dyn Handshake>, peer_identity: Option<&PeerIdentity>,
+        sink: &mut TransportSink, stream: &mut TransportStream,
+    ) -> Result<Option<Vec<Frames>>, ChMuxError<TransportSinkError, TransportStreamError>> {
+        let fut = Self::exchange_hello(cfg, Some(session.hello()), handshake.is_some(), sink, stream);
+        let (_, remote_cfg) = match cfg.connection_timeout {
+            Some(dur) => timeout(dur, fut).await.map_err(|_| ChMuxError::Timeout)??,
+            None => fut.await?,
+        };
+
+        // The new transport must lead to the same remote endpoint.
+        if Self::handshake(cfg, handshake, &remote_cfg, sink, stream).await?.as_ref() != peer_identity {
+            tracing::warn!("peer identity changed");
+            return Ok(None);
+        }
+
+        match remote_cfg.session {
+            Some(SessionHello { id, resume: true, received }) if id == session.remote_id() => {
+                let resend = session.resume(received).ok_or(ChMuxError::SessionLost)?;
+                tracing::debug!(resend = resend.len(), "resuming session");
+                Ok(Some(resend))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Exchanges messages over the transport.
+    ///
+    /// If a session is active and the transport fails, a new transport is obtained
+    /// from the reconnect function and the session is resumed.
+    #[allow(clippy::too_many_arguments)]
+    async fn transport_task<Reconnect, ReconnectFut>(
+        sink: TransportSink, stream: TransportStream, cfg: Cfg, remote_cfg: ExchangedCfg,
+        session: Option<Session>, handshake: Option<Arc<dyn Handshake>>, peer_identity: Option<PeerIdentity>,
+        mut send_rx: Vec<mpsc::Receiver<TransportMsg>>, recv_tx: mpsc::Sender<TransportMsg>, pinger: Pinger,
+        mut ping_rx: mpsc::UnboundedReceiver<PingRequest>, mut reconnect: Reconnect,
+    ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>>
+    where
+        Reconnect: FnMut() -> ReconnectFut,
+        ReconnectFut: Future<Output = Option<(TransportSink, TransportStream)>>,
+    {
+        let ping_interval = remote_cfg.connection_timeout.map(|d| d / 2);
+        let compression = cfg
+            .compression
+            .filter(|compression| remote_cfg.compression & compression.mask() != 0)
+            .map(|compression| (compression, cfg.compression_threshold));
+        let mut transport = (sink, stream);
+        let mut resend = Vec::new();
+        let mut send_done = false;
+        let mut recv_done = false;
+
+        loop {
+            let (mut sink, mut stream) = transport;
+
+            let send = async {
+                // Resend lost messages while the remote endpoint is already receiving.
+                if !resend.is_empty() {
+                    for frames in resend.drain(..) {
+                        Self::feed_frames(frames, &mut sink).await?;
+                    }
+                    Self::flush(&mut sink).await?;
+                }
+
+                if !send_done {
+                    Self::send_task(
+                        &mut sink,
+                        ping_interval,
+                        &mut send_rx,
+                        session.as_ref(),
+                        &pinger,
+                        &mut ping_rx,
+                        compression,
+                    )
+                    .await?;
+                    send_done = true;
+                }
+                Ok(())
+            };
+            let recv = async {
+                if !recv_done {
+                    Self::recv_task(
+                        &mut stream,
+                        cfg.connection_timeout,
+                        &recv_tx,
+                        session.as_ref(),
+                        &pinger,
+                        cfg.chunk_size,
+                    )
+                    .await?;
+                    recv_done = true;
+                }
+                Ok(())
+            };
+
+            let err = match try_join!(send, recv) {
+                Ok(_) => return Ok(()),
+                Err(err) => err,
+            };
+
+            let Some(session) = &session else { return Err(err) };
+            if !err.is_transport_failure() {
+                return Err(err);
+            }
+            tracing::warn!(%err, "transport failed, trying to resume session");
+            pinger.transport_lost();
+
+            // Release failed transport, so that the remote endpoint notices the failure.
+            drop(sink);
+            drop(stream);
+
+            transport = loop {
+                let Some((mut sink, mut stream)) = reconnect().await else {
+                    tracing::warn!("no new transport available for resuming session");
+                    return Err(err);
+                };
+
+                match Self::resume(
+                    &cfg,
+                    session,
+                    handshake.as_deref(),
+                    peer_identity.as_ref(),
+                    &mut sink,
+                    &mut stream,
+                )
+                .await
+                {
+                    Ok(Some(frames)) => {
+                        resend = frames;
+                        break (sink, stream);
+                    }
+                    Ok(None) => {
+                        tracing::warn!("new transport leads to a different remote endpoint");
+                    }
+                    Err(err) if err.is_transport_failure() || matches!(err, ChMuxError::Handshake(_)) => {
+                        tracing::warn!(%err, "resuming session failed");
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+        }
+    }
+
     /// Runs the multiplexer dispatcher.
     ///
     /// The dispatcher terminates when the client, server and all channels have been dropped or
     /// the transport is closed.
+    pub async fn run(self) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
+        self.run_with_reconnect(|| future::ready(None)).await
+    }
+
+    /// Runs the multiplexer dispatcher with support for reconnecting the transport.
+    ///
+    /// If [session resumption](Cfg::session_resumption) is active and the transport fails,
+    /// `reconnect` is called to obtain a new transport to the same remote endpoint.
+    /// The remote endpoint must also obtain a new transport within its reconnect function.
+    /// The session is then resumed over the new transport and all ports stay open.
+    /// If `reconnect` returns `None`, the dispatcher terminates with the transport error.
+    ///
+    /// The dispatcher terminates when the client, server and all channels have been dropped or
+    /// the transport is closed and cannot be reconnected.
     #[tracing::instrument(name = "remoc::chmux", level = "debug", skip_all, ret)]
-    pub async fn run(mut self) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
-        let mut transport_sink = self.transport_sink.take().unwrap();
-        let mut transport_stream = self.transport_stream.take().unwrap();
-
-        // Create send over transport task.
-        let (send_tx, send_rx) = mpsc::channel(self.local_cfg.transport_send_queue);
-        let send_task =
-            Self::send_task(&mut transport_sink, self.remote_cfg.connection_timeout.map(|d| d / 2), send_rx)
-                .fuse();
-        pin_mut!(send_task);
-
-        // Create receive over transport task.
+    pub async fn run_with_reconnect<Reconnect, ReconnectFut>(
+        mut self, reconnect: Reconnect,
+    ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>>
+    where
+        Reconnect: FnMut() -> ReconnectFut,
+        ReconnectFut: Future<Output = Option<(TransportSink, TransportStream)>>,
+    {
+        // Create task for sending and receiving over transport.
+        // A separate send queue is used for each priority.
+        let (send_tx, send_rx): (Vec<_>, Vec<_>) =
+            Priority::ALL.iter().map(|_| mpsc::channel(self.local_cfg.transport_send_queue)).unzip();
         let (recv_tx, mut recv_rx) = mpsc::channel(self.local_cfg.transport_receive_queue);
-        let recv_task = Self::recv_task(&mut transport_stream, self.local_cfg.connection_timeout, recv_tx).fuse();
-        pin_mut!(recv_task);
+        let transport_task = Self::transport_task(
+            self.transport_sink.take().unwrap(),
+            self.transport_stream.take().unwrap(),
+            self.local_cfg.clone(),
+            self.remote_cfg.clone(),
+            self.session.take(),
+            self.handshake.clone(),
+            self.peer_info.identity().cloned(),
+            send_rx,
+            recv_tx,
+            Pinger::new(self.remote_cfg.pong, self.local_cfg.ping_timeout, self.stats.clone()),
+            self.ping_rx.take().unwrap(),
+            reconnect,
+        )
+        .fuse();
+        pin_mut!(transport_task);
 
         // Setup channels.
         let mut channel_rx = self.channel_rx.take().unwrap();
+        let mut sender_rx = self.sender_rx.take().unwrap();
         let mut connect_rx = self.connect_rx.take().unwrap();
         let mut terminate_rx = self.terminate_rx.take().unwrap();
-        let mut send_task_ended = false;
+        let mut transport_task_ended = false;
 
-        while !(self.goodbye_sent && self.goodbye_received && send_task_ended) {
-            let send_prep_task = async {
-                // Obtain permit to ensure that space is available in transport send queue.
-                let permit = match send_tx.reserve().await {
-                    Ok(permit) => permit,
-                    Err(_) => return None,
-                };
+        while !(self.goodbye_sent && self.goodbye_received && transport_task_ended) {
+            let send_queued = send_tx.iter().map(|tx| tx.max_capacity() - tx.capacity()).sum();
+            self.stats.set_transport_queues(send_queued, recv_rx.len());
 
-                // Select local request for processing.
-                let event = tokio::select! {
+            let send_prep_task = async {
+                let (low_rx, normal_rx, high_rx) = Self::by_priority(&mut sender_rx);
+                let reserve = |priority: Priority| send_tx[priority.index()].reserve();
+
+                // Select local request for processing together with a permit for the
+                // transport send queue of the priority its message is put into.
+                // A request is only selected once space is available in its transport send queue,
+                // so that a full queue of lower priority does not block requests of higher priority.
+                tokio::select! {
                     biased;
 
-                    // Server dropped.
-                    () = async { match &self.listen_tx {
-                        Some((listen_wait_tx, _)) => listen_wait_tx.closed().await,
-                        None => future::pending().await
-                    }} => {
-                        // listen_no_wait_tx is closed simultaneously.
-                        GlobalEvt::ListenerDropped
-                    },
-
-                    // Connection request from client.
-                    connect_req_opt = connect_rx.recv(), if !self.all_clients_dropped => {
-                        match connect_req_opt {
-                            Some(connect_req) => GlobalEvt::ConnectReq(connect_req),
-                            None => GlobalEvt::AllClientsDropped,
-                        }
-                    },
-
-                    // Request from port.
-                    Some(msg) = channel_rx.recv() => {
-                        GlobalEvt::Port(msg)
-                    },
+                    // Control requests and requests from port senders with high priority.
+                    Some(permit_event) = async {
+                        let permit = reserve(Priority::High).await.ok()?;
+                        let event = tokio::select! {
+                            biased;
+
+                            // Server dropped.
+                            () = async { match &self.listen_tx {
+                                Some((listen_wait_tx, _)) => listen_wait_tx.closed().await,
+                                None => future::pending().await
+                            }} => {
+                                // listen_no_wait_tx is closed simultaneously.
+                                GlobalEvt::ListenerDropped
+                            },
+
+                            // Connection request from client.
+                            connect_req_opt = connect_rx.recv(), if !self.all_clients_dropped => {
+                                match connect_req_opt {
+                                    Some(connect_req) => GlobalEvt::ConnectReq(connect_req),
+                                    None => GlobalEvt::AllClientsDropped,
+                                }
+                            },
+
+                            // Control request from port.
+                            Some(msg) = channel_rx.recv() => GlobalEvt::Port(msg),
+
+                            // Request from port sender with high priority.
+                            Some(msg) = high_rx.recv() => GlobalEvt::Port(msg),
+
+                            // Reject remote port request that exceeded a quota.
+                            () = future::ready(()), if !self.quota_rejects.is_empty() => GlobalEvt::QuotaReject,
+
+                            // Local request to terminate forcibly or drain.
+                            Some(req) = terminate_rx.recv(), if !self.goodbye_sent => {
+                                match req {
+                                    ShutdownReq::Terminate => GlobalEvt::Terminate,
+                                    ShutdownReq::Drain(timeout) => GlobalEvt::Drain { timeout },
+                                }
+                            }
+                        };
+                        Some((permit, event))
+                    } => Some(permit_event),
+
+                    // Request from port sender with normal priority.
+                    Some(permit_event) = async {
+                        let permit = reserve(Priority::Normal).await.ok()?;
+                        let msg = normal_rx.recv().await?;
+                        Some((permit, GlobalEvt::Port(msg)))
+                    } => Some(permit_event),
+
+                    // Requests from port senders with low priority and termination.
+                    // Goodbye is queued with lowest priority, so that it is sent after all queued messages.
+                    Some(permit_event) = async {
+                        let permit = reserve(Priority::Low).await.ok()?;
+                        let event = tokio::select! {
+                            biased;
+
+                            // Request from port sender with low priority.
+                            Some(msg) = low_rx.recv() => GlobalEvt::Port(msg),
+
+                            // Drain timeout elapsed, terminate forcibly.
+                            () = async { match self.drain_deadline {
+                                Some(deadline) => sleep(deadline.duration_since(Instant::now())).await,
+                                None => future::pending().await,
+                            }}, if !self.goodbye_sent => {
+                                tracing::debug!(open_ports = self.ports.len(), "drain timeout elapsed");
+                                GlobalEvt::SendGoodbye
+                            }
 
-                    // Local request to terminate forcibly.
-                    Some(()) = terminate_rx.recv(), if !self.goodbye_sent => {
-                        GlobalEvt::SendGoodbye
-                    }
+                            // Send Goodbye message and terminate.
+                            () = future::ready(()), if self.should_terminate() && !self.goodbye_sent => {
+                                GlobalEvt::SendGoodbye
+                            },
 
-                    // Send Goodbye message and terminate.
-                    () = future::ready(()), if self.should_terminate() && !self.goodbye_sent => {
-                        GlobalEvt::SendGoodbye
-                    },
-                };
+                            else => return None,
+                        };
+                        Some((permit, event))
+                    } => Some(permit_event),
 
-                Some((permit, event))
+                    else => None,
+                }
             };
 
             tokio::select! {
@@ -702,22 +1372,44 @@ where
                 // Received message from remote endpoint.
                 Some(msg) = recv_rx.recv() => self.handle_received_msg(msg).await?,
 
-                // Send task ended.
-                res = &mut send_task => {
+                // Transport task ended.
+                res = &mut transport_task => {
                     match res {
-                        Ok(()) => send_task_ended = true,
+                        Ok(()) => transport_task_ended = true,
                         Err(err) => return Err(err),
                     }
                 }
-
-                // Receive task failed.
-                Err(err) = &mut recv_task => return Err(err),
             }
         }
 
         Ok(())
     }
 
+    /// Receives the next value with the highest priority from receivers indexed by priority.
+    ///
+    /// Returns `None` when all receivers are closed.
+    fn poll_recv_prioritized<T>(rxs: &mut [mpsc::Receiver<T>], cx: &mut Context) -> Poll<Option<(Priority, T)>> {
+        let mut closed = 0;
+        for (priority, rx) in Priority::ALL.into_iter().zip(rxs.iter_mut()).rev() {
+            match rx.poll_recv(cx) {
+                Poll::Ready(Some(value)) => return Poll::Ready(Some((priority, value))),
+                Poll::Ready(None) => closed += 1,
+                Poll::Pending => (),
+            }
+        }
+
+        if closed == rxs.len() { Poll::Ready(None) } else { Poll::Pending }
+    }
+
+    /// Splits values indexed by priority into the values for low, normal and high priority.
+    ///
+    /// # Panics
+    /// Panics if not exactly one value per priority is specified.
+    fn by_priority<T>(values: &mut [T]) -> (&mut T, &mut T, &mut T) {
+        let [low, normal, high] = values else { panic!("exactly one value per priority is required") };
+        (low, normal, high)
+    }
+
     /// Handle local event that results in sending a message to the remote endpoint.
     #[tracing::instrument(level = "trace", skip_all, fields(event=?event))]
     async fn handle_event(
@@ -730,10 +1422,17 @@ where
 
         match event {
             // Process local connect request.
-            GlobalEvt::ConnectReq(ConnectRequest { local_port, id, sent_tx: _sent_tx, response_tx, wait }) => {
-                if !self.remote_listener_dropped.load(Ordering::Relaxed) {
+            GlobalEvt::ConnectReq(ConnectRequest {
+                local_port,
+                id,
+                priority,
+                sent_tx: _sent_tx,
+                response_tx,
+                wait,
+            }) => {
+                if !self.remote_listener_dropped.load(Ordering::Relaxed) && !self.draining {
                     let local_port_num = *local_port;
-                    if self.ports.insert(local_port, PortState::Connecting { response_tx }).is_some() {
+                    if self.ports.insert(local_port, PortState::Connecting { response_tx, priority }).is_some() {
                         panic!("ConnectRequest for already used local port {local_port_num}");
                     }
                     let id = (self.remote_protocol_version >= PROTOCOL_VERSION_PORT_ID).then_some(id);
@@ -744,7 +1443,7 @@ where
             }
 
             // Remote connect request was accepted by local listener.
-            GlobalEvt::Port(PortEvt::Accepted { local_port, remote_port, port_tx }) => {
+            GlobalEvt::Port(PortEvt::Accepted { local_port, remote_port, priority, port_tx }) => {
                 if !self.outstanding_remote_port_requests.remove(&remote_port) {
                     panic!("Accepted non-outstanding remote port {remote_port} request");
                 }
@@ -753,7 +1452,7 @@ where
                     permit,
                     MultiplexMsg::PortOpened { client_port: remote_port, server_port: local_port_num },
                 );
-                let (sender, receiver) = self.create_port(local_port, remote_port);
+                let (sender, receiver) = self.create_port(local_port, remote_port, priority);
                 let _ = port_tx.send((sender, receiver));
             }
 
@@ -766,8 +1465,11 @@ where
             }
 
             // Send data from port.
-            GlobalEvt::Port(PortEvt::SendData { remote_port, data, first, last }) => {
-                let msg = MultiplexMsg::Data { port: remote_port, first, last };
+            GlobalEvt::Port(PortEvt::SendData { local_port, remote_port, data, first, last }) => {
+                if let Some(PortState::Connected { counters, .. }) = self.ports.get(&local_port) {
+                    counters.sent(data.len());
+                }
+                let msg = MultiplexMsg::Data { port: remote_port, first, last, compression: None };
                 tracing::trace!(op="send", msg=?msg, data=?&data);
                 permit.send(TransportMsg::with_data(msg, data));
             }
@@ -776,9 +1478,9 @@ where
             GlobalEvt::Port(PortEvt::SendPorts { remote_port, ports, first, last, wait }) => {
                 let mut port_nums = Vec::new();
                 let mut ids = (self.remote_protocol_version >= PROTOCOL_VERSION_PORT_ID).then_some(Vec::new());
-                for (PortReq { port, id }, response_tx) in ports {
+                for (PortReq { port, id, priority }, response_tx) in ports {
                     let port_num = *port;
-                    if self.ports.insert(port, PortState::Connecting { response_tx }).is_some() {
+                    if self.ports.insert(port, PortState::Connecting { response_tx, priority }).is_some() {
                         panic!("SendPorts with already used local port {port_num}");
                     }
                     port_nums.push(port_num);
@@ -859,11 +1561,37 @@ where
                 send_msg(permit, MultiplexMsg::ListenerFinish);
             }
 
+            // Send Goodbye message after all queued messages.
+            GlobalEvt::Terminate => {
+                self.terminate_requested = true;
+            }
+
             // Send Goodbye message.
             GlobalEvt::SendGoodbye => {
                 self.goodbye_sent = true;
                 send_msg(permit, MultiplexMsg::Goodbye);
             }
+
+            // Reject remote port request that exceeded a quota.
+            GlobalEvt::QuotaReject => {
+                if let Some(remote_port) = self.quota_rejects.pop_front() {
+                    send_msg(permit, MultiplexMsg::Rejected { client_port: remote_port, no_ports: false });
+                }
+            }
+
+            // Stop accepting new ports and notify remote endpoint.
+            // Remote connect requests still queued for or arriving after closing the listen
+            // queue are rejected when their request is dropped.
+            GlobalEvt::Drain { timeout } => {
+                if !self.draining {
+                    tracing::debug!(open_ports = self.ports.len(), ?timeout, "draining");
+                    self.draining = true;
+                    self.drain_deadline = Some(Instant::now() + timeout);
+                    if self.listen_tx.take().is_some() {
+                        send_msg(permit, MultiplexMsg::ListenerFinish);
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -889,21 +1617,45 @@ where
             }
 
             //  Nothing to do for ping message.
-            MultiplexMsg::Ping => (),
+            MultiplexMsg::Ping { .. } => (),
+
+            // Pongs are processed by the receive task.
+            MultiplexMsg::Pong { .. } => (),
+
+            // Handshake messages only allowed when establishing connection.
+            MultiplexMsg::HandshakeData | MultiplexMsg::HandshakeDone { .. } => {
+                return Err(protocol_err(
+                    "received handshake message for already established multiplexer connection",
+                ));
+            }
+
+            // Acknowledgements are processed by the receive task when a session is active.
+            MultiplexMsg::Ack { .. } => {
+                return Err(protocol_err("received Ack message without active session"));
+            }
 
             // Open port request from remote endpoint.
             MultiplexMsg::OpenPort { client_port, wait, id } => {
-                if !self.outstanding_remote_port_requests.insert(client_port) {
+                if self.outstanding_remote_port_requests.contains(&client_port)
+                    || self.quota_rejects.contains(&client_port)
+                {
                     return Err(protocol_err(format!(
                         "remote endpoint sent OpenPort request for same remote port {client_port} twice"
                     )));
                 }
+                if let Err(quota) = self.check_quotas(1) {
+                    return self.quota_exceeded(quota, [client_port]);
+                }
+                self.outstanding_remote_port_requests.insert(client_port);
                 let req = RemoteConnectMsg::Request(Request::new(
                     client_port,
                     id.unwrap_or(client_port),
                     wait,
+                    Priority::default(),
+                    self.peer_info.identity().cloned(),
                     self.port_allocator.clone(),
                     self.channel_tx.clone(),
+                    &self.helper_tasks,
                 ));
                 if let Some((listen_wait_tx, listen_no_wait_tx)) = &self.listen_tx {
                     let res = if wait { listen_wait_tx.try_send(req) } else { listen_no_wait_tx.try_send(req) };
@@ -916,8 +1668,8 @@ where
             // Port opened response from remote endpoint.
             MultiplexMsg::PortOpened { client_port, server_port } => {
                 match self.ports.remove_entry(&client_port) {
-                    Some((local_port, PortState::Connecting { response_tx })) => {
-                        let (sender, receiver) = self.create_port(local_port, server_port);
+                    Some((local_port, PortState::Connecting { response_tx, priority })) => {
+                        let (sender, receiver) = self.create_port(local_port, server_port, priority);
                         let _ = response_tx.send(ConnectResponse::Accepted(sender, receiver));
                     }
                     _ => {
@@ -930,7 +1682,7 @@ where
 
             // Port open rejected response from remote endpoint.
             MultiplexMsg::Rejected { client_port, no_ports } => match self.ports.remove(&client_port) {
-                Some(PortState::Connecting { response_tx }) => {
+                Some(PortState::Connecting { response_tx, .. }) => {
                     let _ = response_tx.send(ConnectResponse::Rejected { no_ports });
                 }
                 _ => {
@@ -941,14 +1693,16 @@ where
             },
 
             // Data from remote endpoint.
-            MultiplexMsg::Data { port, first, last } => {
+            MultiplexMsg::Data { port, first, last, .. } => {
                 if let Some(PortState::Connected {
+                    counters,
                     receiver_tx_data: Some(receiver_tx_data),
                     receiver_credit_monitor,
                     ..
                 }) = self.ports.get_mut(&port)
                 {
                     let data = data.unwrap();
+                    counters.received(data.len());
                     let used_credit = match u32::try_from(data.len()) {
                         Ok(size) if size <= self.local_cfg.chunk_size => {
                             receiver_credit_monitor.use_credits(size.max(1))?
@@ -976,22 +1730,43 @@ where
 
             // Ports from remote endpoint.
             MultiplexMsg::PortData { port, first, last, wait, ports, ids } => {
-                if let Some(PortState::Connected {
-                    receiver_tx_data: Some(receiver_tx_data),
-                    receiver_credit_monitor,
-                    ..
-                }) = self.ports.get_mut(&port)
-                {
+                if let Some(PortState::Connected { receiver_tx_data: Some(_), .. }) = self.ports.get(&port) {
                     for port in &ports {
-                        if !self.outstanding_remote_port_requests.insert(*port) {
+                        if self.outstanding_remote_port_requests.contains(port)
+                            || self.quota_rejects.contains(port)
+                        {
                             return Err(protocol_err(format!(
                                 "remote endpoint sent PortData request for same remote port {port} twice"
                             )));
                         }
                     }
 
+                    // When a quota is exceeded all port requests of this message are rejected and
+                    // the receiver is notified which quota was exceeded.
+                    let n_ports = ports.len();
+                    let (ports, quota_exceeded) = match self.check_quotas(ports.len()) {
+                        Ok(()) => (ports, None),
+                        Err(quota) => {
+                            self.quota_exceeded(quota, ports)?;
+                            (Vec::new(), Some(quota))
+                        }
+                    };
+
+                    // Re-borrow port state, since checking quotas required access to self.
+                    let Some(PortState::Connected {
+                        priority,
+                        receiver_tx_data: Some(receiver_tx_data),
+                        receiver_credit_monitor,
+                        ..
+                    }) = self.ports.get_mut(&port)
+                    else {
+                        unreachable!()
+                    };
+
+                    self.outstanding_remote_port_requests.extend(ports.iter().copied());
+
                     let used_credit =
-                        match ports.len().checked_mul(size_of::<u32>()).and_then(|v| u32::try_from(v).ok()) {
+                        match n_ports.checked_mul(size_of::<u32>()).and_then(|v| u32::try_from(v).ok()) {
                             Some(size) if size <= self.local_cfg.chunk_size => {
                                 receiver_credit_monitor.use_credits(size)?
                             }
@@ -1003,6 +1778,8 @@ where
                             }
                         };
 
+                    let priority = *priority;
+                    let peer_identity = self.peer_info.identity().cloned();
                     let port_allocator = self.port_allocator.clone();
                     let channel_tx = self.channel_tx.clone();
                     let ids = ids.unwrap_or_else(|| ports.clone());
@@ -1010,7 +1787,16 @@ where
                         .into_iter()
                         .zip(ids)
                         .map(|(remote_port, id)| {
-                            Request::new(remote_port, id, wait, port_allocator.clone(), channel_tx.clone())
+                            Request::new(
+                                remote_port,
+                                id,
+                                wait,
+                                priority,
+                                peer_identity.clone(),
+                                port_allocator.clone(),
+                                channel_tx.clone(),
+                                &self.helper_tasks,
+                            )
                         })
                         .collect();
                     let _ = receiver_tx_data.send(PortReceiveMsg::PortRequests(ReceivedPortRequests {
@@ -1018,6 +1804,7 @@ where
                         first,
                         last,
                         credit: used_credit,
+                        quota_exceeded,
                     }));
                 } else {
                     return Err(protocol_err(format!(
@@ -1174,6 +1961,7 @@ where
 impl<TransportSink, TransportStream> Drop for ChMux<TransportSink, TransportStream> {
     fn drop(&mut self) {
         // Should be present to ensure correct drop order.
+        self.stats.clear_ports();
     }
 }
 
@@ -0,0 +1,142 @@
+//! Information about the remote endpoint of a connection.
+
+use bytes::Bytes;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use super::PeerIdentity;
+
+/// Address and metadata provided by the application.
+#[derive(Debug, Default)]
+struct Provided {
+    addr: Option<SocketAddr>,
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    identity: Option<PeerIdentity>,
+    trace_context: bool,
+    certificates: OnceLock<Vec<Bytes>>,
+    public_key: OnceLock<Bytes>,
+    provided: Mutex<Provided>,
+}
+
+/// Information about the remote endpoint of a connection.
+///
+/// This consists of the [identity](PeerIdentity) established by the [handshake](super::Handshake),
+/// the certificate chain, public key and network address of the remote endpoint and custom metadata.
+/// The certificate chain is provided when connecting over TLS (requires the `tls` feature)
+/// and the public key when connecting using the Noise protocol (requires the `noise` feature).
+/// Since the multiplexer is independent of the underlying transport, the address and metadata
+/// must be provided by the application, usually right after the connection has been established.
+///
+/// Cloning is cheap and clones share the address and metadata.
+#[derive(Clone, Default)]
+pub struct PeerInfo(Arc<Inner>);
+
+impl fmt::Debug for PeerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let provided = self.0.provided.lock().unwrap();
+        f.debug_struct("PeerInfo")
+            .field("identity", &self.0.identity)
+            .field("trace_context", &self.0.trace_context)
+            .field("certificates", &self.certificates().len())
+            .field("public_key", &self.public_key())
+            .field("addr", &provided.addr)
+            .field("metadata", &provided.metadata)
+            .finish()
+    }
+}
+
+impl PeerInfo {
+    /// Creates peer information for the specified identity.
+    pub(crate) fn new(identity: Option<PeerIdentity>, trace_context: bool) -> Self {
+        Self(Arc::new(Inner {
+            identity,
+            trace_context,
+            certificates: OnceLock::new(),
+            public_key: OnceLock::new(),
+            provided: Default::default(),
+        }))
+    }
+
+    /// Identity of the remote endpoint established by the handshake.
+    ///
+    /// Returns `None` if no handshake was performed.
+    pub fn identity(&self) -> Option<&PeerIdentity> {
+        self.0.identity.as_ref()
+    }
+
+    /// Whether span contexts are transmitted to and from the remote endpoint.
+    ///
+    /// This is the case if both endpoints have enabled the `trace-context` crate feature.
+    /// See the `trace` module for details.
+    pub fn trace_context(&self) -> bool {
+        self.0.trace_context
+    }
+
+    /// Network address of the remote endpoint.
+    ///
+    /// Returns `None` if it has not been [set](Self::set_addr).
+    pub fn addr(&self) -> Option<SocketAddr> {
+        self.0.provided.lock().unwrap().addr
+    }
+
+    /// Sets the network address of the remote endpoint.
+    pub fn set_addr(&self, addr: SocketAddr) {
+        self.0.provided.lock().unwrap().addr = Some(addr);
+    }
+
+    /// DER-encoded certificate chain presented by the remote endpoint, starting with its own certificate.
+    ///
+    /// Returns an empty chain if the connection has not been established over TLS
+    /// or the remote endpoint presented no certificate.
+    pub fn certificates(&self) -> Vec<Bytes> {
+        self.0.certificates.get().cloned().unwrap_or_default()
+    }
+
+    /// Sets the DER-encoded certificate chain presented by the remote endpoint.
+    ///
+    /// This is done by the transport before the connection is handed to the application
+    /// and has no effect if the certificate chain has already been set.
+    #[cfg_attr(not(feature = "tls"), allow(dead_code))]
+    pub(crate) fn set_certificates(&self, certificates: Vec<Bytes>) {
+        let _ = self.0.certificates.set(certificates);
+    }
+
+    /// Static public key of the remote endpoint, authenticated by the secure channel.
+    ///
+    /// Returns `None` if the connection has not been established using the Noise protocol.
+    pub fn public_key(&self) -> Option<Bytes> {
+        self.0.public_key.get().cloned()
+    }
+
+    /// Sets the static public key of the remote endpoint.
+    ///
+    /// This is done by the transport before the connection is handed to the application
+    /// and has no effect if the public key has already been set.
+    #[cfg_attr(not(feature = "noise"), allow(dead_code))]
+    pub(crate) fn set_public_key(&self, public_key: Bytes) {
+        let _ = self.0.public_key.set(public_key);
+    }
+
+    /// Returns the metadata value for the specified key.
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        self.0.provided.lock().unwrap().metadata.get(key).cloned()
+    }
+
+    /// Sets the metadata value for the specified key.
+    pub fn set_metadata(&self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.provided.lock().unwrap().metadata.insert(key.into(), value.into());
+    }
+
+    /// All metadata.
+    pub fn all_metadata(&self) -> HashMap<String, String> {
+        self.0.provided.lock().unwrap().metadata.clone()
+    }
+}
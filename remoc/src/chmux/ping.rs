@@ -0,0 +1,165 @@
+//! Round-trip time measurement using pings.
+
+use std::{cmp::Ordering, collections::HashMap, mem, sync::Mutex, time::Duration};
+use tokio::sync::{Notify, oneshot};
+
+use super::{client::PingError, msg::MultiplexMsg, stats::Stats};
+use crate::exec::time::Instant;
+
+/// Request for an explicit ping, answered with the round-trip time.
+pub(crate) type PingRequest = oneshot::Sender<Result<Duration, PingError>>;
+
+/// Maximum number of pings awaiting a pong.
+///
+/// When exceeded, the oldest ping is forgotten.
+const MAX_OUTSTANDING: usize = 64;
+
+/// Maximum number of pongs waiting to be sent.
+///
+/// When exceeded, further pings of the remote endpoint are not answered.
+const MAX_PONGS: usize = 64;
+
+/// A ping that has not been answered yet.
+struct Outstanding {
+    sent: Instant,
+    requests: Vec<PingRequest>,
+}
+
+#[derive(Default)]
+struct PingerInner {
+    next_nonce: u64,
+    outstanding: HashMap<u64, Outstanding>,
+    /// Requests whose ping was lost due to a transport failure.
+    lost: Vec<PingRequest>,
+    /// Nonces of received pings that must be answered.
+    pongs: Vec<u64>,
+}
+
+/// Tracks pings sent to the remote endpoint and pongs that must be sent to it.
+///
+/// Shared between the send and receive task of the transport.
+pub(crate) struct Pinger {
+    /// Remote endpoint answers pings.
+    remote_pong: bool,
+    /// Time after which an unanswered ping is forgotten.
+    expiry: Duration,
+    stats: Stats,
+    inner: Mutex<PingerInner>,
+    pong_required: Notify,
+}
+
+impl Pinger {
+    /// Creates a new pinger.
+    ///
+    /// Pings that are not answered within `expiry` are forgotten.
+    pub fn new(remote_pong: bool, expiry: Duration, stats: Stats) -> Self {
+        Self {
+            remote_pong,
+            expiry,
+            stats,
+            inner: Mutex::new(PingerInner::default()),
+            pong_required: Notify::new(),
+        }
+    }
+
+    /// Whether pings are answered by the remote endpoint.
+    pub fn remote_pong(&self) -> bool {
+        self.remote_pong
+    }
+
+    /// Creates a ping message.
+    ///
+    /// If the remote endpoint answers pings, the ping is assigned a nonce and
+    /// the specified requests are answered when its pong is received.
+    /// Expired pings are forgotten and their requests fail with [PingError::Timeout].
+    pub fn ping(&self, requests: Vec<PingRequest>) -> MultiplexMsg {
+        if !self.remote_pong {
+            return MultiplexMsg::Ping { nonce: None };
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let mut expired: Vec<u64> = inner
+            .outstanding
+            .iter()
+            .filter(|(_, outstanding)| outstanding.sent.elapsed() > self.expiry)
+            .map(|(nonce, _)| *nonce)
+            .collect();
+        if inner.outstanding.len() - expired.len() >= MAX_OUTSTANDING
+            && let Some(oldest) = inner
+                .outstanding
+                .iter()
+                .filter(|(nonce, _)| !expired.contains(nonce))
+                .min_by(|(_, a), (_, b)| a.sent.partial_cmp(&b.sent).unwrap_or(Ordering::Equal))
+                .map(|(nonce, _)| *nonce)
+        {
+            expired.push(oldest);
+        }
+        for nonce in expired {
+            tracing::debug!(nonce, "forgetting unanswered ping");
+            let outstanding = inner.outstanding.remove(&nonce).unwrap();
+            for req in outstanding.requests {
+                let _ = req.send(Err(PingError::Timeout));
+            }
+        }
+
+        let nonce = inner.next_nonce;
+        inner.next_nonce = inner.next_nonce.wrapping_add(1);
+        inner.outstanding.insert(nonce, Outstanding { sent: Instant::now(), requests });
+        MultiplexMsg::Ping { nonce: Some(nonce) }
+    }
+
+    /// Takes the requests whose ping was lost, so that they can be sent again.
+    pub fn take_lost(&self) -> Vec<PingRequest> {
+        mem::take(&mut self.inner.lock().unwrap().lost)
+    }
+
+    /// Processes a ping received from the remote endpoint.
+    pub fn ping_received(&self, nonce: Option<u64>) {
+        if let Some(nonce) = nonce {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.pongs.len() >= MAX_PONGS {
+                tracing::debug!(nonce, "too many pongs pending, not answering ping");
+                return;
+            }
+            inner.pongs.push(nonce);
+            self.pong_required.notify_one();
+        }
+    }
+
+    /// Waits until pongs must be sent and returns them.
+    pub async fn pongs(&self) -> Vec<MultiplexMsg> {
+        loop {
+            let pongs = mem::take(&mut self.inner.lock().unwrap().pongs);
+            if !pongs.is_empty() {
+                return pongs.into_iter().map(|nonce| MultiplexMsg::Pong { nonce }).collect();
+            }
+            self.pong_required.notified().await;
+        }
+    }
+
+    /// Processes a pong received from the remote endpoint.
+    ///
+    /// Pongs for unknown pings are ignored.
+    pub fn pong_received(&self, nonce: u64) {
+        let Some(Outstanding { sent, requests }) = self.inner.lock().unwrap().outstanding.remove(&nonce) else {
+            tracing::debug!(nonce, "received pong for unknown ping");
+            return;
+        };
+
+        let rtt = sent.elapsed();
+        self.stats.set_ping_rtt(rtt);
+        for req in requests {
+            let _ = req.send(Ok(rtt));
+        }
+    }
+
+    /// Forgets outstanding pings and pongs, since the transport has failed.
+    ///
+    /// Requests of outstanding pings are kept for sending again.
+    pub fn transport_lost(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let outstanding = mem::take(&mut inner.outstanding);
+        inner.lost.extend(outstanding.into_values().flat_map(|outstanding| outstanding.requests));
+        inner.pongs.clear();
+    }
+}
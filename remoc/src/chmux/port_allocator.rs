@@ -9,6 +9,8 @@ use std::{
 };
 use tokio::sync::oneshot;
 
+use super::Priority;
+
 struct PortAllocatorInner {
     used: HashSet<u32>,
     limit: u32,
@@ -172,13 +174,15 @@ pub struct PortReq {
     pub port: PortNumber,
     /// A user-specified id.
     pub id: u32,
+    /// Send priority of the port.
+    pub priority: Priority,
 }
 
 impl From<PortNumber> for PortReq {
     /// Create a new port connection request with [`id`](Self::id) set to
     /// the [port number](Self::port).
     fn from(port: PortNumber) -> Self {
-        Self { id: port.number, port }
+        Self { id: port.number, port, priority: Priority::default() }
     }
 }
 
@@ -200,4 +204,10 @@ impl PortReq {
         self.id = id;
         self
     }
+
+    /// Sets the send priority of the port.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
 }
@@ -0,0 +1,63 @@
+//! Send priority of ports.
+
+/// Send priority of a port.
+///
+/// The multiplexer transmits queued data of ports with a higher priority before
+/// queued data of ports with a lower priority.
+/// Data of ports with the same priority is transmitted in the order it was queued.
+///
+/// The priority only affects the local endpoint, i.e. it is not transmitted to
+/// the remote endpoint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Priority {
+    /// Low priority, for example for bulk data transfers.
+    Low,
+    /// Normal priority.
+    #[default]
+    Normal,
+    /// High priority, for example for latency-sensitive requests.
+    High,
+}
+
+impl Priority {
+    /// All priorities ordered from lowest to highest.
+    pub const ALL: [Priority; 3] = [Priority::Low, Priority::Normal, Priority::High];
+
+    /// Index of priority in [ALL](Self::ALL).
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Send priorities of kinds of [remote channels](crate::rch).
+///
+/// A channel kind without a priority inherits the priority of the
+/// channel it was sent over.
+/// By default no priorities are set.
+///
+/// The priorities apply to channels that are sent or received over
+/// the connection they are configured for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelPriorities {
+    /// Priority of [binary channels](crate::rch::bin).
+    ///
+    /// These are also used by [IO channels](crate::rch::io) and [lazy blobs](crate::robj::lazy_blob).
+    pub bin: Option<Priority>,
+    /// Priority of [local/remote channels](crate::rch::lr).
+    pub lr: Option<Priority>,
+    /// Priority of [multi producer, single consumer channels](crate::rch::mpsc).
+    ///
+    /// These are also used by [oneshot](crate::rch::oneshot) and [broadcast](crate::rch::broadcast) channels
+    /// and [remote functions](crate::rfn).
+    pub mpsc: Option<Priority>,
+    /// Priority of [watch channels](crate::rch::watch).
+    pub watch: Option<Priority>,
+    /// Priority of request channels of [remote trait clients](crate::rtc).
+    ///
+    /// If unset, the priority of [mpsc channels](Self::mpsc) applies.
+    pub rtc: Option<Priority>,
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
@@ -0,0 +1,99 @@
+//! Per-connection resource quotas.
+
+use futures::Future;
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::exec;
+
+/// A resource whose usage can be limited per connection.
+///
+/// The limits are configured in the [multiplexer configuration](super::Cfg).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quota {
+    /// Upper bound of the total size of the receive buffers of all open ports,
+    /// configured by [Cfg::max_receive_buffer_memory](super::Cfg::max_receive_buffer_memory).
+    ReceiveBufferMemory,
+    /// Number of running helper tasks,
+    /// configured by [Cfg::max_helper_tasks](super::Cfg::max_helper_tasks).
+    HelperTasks,
+    /// Number of port requests from the remote endpoint that have been neither accepted nor rejected,
+    /// configured by [Cfg::max_pending_port_requests](super::Cfg::max_pending_port_requests).
+    PendingPortRequests,
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ReceiveBufferMemory => write!(f, "receive buffer memory"),
+            Self::HelperTasks => write!(f, "helper tasks"),
+            Self::PendingPortRequests => write!(f, "pending port requests"),
+        }
+    }
+}
+
+/// Behavior when the remote endpoint requests a port that would exceed a [quota](Quota).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuotaPolicy {
+    /// Reject the port request.
+    ///
+    /// When the request is part of data sent over a port, all port requests of that
+    /// data message are rejected and receiving it fails with
+    /// [RecvError::QuotaExceeded](super::RecvError::QuotaExceeded).
+    #[default]
+    Reject,
+    /// Terminate the connection with [ChMuxError::QuotaExceeded](super::ChMuxError::QuotaExceeded).
+    Terminate,
+}
+
+/// Counter of running helper tasks spawned on behalf of the remote endpoint.
+#[derive(Clone, Default)]
+pub(crate) struct HelperTasks(Arc<AtomicUsize>);
+
+impl fmt::Debug for HelperTasks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("HelperTasks").field(&self.count()).finish()
+    }
+}
+
+impl HelperTasks {
+    /// Number of running helper tasks.
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Spawns a helper task that is counted while it is running.
+    pub fn spawn<F>(&self, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let guard = HelperTaskGuard::new(self.0.clone());
+        exec::spawn(async move {
+            let _guard = guard;
+            fut.await
+        });
+    }
+}
+
+/// Decrements the helper task count when dropped.
+struct HelperTaskGuard(Arc<AtomicUsize>);
+
+impl HelperTaskGuard {
+    fn new(count: Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::Relaxed);
+        Self(count)
+    }
+}
+
+impl Drop for HelperTaskGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -9,10 +9,11 @@ use tokio::sync::{mpsc, oneshot};
 use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
-    AnyStorage, ForwardError, PortAllocator, Request, Sender,
+    AnyStorage, ChannelPriorities, ForwardError, PeerInfo, PortAllocator, Priority, Request, Sender,
     credit::{ChannelCreditReturner, UsedCredit},
     forward,
     mux::PortEvt,
+    quota::{HelperTasks, Quota},
 };
 use crate::exec;
 
@@ -26,6 +27,9 @@ pub enum RecvError {
     ExceedsMaxDataSize(usize),
     /// Received ports exceed maximum count.
     ExceedsMaxPortCount(usize),
+    /// Received ports were rejected, since they would have exceeded a [quota](Quota)
+    /// of the local endpoint.
+    QuotaExceeded(Quota),
 }
 
 impl RecvError {
@@ -50,6 +54,7 @@ impl fmt::Display for RecvError {
             Self::ExceedsMaxPortCount(max_count) => {
                 write!(f, "port message exceeds maximum allowed count of {max_count} ports")
             }
+            Self::QuotaExceeded(quota) => write!(f, "received ports rejected since quota for {quota} exceeded"),
         }
     }
 }
@@ -63,6 +68,7 @@ impl From<RecvError> for std::io::Error {
             RecvError::ChMux => Self::new(ErrorKind::ConnectionReset, err.to_string()),
             RecvError::ExceedsMaxDataSize(_) => Self::new(ErrorKind::InvalidData, err.to_string()),
             RecvError::ExceedsMaxPortCount(_) => Self::new(ErrorKind::InvalidData, err.to_string()),
+            RecvError::QuotaExceeded(_) => Self::new(ErrorKind::QuotaExceeded, err.to_string()),
         }
     }
 }
@@ -140,6 +146,8 @@ pub(crate) struct ReceivedPortRequests {
     pub last: bool,
     /// Flow-control credit.
     pub credit: UsedCredit,
+    /// Quota that was exceeded, causing all port requests of this chunk to be rejected.
+    pub quota_exceeded: Option<Quota>,
 }
 
 /// Port receive message.
@@ -287,6 +295,7 @@ pub struct Receiver {
     remote_port: u32,
     max_data_size: usize,
     max_ports: usize,
+    priority: Priority,
     tx: mpsc::Sender<PortEvt>,
     rx: mpsc::UnboundedReceiver<PortReceiveMsg>,
     receiving: Receiving,
@@ -295,6 +304,9 @@ pub struct Receiver {
     finished: bool,
     port_allocator: PortAllocator,
     storage: AnyStorage,
+    channel_priorities: ChannelPriorities,
+    peer_info: PeerInfo,
+    helper_tasks: HelperTasks,
     _drop_tx: oneshot::Sender<()>,
 }
 
@@ -305,6 +317,7 @@ impl fmt::Debug for Receiver {
             .field("remote_port", &self.remote_port)
             .field("max_data_size", &self.max_data_size)
             .field("max_ports", &self.max_ports)
+            .field("priority", &self.priority)
             .field("closed", &self.closed)
             .field("finished", &self.finished)
             .finish()
@@ -314,9 +327,10 @@ impl fmt::Debug for Receiver {
 impl Receiver {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        local_port: u32, remote_port: u32, max_data_size: usize, max_port_count: usize,
+        local_port: u32, remote_port: u32, max_data_size: usize, max_port_count: usize, priority: Priority,
         tx: mpsc::Sender<PortEvt>, rx: mpsc::UnboundedReceiver<PortReceiveMsg>, credits: ChannelCreditReturner,
-        port_allocator: PortAllocator, storage: AnyStorage,
+        port_allocator: PortAllocator, storage: AnyStorage, channel_priorities: ChannelPriorities,
+        peer_info: PeerInfo, helper_tasks: HelperTasks,
     ) -> Self {
         let (_drop_tx, drop_rx) = oneshot::channel();
         let tx_drop = tx.clone();
@@ -330,6 +344,7 @@ impl Receiver {
             remote_port,
             max_data_size,
             max_ports: max_port_count,
+            priority,
             tx,
             rx,
             receiving: Receiving::Nothing,
@@ -338,6 +353,9 @@ impl Receiver {
             finished: false,
             port_allocator,
             storage,
+            channel_priorities,
+            peer_info,
+            helper_tasks,
             _drop_tx,
         }
     }
@@ -352,6 +370,13 @@ impl Receiver {
         self.remote_port
     }
 
+    /// Send priority of the port.
+    ///
+    /// Port requests received over this port inherit this priority by default.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
     /// Maximum data size in bytes to receive per message.
     ///
     /// The default value is specified by [Cfg::max_data_size](super::Cfg::max_data_size).
@@ -523,6 +548,10 @@ impl Receiver {
                     }
 
                     if let Receiving::Requests(mut requests) = mem::take(&mut self.receiving) {
+                        if let Some(quota) = req.quota_exceeded {
+                            return Err(RecvError::QuotaExceeded(quota));
+                        }
+
                         requests.extend(req.requests);
 
                         if requests.len() > self.max_ports {
@@ -574,6 +603,21 @@ impl Receiver {
         self.storage.clone()
     }
 
+    /// Returns the send priorities of remote channel kinds configured for the channel multiplexer.
+    pub fn channel_priorities(&self) -> ChannelPriorities {
+        self.channel_priorities
+    }
+
+    /// Counter of helper tasks spawned on behalf of the remote endpoint.
+    pub(crate) fn helper_tasks(&self) -> &HelperTasks {
+        &self.helper_tasks
+    }
+
+    /// Returns information about the remote endpoint of the channel multiplexer.
+    pub fn peer_info(&self) -> PeerInfo {
+        self.peer_info.clone()
+    }
+
     /// Forwards all data received to the specified sender.
     ///
     /// This also recursively spawns background tasks for forwarding data on received ports.
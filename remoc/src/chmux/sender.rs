@@ -18,7 +18,7 @@ use tokio::sync::{Mutex, mpsc, oneshot};
 use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
-    AnyStorage, Connect, ConnectError, PortAllocator, PortReq,
+    AnyStorage, ChannelPriorities, Connect, ConnectError, PortAllocator, PortReq, Priority,
     client::ConnectResponse,
     credit::{AssignedCredits, CreditUser},
     mux::PortEvt,
@@ -193,12 +193,14 @@ pub struct Sender {
     remote_port: u32,
     chunk_size: usize,
     max_data_size: usize,
+    priority: Priority,
     tx: mpsc::Sender<PortEvt>,
     credits: CreditUser,
     hangup_recved: Weak<AtomicBool>,
     hangup_notify: Weak<std::sync::Mutex<Option<Vec<oneshot::Sender<()>>>>>,
     port_allocator: PortAllocator,
     storage: AnyStorage,
+    channel_priorities: ChannelPriorities,
     _drop_tx: oneshot::Sender<()>,
 }
 
@@ -209,6 +211,7 @@ impl fmt::Debug for Sender {
             .field("remote_port", &self.remote_port)
             .field("chunk_size", &self.chunk_size)
             .field("max_data_size", &self.max_data_size)
+            .field("priority", &self.priority)
             .field("is_closed", &self.is_closed())
             .finish()
     }
@@ -218,10 +221,10 @@ impl Sender {
     /// Create a new sender.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        local_port: u32, remote_port: u32, chunk_size: usize, max_data_size: usize, tx: mpsc::Sender<PortEvt>,
-        credits: CreditUser, hangup_recved: Weak<AtomicBool>,
+        local_port: u32, remote_port: u32, chunk_size: usize, max_data_size: usize, priority: Priority,
+        tx: mpsc::Sender<PortEvt>, credits: CreditUser, hangup_recved: Weak<AtomicBool>,
         hangup_notify: Weak<std::sync::Mutex<Option<Vec<oneshot::Sender<()>>>>>, port_allocator: PortAllocator,
-        storage: AnyStorage,
+        storage: AnyStorage, channel_priorities: ChannelPriorities,
     ) -> Self {
         let (_drop_tx, drop_rx) = oneshot::channel();
         let tx_drop = tx.clone();
@@ -235,12 +238,14 @@ impl Sender {
             remote_port,
             chunk_size,
             max_data_size,
+            priority,
             tx,
             credits,
             hangup_recved,
             hangup_notify,
             port_allocator,
             storage,
+            channel_priorities,
             _drop_tx,
         }
     }
@@ -270,6 +275,13 @@ impl Sender {
         self.max_data_size
     }
 
+    /// Send priority of the port.
+    ///
+    /// Ports connected by sending them over this port inherit this priority by default.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
     /// Sends data over the channel.
     ///
     /// Waits until send space becomes available.
@@ -282,7 +294,13 @@ impl Sender {
             let mut credits = self.credits.request(1, 1).await?;
             credits.take(1);
 
-            let msg = PortEvt::SendData { remote_port: self.remote_port, data, first: true, last: true };
+            let msg = PortEvt::SendData {
+                local_port: self.local_port,
+                remote_port: self.remote_port,
+                data,
+                first: true,
+                last: true,
+            };
             self.tx.send(msg).await?;
         } else {
             let mut first = true;
@@ -299,6 +317,7 @@ impl Sender {
                 credits.take(chunk.len() as u32);
 
                 let msg = PortEvt::SendData {
+                    local_port: self.local_port,
                     remote_port: self.remote_port,
                     data: chunk,
                     first,
@@ -330,7 +349,13 @@ impl Sender {
             match self.credits.try_request(1)? {
                 Some(mut credits) => {
                     credits.take(1);
-                    let msg = PortEvt::SendData { remote_port: self.remote_port, data, first: true, last: true };
+                    let msg = PortEvt::SendData {
+                        local_port: self.local_port,
+                        remote_port: self.remote_port,
+                        data,
+                        first: true,
+                        last: true,
+                    };
                     self.tx.try_send(msg)?;
                     Ok(())
                 }
@@ -347,6 +372,7 @@ impl Sender {
                         credits.take(chunk.len() as u32);
 
                         let msg = PortEvt::SendData {
+                            local_port: self.local_port,
                             remote_port: self.remote_port,
                             data: chunk,
                             first,
@@ -470,6 +496,11 @@ impl Sender {
     pub fn storage(&self) -> AnyStorage {
         self.storage.clone()
     }
+
+    /// Returns the send priorities of remote channel kinds configured for the channel multiplexer.
+    pub fn channel_priorities(&self) -> ChannelPriorities {
+        self.channel_priorities
+    }
 }
 
 impl Drop for Sender {
@@ -496,8 +527,13 @@ impl<'a> ChunkSender<'a> {
             }
             self.credits.take(1);
 
-            let msg =
-                PortEvt::SendData { remote_port: self.sender.remote_port, data, first: self.first, last: finish };
+            let msg = PortEvt::SendData {
+                local_port: self.sender.local_port,
+                remote_port: self.sender.remote_port,
+                data,
+                first: self.first,
+                last: finish,
+            };
             self.sender.tx.send(msg).await?;
 
             self.first = false;
@@ -514,6 +550,7 @@ impl<'a> ChunkSender<'a> {
                 self.credits.take(chunk.len() as u32);
 
                 let msg = PortEvt::SendData {
+                    local_port: self.sender.local_port,
                     remote_port: self.sender.remote_port,
                     data: chunk,
                     first: self.first,
@@ -0,0 +1,178 @@
+//! Session resumption.
+
+use byteorder::{LE, ReadBytesExt, WriteBytesExt};
+use bytes::Bytes;
+use std::{collections::VecDeque, io, sync::Mutex};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Number of received messages after which an acknowledgement is sent.
+const ACK_INTERVAL: u64 = 64;
+
+/// Resume flag of session Hello extension.
+const SESSION_FLAG_RESUME: u8 = 0b0000_0001;
+
+/// Session information exchanged within the Hello message.
+#[derive(Clone, Debug)]
+pub struct SessionHello {
+    /// Session id of sending endpoint.
+    pub id: Uuid,
+    /// Sending endpoint wants to resume an existing session.
+    pub resume: bool,
+    /// Number of messages received by sending endpoint since start of session.
+    pub received: u64,
+}
+
+impl SessionHello {
+    /// Hello for a new session.
+    pub(crate) fn new() -> Self {
+        Self { id: Uuid::new_v4(), resume: false, received: 0 }
+    }
+
+    pub(crate) fn write(&self, mut writer: impl io::Write) -> Result<(), io::Error> {
+        writer.write_u128::<LE>(self.id.as_u128())?;
+        writer.write_u8(if self.resume { SESSION_FLAG_RESUME } else { 0 })?;
+        writer.write_u64::<LE>(self.received)?;
+        Ok(())
+    }
+
+    pub(crate) fn read(mut reader: impl io::Read) -> Result<Self, io::Error> {
+        Ok(Self {
+            id: Uuid::from_u128(reader.read_u128::<LE>()?),
+            resume: reader.read_u8()? & SESSION_FLAG_RESUME != 0,
+            received: reader.read_u64::<LE>()?,
+        })
+    }
+}
+
+/// Encoded message frame with optionally associated data frame.
+pub(crate) type Frames = (Bytes, Option<Bytes>);
+
+/// A resumable session.
+pub(crate) struct Session {
+    /// Our session id.
+    local_id: Uuid,
+    /// Maximum number of unacknowledged sent messages.
+    max_unacked: usize,
+    /// Session id of remote endpoint.
+    remote_id: Uuid,
+    /// Mutable state.
+    state: Mutex<SessionState>,
+    /// Notification that an acknowledgement should be sent.
+    ack_notify: Notify,
+}
+
+/// Mutable session state.
+#[derive(Default)]
+struct SessionState {
+    /// Number of messages sent since start of session.
+    sent: u64,
+    /// Sent messages that have not yet been acknowledged by the remote endpoint.
+    unacked: VecDeque<Frames>,
+    /// Number of messages received since start of session.
+    received: u64,
+    /// Number of received messages that have been acknowledged to the remote endpoint.
+    acked: u64,
+}
+
+impl SessionState {
+    /// Removes messages acknowledged by the remote endpoint from the replay buffer.
+    ///
+    /// Returns false if the acknowledgement is invalid.
+    fn acknowledge(&mut self, remote_received: u64) -> bool {
+        let oldest = self.sent - self.unacked.len() as u64;
+        if remote_received > self.sent {
+            return false;
+        }
+
+        let n = remote_received.saturating_sub(oldest) as usize;
+        self.unacked.drain(..n);
+        true
+    }
+}
+
+impl Session {
+    /// Creates a new session.
+    pub(crate) fn new(local_id: Uuid, remote_id: Uuid, max_unacked: usize) -> Self {
+        Self {
+            local_id,
+            remote_id,
+            max_unacked,
+            state: Mutex::new(SessionState::default()),
+            ack_notify: Notify::new(),
+        }
+    }
+
+    /// Session id, which is the same on both endpoints.
+    pub(crate) fn id(&self) -> Uuid {
+        Uuid::from_u128(self.local_id.as_u128() ^ self.remote_id.as_u128())
+    }
+
+    /// Session id of remote endpoint.
+    pub(crate) fn remote_id(&self) -> Uuid {
+        self.remote_id
+    }
+
+    /// Hello for resuming this session.
+    ///
+    /// This acknowledges all messages received so far.
+    pub(crate) fn hello(&self) -> SessionHello {
+        let mut state = self.state.lock().unwrap();
+        state.acked = state.received;
+        SessionHello { id: self.local_id, resume: true, received: state.received }
+    }
+
+    /// Records a message sent to the remote endpoint.
+    ///
+    /// Returns false if too many sent messages have not been acknowledged.
+    pub(crate) fn sent(&self, frames: Frames) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.unacked.len() >= self.max_unacked {
+            return false;
+        }
+        state.sent += 1;
+        state.unacked.push_back(frames);
+        true
+    }
+
+    /// Records a message received from the remote endpoint.
+    pub(crate) fn received(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.received += 1;
+        if state.received - state.acked >= ACK_INTERVAL {
+            self.ack_notify.notify_one();
+        }
+    }
+
+    /// Waits until enough messages have been received to warrant an acknowledgement.
+    pub(crate) async fn ack_required(&self) {
+        self.ack_notify.notified().await
+    }
+
+    /// Returns the number of received messages and marks them as acknowledged.
+    pub(crate) fn ack(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.acked = state.received;
+        state.received
+    }
+
+    /// Processes an acknowledgement from the remote endpoint.
+    ///
+    /// Returns false if the acknowledgement is invalid.
+    pub(crate) fn acknowledged(&self, remote_received: u64) -> bool {
+        self.state.lock().unwrap().acknowledge(remote_received)
+    }
+
+    /// Returns the messages that must be resent to the remote endpoint for
+    /// resuming the session.
+    ///
+    /// Returns `None` if messages required by the remote endpoint are not available anymore.
+    pub(crate) fn resume(&self, remote_received: u64) -> Option<Vec<Frames>> {
+        let mut state = self.state.lock().unwrap();
+        let oldest = state.sent - state.unacked.len() as u64;
+        if remote_received < oldest || !state.acknowledge(remote_received) {
+            return None;
+        }
+        Some(state.unacked.iter().cloned().collect())
+    }
+}
@@ -0,0 +1,241 @@
+//! Connection statistics.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use super::{Priority, quota::HelperTasks};
+use crate::exec::time::Instant;
+
+/// Traffic counters.
+#[derive(Debug, Default)]
+struct Counters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    credit_stalls: AtomicU64,
+}
+
+/// Counters of a connected port.
+///
+/// Updates are also applied to the totals of the multiplexer.
+#[derive(Debug)]
+pub(crate) struct PortCounters {
+    local_port: u32,
+    remote_port: u32,
+    priority: Priority,
+    port: Counters,
+    total: Arc<Counters>,
+}
+
+impl PortCounters {
+    /// Records sent data.
+    pub fn sent(&self, bytes: usize) {
+        self.port.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.total.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Records received data.
+    pub fn received(&self, bytes: usize) {
+        self.port.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.total.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Records that sending had to wait for flow credits from the remote endpoint.
+    pub fn credit_stall(&self) {
+        self.port.credit_stalls.fetch_add(1, Ordering::Relaxed);
+        self.total.credit_stalls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the counters.
+    fn snapshot(&self) -> PortStats {
+        PortStats {
+            local_port: self.local_port,
+            remote_port: self.remote_port,
+            priority: self.priority,
+            bytes_sent: self.port.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.port.bytes_received.load(Ordering::Relaxed),
+            credit_stalls: self.port.credit_stalls.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Statistics of a connected port.
+///
+/// This is a snapshot obtained by [Stats::ports].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct PortStats {
+    /// Local port number.
+    pub local_port: u32,
+    /// Remote port number.
+    pub remote_port: u32,
+    /// Send priority.
+    pub priority: Priority,
+    /// Number of data bytes sent.
+    pub bytes_sent: u64,
+    /// Number of data bytes received.
+    pub bytes_received: u64,
+    /// Number of times sending had to wait for flow credits from the remote endpoint.
+    pub credit_stalls: u64,
+}
+
+struct StatsInner {
+    created: Instant,
+    total: Arc<Counters>,
+    ports: Mutex<HashMap<u32, Arc<PortCounters>>>,
+    transport_send_queue: AtomicUsize,
+    transport_receive_queue: AtomicUsize,
+    ping_rtt: Mutex<Option<Duration>>,
+    helper_tasks: HelperTasks,
+}
+
+/// Statistics of a channel multiplexer.
+///
+/// This is a cloneable handle obtained from [ChMux::stats](super::ChMux::stats) or
+/// [Connect::stats](crate::Connect::stats).
+/// It stays valid after the multiplexer has terminated and can be used to export
+/// the state of a connection to a metrics system.
+///
+/// Byte counts include only data sent or received by [senders](super::Sender) and
+/// [receivers](super::Receiver), but not protocol overhead.
+#[derive(Clone)]
+pub struct Stats(Arc<StatsInner>);
+
+impl fmt::Debug for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Stats")
+            .field("age", &self.age())
+            .field("open_ports", &self.open_ports())
+            .field("bytes_sent", &self.bytes_sent())
+            .field("bytes_received", &self.bytes_received())
+            .field("credit_stalls", &self.credit_stalls())
+            .field("transport_send_queue", &self.transport_send_queue())
+            .field("transport_receive_queue", &self.transport_receive_queue())
+            .field("ping_rtt", &self.ping_rtt())
+            .field("helper_tasks", &self.helper_tasks())
+            .finish()
+    }
+}
+
+impl Stats {
+    /// Creates statistics for a new connection.
+    pub(crate) fn new(helper_tasks: HelperTasks) -> Self {
+        Self(Arc::new(StatsInner {
+            created: Instant::now(),
+            total: Arc::new(Counters::default()),
+            ports: Mutex::new(HashMap::new()),
+            transport_send_queue: AtomicUsize::new(0),
+            transport_receive_queue: AtomicUsize::new(0),
+            ping_rtt: Mutex::new(None),
+            helper_tasks,
+        }))
+    }
+
+    /// Registers a connected port and returns its counters.
+    pub(crate) fn add_port(&self, local_port: u32, remote_port: u32, priority: Priority) -> Arc<PortCounters> {
+        let counters = Arc::new(PortCounters {
+            local_port,
+            remote_port,
+            priority,
+            port: Counters::default(),
+            total: self.0.total.clone(),
+        });
+        self.0.ports.lock().unwrap().insert(local_port, counters.clone());
+        counters
+    }
+
+    /// Removes a released port.
+    pub(crate) fn remove_port(&self, local_port: u32) {
+        self.0.ports.lock().unwrap().remove(&local_port);
+    }
+
+    /// Removes all ports, since the multiplexer has terminated.
+    pub(crate) fn clear_ports(&self) {
+        self.0.ports.lock().unwrap().clear();
+    }
+
+    /// Records the occupancy of the transport queues.
+    pub(crate) fn set_transport_queues(&self, send: usize, receive: usize) {
+        self.0.transport_send_queue.store(send, Ordering::Relaxed);
+        self.0.transport_receive_queue.store(receive, Ordering::Relaxed);
+    }
+
+    /// Records the round-trip time of an answered ping.
+    pub(crate) fn set_ping_rtt(&self, rtt: Duration) {
+        *self.0.ping_rtt.lock().unwrap() = Some(rtt);
+    }
+
+    /// Time since the connection has been established.
+    pub fn age(&self) -> Duration {
+        self.0.created.elapsed()
+    }
+
+    /// Number of connected ports.
+    pub fn open_ports(&self) -> usize {
+        self.0.ports.lock().unwrap().len()
+    }
+
+    /// Snapshot of the connected ports, ordered by local port number.
+    pub fn ports(&self) -> Vec<PortStats> {
+        let mut ports: Vec<_> = self.0.ports.lock().unwrap().values().map(|port| port.snapshot()).collect();
+        ports.sort_unstable_by_key(|port| port.local_port);
+        ports
+    }
+
+    /// Total number of data bytes sent over all ports, including closed ports.
+    pub fn bytes_sent(&self) -> u64 {
+        self.0.total.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Total number of data bytes received over all ports, including closed ports.
+    pub fn bytes_received(&self) -> u64 {
+        self.0.total.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Total number of times sending had to wait for flow credits from the remote endpoint,
+    /// including closed ports.
+    pub fn credit_stalls(&self) -> u64 {
+        self.0.total.credit_stalls.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages waiting in the transport send queues of all priorities.
+    ///
+    /// The queue length is configured by [Cfg::transport_send_queue](super::Cfg::transport_send_queue).
+    /// This is updated whenever the multiplexer processes an event.
+    pub fn transport_send_queue(&self) -> usize {
+        self.0.transport_send_queue.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages waiting in the transport receive queue.
+    ///
+    /// The queue length is configured by
+    /// [Cfg::transport_receive_queue](super::Cfg::transport_receive_queue).
+    /// This is updated whenever the multiplexer processes an event.
+    pub fn transport_receive_queue(&self) -> usize {
+        self.0.transport_receive_queue.load(Ordering::Relaxed)
+    }
+
+    /// Round-trip time of the last answered ping.
+    ///
+    /// Pings are sent in intervals of half the [connection timeout](super::Cfg::connection_timeout)
+    /// of the remote endpoint and by [Client::ping](super::Client::ping).
+    /// This is `None` until the first ping has been answered or if the remote endpoint
+    /// does not support answering pings.
+    pub fn ping_rtt(&self) -> Option<Duration> {
+        *self.0.ping_rtt.lock().unwrap()
+    }
+
+    /// Number of running helper tasks spawned on behalf of the remote endpoint.
+    ///
+    /// This can be limited by [Cfg::max_helper_tasks](super::Cfg::max_helper_tasks).
+    pub fn helper_tasks(&self) -> usize {
+        self.0.helper_tasks.count()
+    }
+}
@@ -1,24 +1,42 @@
 //! Initial connection functions.
 
-use bytes::Bytes;
-use futures::{Future, FutureExt, Sink, Stream, StreamExt, TryStreamExt, future::BoxFuture};
+use bytes::{Bytes, BytesMut};
+use futures::{Future, FutureExt, Sink, Stream, StreamExt, TryStreamExt, future::BoxFuture, stream::MapOk};
 use std::{
-    convert::{Infallible, TryInto},
+    convert::Infallible,
     error::Error,
     fmt, io,
     pin::Pin,
     task::{Context, Poll},
 };
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, BufWriter};
-use tokio_util::codec::LengthDelimitedCodec;
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 
 use crate::{
     RemoteSend,
-    chmux::{ChMux, ChMuxError},
+    chmux::{ChMuxError, PeerIdentity, PeerInfo, Stats},
     codec,
     rch::base,
 };
 
+#[cfg(feature = "noise")]
+mod noise;
+mod options;
+#[cfg(all(feature = "process", not(target_family = "wasm")))]
+mod process;
+#[cfg(all(feature = "quic", not(target_family = "wasm")))]
+mod quic;
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "websocket")]
+mod websocket;
+
+pub use options::{ConnectOptions, IoSecureOption, ReconnectOption, WithHello, WithReconnect};
+#[cfg(all(feature = "process", not(target_family = "wasm")))]
+pub use process::ChildProcess;
+#[cfg(feature = "tls")]
+pub use tls::{TlsClient, TlsServer};
+
 /// Error occurred during establishing a connection over a physical transport.
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 #[derive(Debug, Clone)]
@@ -27,6 +45,8 @@ pub enum ConnectError<TransportSinkError, TransportStreamError> {
     ChMux(ChMuxError<TransportSinkError, TransportStreamError>),
     /// Opening initial [remote](crate::rch::base) channel failed.
     RemoteConnect(base::ConnectError),
+    /// Exchanging the application hello with the remote endpoint failed.
+    Hello(HelloError),
 }
 
 impl<TransportSinkError, TransportStreamError> fmt::Display
@@ -39,6 +59,7 @@ where
         match self {
             Self::ChMux(err) => write!(f, "chmux error: {err}"),
             Self::RemoteConnect(err) => write!(f, "channel connect failed: {err}"),
+            Self::Hello(err) => write!(f, "hello exchange failed: {err}"),
         }
     }
 }
@@ -66,6 +87,42 @@ impl<TransportSinkError, TransportStreamError> From<base::ConnectError>
     }
 }
 
+impl<TransportSinkError, TransportStreamError> From<HelloError>
+    for ConnectError<TransportSinkError, TransportStreamError>
+{
+    fn from(err: HelloError) -> Self {
+        Self::Hello(err)
+    }
+}
+
+/// Error occurred during exchanging the application hello with the remote endpoint.
+///
+/// See [ConnectOptions::hello] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
+#[derive(Debug, Clone)]
+pub enum HelloError {
+    /// Sending the local hello failed.
+    Send(base::SendErrorKind),
+    /// Receiving the remote hello failed.
+    ///
+    /// This also occurs when both endpoints use incompatible hello types.
+    Recv(base::RecvError),
+    /// The remote endpoint did not send a hello.
+    NotReceived,
+}
+
+impl fmt::Display for HelloError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Send(err) => write!(f, "sending hello failed: {err}"),
+            Self::Recv(err) => write!(f, "receiving hello failed: {err}"),
+            Self::NotReceived => write!(f, "no hello was received"),
+        }
+    }
+}
+
+impl Error for HelloError {}
+
 /// Methods for establishing a connection over a physical transport.
 ///
 /// You must poll the returned [Connect] future or spawn it onto a task for the connection to work.
@@ -108,6 +165,11 @@ impl<TransportSinkError, TransportStreamError> From<base::ConnectError>
 /// They streamline connection handling when a single value, such as a [RTC](crate::rtc) client,
 /// should be exchanged over the connection and the flexibility of a base channel is not necessary.
 ///
+/// # Connection options
+///
+/// [ConnectOptions] establishes a connection with optional features, such as
+/// session resumption.
+///
 /// # Example
 ///
 /// In the following example the server listens on TCP port 9875 and the client connects to it.
@@ -177,13 +239,38 @@ impl<TransportSinkError, TransportStreamError> From<base::ConnectError>
 /// ```
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 #[must_use = "You must poll or spawn the Connect future for the connection to work."]
-pub struct Connect<'transport, TransportSinkError, TransportStreamError>(
-    BoxFuture<'transport, Result<(), ChMuxError<TransportSinkError, TransportStreamError>>>,
-);
+pub struct Connect<'transport, TransportSinkError, TransportStreamError> {
+    fut: BoxFuture<'transport, Result<(), ChMuxError<TransportSinkError, TransportStreamError>>>,
+    stats: Stats,
+    peer_info: PeerInfo,
+}
 
 impl<'transport, TransportSinkError, TransportStreamError>
     Connect<'transport, TransportSinkError, TransportStreamError>
 {
+    /// Statistics of the underlying [chmux](crate::chmux) connection.
+    ///
+    /// The returned handle can be cloned and stays valid after the connection has terminated.
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
+    /// Identity of the remote endpoint established by the handshake.
+    ///
+    /// Returns `None` if no handshake was performed.
+    /// See [ConnectOptions::handshake].
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.peer_info.identity()
+    }
+
+    /// Information about the remote endpoint of this connection.
+    ///
+    /// The address and metadata of the remote endpoint can be set on the returned handle.
+    /// Set them before exchanging any remote objects over this connection.
+    pub fn peer_info(&self) -> PeerInfo {
+        self.peer_info.clone()
+    }
+
     /// Establishes a connection over a framed transport (a [sink](Sink) and a [stream](Stream) of binary data) and
     /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
     ///
@@ -212,18 +299,20 @@ impl<'transport, TransportSinkError, TransportStreamError>
         Rx: RemoteSend,
         Codec: codec::Codec,
     {
-        let (mux, client, mut listener) = ChMux::new(cfg, transport_sink, transport_stream).await?;
-        let mut connection = Self(mux.run().boxed());
+        ConnectOptions::new(cfg).framed(transport_sink, transport_stream).await
+    }
 
+    /// Runs the dispatcher while waiting for the specified future to complete.
+    async fn drive<T, E>(
+        &mut self, fut: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, ConnectError<TransportSinkError, TransportStreamError>>
+    where
+        E: Into<ConnectError<TransportSinkError, TransportStreamError>>,
+    {
         tokio::select! {
             biased;
-            Err(err) = &mut connection => Err(err.into()),
-            result = base::connect(&client, &mut listener) => {
-                match result {
-                    Ok((tx, rx)) => Ok((connection, tx, rx)),
-                    Err(err) => Err(err.into()),
-                }
-            }
+            Err(err) = &mut *self => Err(err.into()),
+            result = fut => result.map_err(Into::into),
         }
     }
 }
@@ -256,19 +345,7 @@ impl<'transport> Connect<'transport, io::Error, io::Error> {
         Rx: RemoteSend,
         Codec: codec::Codec,
     {
-        let max_recv_frame_length: usize = cfg.max_frame_length().try_into().unwrap();
-        let transport_sink = LengthDelimitedCodec::builder()
-            .little_endian()
-            .length_field_length(4)
-            .max_frame_length(u32::MAX as _)
-            .new_write(output);
-        let transport_stream = LengthDelimitedCodec::builder()
-            .little_endian()
-            .length_field_length(4)
-            .max_frame_length(max_recv_frame_length)
-            .new_read(input)
-            .map_ok(|item| item.freeze());
-        Self::framed(cfg, transport_sink, transport_stream).await
+        ConnectOptions::new(cfg).io(input, output).await
     }
 
     /// Establishes a buffered connection over an IO transport (an [AsyncRead] and [AsyncWrite]) and
@@ -302,13 +379,42 @@ impl<'transport> Connect<'transport, io::Error, io::Error> {
     }
 }
 
+/// Length-delimited framing of an IO transport.
+type IoFramed<Read, Write> = (
+    FramedWrite<Write, LengthDelimitedCodec>,
+    MapOk<FramedRead<Read, LengthDelimitedCodec>, fn(BytesMut) -> Bytes>,
+);
+
+/// Prepends a length header to each chmux packet for transportation over an IO transport,
+/// limiting the length of received packets.
+fn io_framed_with_limit<Read, Write>(
+    max_recv_frame_length: usize, input: Read, output: Write,
+) -> IoFramed<Read, Write>
+where
+    Read: AsyncRead,
+    Write: AsyncWrite,
+{
+    let transport_sink = LengthDelimitedCodec::builder()
+        .little_endian()
+        .length_field_length(4)
+        .max_frame_length(u32::MAX as _)
+        .new_write(output);
+    let transport_stream = LengthDelimitedCodec::builder()
+        .little_endian()
+        .length_field_length(4)
+        .max_frame_length(max_recv_frame_length)
+        .new_read(input)
+        .map_ok(BytesMut::freeze as fn(BytesMut) -> Bytes);
+    (transport_sink, transport_stream)
+}
+
 impl<TransportSinkError, TransportStreamError> Future for Connect<'_, TransportSinkError, TransportStreamError> {
     /// Result of connection after it has been terminated.
     type Output = Result<(), ChMuxError<TransportSinkError, TransportStreamError>>;
 
     /// This future runs the dispatcher for this connection.
     fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
-        Pin::into_inner(self).0.poll_unpin(cx)
+        Pin::into_inner(self).fut.poll_unpin(cx)
     }
 }
 
@@ -323,6 +429,7 @@ impl LoopbackConnect {
     /// returns a [sender](base::Sender) and [receiver](base::Receiver).
     ///
     /// This establishes a [chmux](crate::chmux) connection over the loopback transport and opens a channel.
+    /// The [statistics](Connect::stats) are those of the endpoint of the returned sender.
     ///
     /// You must poll the returned [Connect] future or spawn it for the connection to work.
     ///
@@ -348,13 +455,16 @@ impl LoopbackConnect {
         )
         .unwrap();
 
-        let connection = Self(
-            async move {
+        let stats = a_connect.stats();
+        let connection = Self {
+            fut: async move {
                 tokio::try_join!(a_connect, b_connect)?;
                 Ok(())
             }
             .boxed(),
-        );
+            stats,
+            peer_info: PeerInfo::default(),
+        };
 
         (connection, a_base_tx, b_base_rx)
     }
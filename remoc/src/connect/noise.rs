@@ -0,0 +1,337 @@
+//! Noise secure channel transport.
+
+use bytes::Bytes;
+use futures::{FutureExt, Sink, SinkExt, Stream, TryStreamExt};
+use std::{
+    error::Error,
+    io,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::{
+    Connect, ConnectError, ConnectOptions, io_framed_with_limit,
+    options::{
+        IoSecureOption, Options, ReconnectOption, SecureIo, SecureIoFn, WithHello, exchange_hello, sealed,
+    },
+};
+use crate::{
+    RemoteSend,
+    chmux::{self, ChMuxError, HandshakeError, PeerInfo},
+    codec,
+    noise::{self, Noise, NoiseError, NoiseSink, NoiseStream},
+    rch::base,
+};
+
+impl sealed::Sealed for Noise {}
+
+impl<'transport, Read, Write> IoSecureOption<'transport, Read, Write> for Noise
+where
+    Read: AsyncRead + Send + Sync + Unpin + 'transport,
+    Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+{
+    fn into_fn(self, cfg: &crate::Cfg) -> Option<SecureIoFn<'transport, Read, Write>> {
+        let (timeout, max_frame_length) = (cfg.connection_timeout, cfg.max_frame_length().try_into().unwrap());
+        let noise = Arc::new(self);
+        let public_key = Arc::new(OnceLock::new());
+        Some(Arc::new(move |input, output| {
+            let (noise, public_key) = (noise.clone(), public_key.clone());
+            async move {
+                let (transport_sink, transport_stream) = io_framed_with_limit(noise::MAX_MSG_LEN, input, output);
+                let (sink, stream, remote_key) =
+                    secure(&noise, transport_sink, transport_stream, timeout, max_frame_length, &public_key)
+                        .await?;
+                let secured: SecureIo<'transport> = (
+                    Box::pin(sink.sink_map_err(io::Error::from)),
+                    Box::pin(stream.map_err(io::Error::from)),
+                    Box::new(move |peer_info: &PeerInfo| peer_info.set_public_key(remote_key)),
+                );
+                Ok(secured)
+            }
+            .boxed()
+        }))
+    }
+}
+
+/// Performs the Noise handshake within the timeout.
+///
+/// The public key of the remote endpoint presented during the first handshake is stored in
+/// `public_key` and a later handshake over a new transport fails if it presents a different one.
+async fn secure<TransportSink, TransportStream, TransportSinkError, TransportStreamError>(
+    noise: &Noise, transport_sink: TransportSink, transport_stream: TransportStream,
+    timeout_dur: Option<Duration>, max_frame_length: usize, public_key: &OnceLock<Bytes>,
+) -> Result<
+    (NoiseSink<TransportSink>, NoiseStream<TransportStream>, Bytes),
+    ChMuxError<TransportSinkError, TransportStreamError>,
+>
+where
+    TransportSink: Sink<Bytes, Error = TransportSinkError> + Unpin,
+    TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Unpin,
+{
+    let (sink, stream, remote_key) =
+        noise.handshake(transport_sink, transport_stream, max_frame_length, timeout_dur).await?;
+    if *public_key.get_or_init(|| remote_key.clone()) != remote_key {
+        return Err(ChMuxError::Handshake(HandshakeError::failed(
+            "remote endpoint presented a different public key",
+        )));
+    }
+    Ok((sink, stream, remote_key))
+}
+
+/// Wraps the transport errors of a failed Noise handshake.
+fn noise_handshake_error<TransportSinkError, TransportStreamError>(
+    err: ChMuxError<TransportSinkError, TransportStreamError>,
+) -> ChMuxError<NoiseError<TransportSinkError>, NoiseError<TransportStreamError>> {
+    match err {
+        ChMuxError::SinkError(err) => ChMuxError::SinkError(NoiseError::Transport(err)),
+        ChMuxError::StreamError(err) => ChMuxError::StreamError(NoiseError::Transport(err)),
+        ChMuxError::StreamClosed => ChMuxError::StreamClosed,
+        ChMuxError::Reset => ChMuxError::Reset,
+        ChMuxError::Timeout => ChMuxError::Timeout,
+        ChMuxError::Protocol(msg) => ChMuxError::Protocol(msg),
+        ChMuxError::SessionLost => ChMuxError::SessionLost,
+        ChMuxError::Handshake(err) => ChMuxError::Handshake(err),
+        ChMuxError::QuotaExceeded(quota) => ChMuxError::QuotaExceeded(quota),
+    }
+}
+
+impl<'transport, TransportSink, TransportStream> Options<'transport, (TransportSink, TransportStream)> {
+    /// Establishes the chmux connection over the framed transport, secured using the Noise protocol.
+    ///
+    /// The public key of the remote endpoint is stored in the peer information
+    /// before the connection is returned.
+    async fn noise_mux<TransportSinkError, TransportStreamError>(
+        self, noise: Noise, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+            chmux::Client,
+            chmux::Listener,
+        ),
+        ConnectError<NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+    >
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+    {
+        let (timeout, max_frame_length) = (self.cfg.connection_timeout, self.max_frame_length());
+        let noise = Arc::new(noise);
+        let public_key = Arc::new(OnceLock::new());
+
+        let (sink, stream, remote_key) =
+            secure(&noise, transport_sink, transport_stream, timeout, max_frame_length, &public_key)
+                .await
+                .map_err(noise_handshake_error)?;
+        let (connection, client, listener) = self
+            .map_transport(move |(transport_sink, transport_stream)| {
+                let (noise, public_key) = (noise.clone(), public_key.clone());
+                async move {
+                    match secure(&noise, transport_sink, transport_stream, timeout, max_frame_length, &public_key)
+                        .await
+                    {
+                        Ok((sink, stream, _)) => Some((sink, stream)),
+                        Err(err) => {
+                            tracing::warn!(%err, "securing new transport failed");
+                            None
+                        }
+                    }
+                }
+                .boxed()
+            })
+            .mux(sink, stream)
+            .await?;
+        connection.peer_info.set_public_key(remote_key);
+
+        Ok((connection, client, listener))
+    }
+}
+
+impl<Reconnect> ConnectOptions<Reconnect, (), Noise> {
+    /// Establishes a secure channel using the Noise protocol over a framed transport
+    /// (a [sink](Sink) and a [stream](Stream) of binary data) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This establishes a [chmux](crate::chmux) connection using these options over the secure channel
+    /// and opens a remote channel.
+    /// Every frame sent over the transport is encrypted.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+    pub async fn framed<
+        'transport,
+        TransportSink,
+        TransportStream,
+        TransportSinkError,
+        TransportStreamError,
+        Tx,
+        Rx,
+        Codec,
+    >(
+        self, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+        ),
+        ConnectError<NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (TransportSink, TransportStream)>,
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), noise) = self.resolve();
+        let (mut connection, client, mut listener) =
+            options.noise_mux(noise, transport_sink, transport_stream).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+impl<Reconnect, Hello> ConnectOptions<Reconnect, WithHello<Hello>, Noise>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a secure channel using the Noise protocol over a framed transport
+    /// (a [sink](Sink) and a [stream](Stream) of binary data),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// This establishes a [chmux](crate::chmux) connection using these options over the secure channel
+    /// and opens a remote channel.
+    /// Every frame sent over the transport is encrypted.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+    pub async fn framed<
+        'transport,
+        TransportSink,
+        TransportStream,
+        TransportSinkError,
+        TransportStreamError,
+        Tx,
+        Rx,
+        Codec,
+    >(
+        self, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+            Hello,
+        ),
+        ConnectError<NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (TransportSink, TransportStream)>,
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), noise) = self.resolve();
+        let (mut connection, client, mut listener) =
+            options.noise_mux(noise, transport_sink, transport_stream).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+impl<'transport, TransportSinkError, TransportStreamError>
+    Connect<'transport, NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>
+{
+    /// Establishes a secure channel using the Noise protocol over a framed transport
+    /// (a [sink](Sink) and a [stream](Stream) of binary data) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The Noise handshake is performed first, then a [chmux](crate::chmux) connection
+    /// is established over the secure channel and a remote channel is opened.
+    /// The authenticated static public key of the remote endpoint is available from
+    /// [peer_info](Self::peer_info).
+    ///
+    /// This is a shorthand for [ConnectOptions::noise], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+    pub async fn framed_with_noise<TransportSink, TransportStream, Tx, Rx, Codec>(
+        cfg: crate::Cfg, noise: Noise, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+        ),
+        ConnectError<NoiseError<TransportSinkError>, NoiseError<TransportStreamError>>,
+    >
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).noise(noise).framed(transport_sink, transport_stream).await
+    }
+}
+
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a secure channel using the Noise protocol over an IO transport
+    /// (an [AsyncRead] and [AsyncWrite]) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This prepends a length header to each encrypted message for transportation over the
+    /// unframed connection.
+    /// See [framed_with_noise](Connect::framed_with_noise) for details.
+    ///
+    /// This is a shorthand for [ConnectOptions::noise], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+    pub async fn io_with_noise<Read, Write, Tx, Rx, Codec>(
+        cfg: crate::Cfg, noise: Noise, input: Read, output: Write,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Read: AsyncRead + Send + Sync + Unpin + 'transport,
+        Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).noise(noise).io(input, output).await
+    }
+}
@@ -0,0 +1,647 @@
+//! Options for establishing a connection.
+
+use bytes::Bytes;
+use futures::{
+    FutureExt, Sink, Stream,
+    future::{self, BoxFuture},
+};
+use std::{error::Error, fmt, future::Future, io, pin::Pin, sync::Arc};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::{Connect, ConnectError, HelloError, io_framed_with_limit};
+use crate::{
+    RemoteSend,
+    chmux::{self, ChMux, ChMuxError, Handshake, PeerInfo},
+    codec,
+    rch::base,
+};
+
+#[cfg(feature = "noise")]
+use crate::noise::Noise;
+
+#[cfg(feature = "tls")]
+use super::tls::{TlsClient, TlsServer};
+#[cfg(feature = "tls")]
+use tokio_rustls::rustls::{self, pki_types::ServerName};
+
+/// Function providing a new transport after the transport of a connection has failed.
+type ReconnectFn<'transport, Transport> =
+    Box<dyn FnMut() -> BoxFuture<'transport, Option<Transport>> + Send + 'transport>;
+
+/// Function establishing a secure channel over an IO transport.
+pub(super) type SecureIoFn<'transport, Read, Write> = Arc<
+    dyn Fn(Read, Write) -> BoxFuture<'transport, Result<SecureIo<'transport>, ChMuxError<io::Error, io::Error>>>
+        + Send
+        + Sync
+        + 'transport,
+>;
+
+/// Framed transport secured by a [SecureIoFn] together with a function that stores
+/// the credentials of the remote endpoint in the [peer information](PeerInfo).
+pub(super) type SecureIo<'transport> = (
+    Pin<Box<dyn Sink<Bytes, Error = io::Error> + Send + Sync + 'transport>>,
+    Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync + 'transport>>,
+    Box<dyn FnOnce(&PeerInfo) + Send>,
+);
+
+/// Options for establishing a connection over a physical transport.
+///
+/// This enables optional features of a connection, such as
+/// [authentication](Self::handshake) of the remote endpoint,
+/// [resumption](Self::reconnect) of the connection over a new transport after a failure,
+/// the exchange of an [application hello](Self::hello),
+/// encryption of an IO transport using TLS (`tls_client` and `tls_server`, requires the `tls` feature)
+/// and encryption of any transport using the Noise protocol (`noise`, requires the `noise` feature).
+/// These features can be freely combined, except that only one kind of encryption can be used.
+///
+/// The connection is then established over a framed transport using [framed](Self::framed),
+/// over an IO transport using [io](Self::io),
+/// over a WebSocket using `websocket` (requires the `websocket` feature),
+/// over a QUIC connection using `quic` (requires the `quic` feature) or
+/// with a child process using `child_process` and `stdio` (requires the `process` feature).
+///
+/// # Example
+///
+/// In the following example the client connects again when the TCP connection fails
+/// and the server accepts the new TCP connection over a listener dedicated to this connection.
+/// The session is then resumed without losing data.
+///
+/// ```
+/// use std::{net::Ipv4Addr, sync::Arc};
+/// use tokio::net::{TcpListener, TcpStream};
+/// use remoc::{ConnectOptions, prelude::*};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let listener = Arc::new(TcpListener::bind((Ipv4Addr::LOCALHOST, 9878)).await.unwrap());
+///
+///     let connect = || async {
+///         let socket = TcpStream::connect((Ipv4Addr::LOCALHOST, 9878)).await.ok()?;
+///         Some(socket.into_split())
+///     };
+///     let accept = move || {
+///         let listener = listener.clone();
+///         async move {
+///             let (socket, _) = listener.accept().await.ok()?;
+///             Some(socket.into_split())
+///         }
+///     };
+///
+///     let (a, b) = tokio::join!(
+///         async {
+///             let (input, output) = connect().await.unwrap();
+///             ConnectOptions::new(remoc::Cfg::default())
+///                 .reconnect(connect)
+///                 .io::<_, _, String, (), remoc::codec::Default>(input, output)
+///                 .await
+///         },
+///         async {
+///             let (input, output) = accept().await.unwrap();
+///             ConnectOptions::new(remoc::Cfg::default())
+///                 .reconnect(accept)
+///                 .io::<_, _, (), String, remoc::codec::Default>(input, output)
+///                 .await
+///         },
+///     );
+///     let (a_conn, mut a_tx, _) = a.unwrap();
+///     let (b_conn, _, mut b_rx) = b.unwrap();
+///     tokio::spawn(a_conn);
+///     tokio::spawn(b_conn);
+///
+///     a_tx.send("Hello".to_string()).await.unwrap();
+///     assert_eq!(b_rx.recv().await.unwrap(), Some("Hello".to_string()));
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
+pub struct ConnectOptions<Reconnect = (), Hello = (), Secure = ()> {
+    cfg: crate::Cfg,
+    handshake: Option<Arc<dyn Handshake>>,
+    reconnect: Reconnect,
+    hello: Hello,
+    secure: Secure,
+}
+
+impl<Reconnect, Hello, Secure> fmt::Debug for ConnectOptions<Reconnect, Hello, Secure>
+where
+    Reconnect: fmt::Debug,
+    Hello: fmt::Debug,
+    Secure: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConnectOptions")
+            .field("cfg", &self.cfg)
+            .field("handshake", &self.handshake.is_some())
+            .field("reconnect", &self.reconnect)
+            .field("hello", &self.hello)
+            .field("secure", &self.secure)
+            .finish()
+    }
+}
+
+/// Reconnect function providing new transports of type `Transport`.
+///
+/// See [ConnectOptions::reconnect] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
+pub struct WithReconnect<'transport, Transport>(ReconnectFn<'transport, Transport>);
+
+impl<Transport> fmt::Debug for WithReconnect<'_, Transport> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WithReconnect").finish_non_exhaustive()
+    }
+}
+
+/// Hello sent to the remote endpoint.
+///
+/// See [ConnectOptions::hello] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
+#[derive(Debug, Clone)]
+pub struct WithHello<Hello>(pub(super) Hello);
+
+pub(super) mod sealed {
+    pub trait Sealed {}
+}
+
+impl sealed::Sealed for () {}
+impl<Transport> sealed::Sealed for WithReconnect<'_, Transport> {}
+
+/// Reconnect option of [ConnectOptions] for transports of type `Transport`.
+///
+/// This is implemented for `()`, when the connection is not resumed, and for [WithReconnect].
+pub trait ReconnectOption<'transport, Transport>: sealed::Sealed + Send {
+    #[doc(hidden)]
+    fn into_fn(self) -> Option<ReconnectFn<'transport, Transport>>;
+}
+
+impl<'transport, Transport> ReconnectOption<'transport, Transport> for () {
+    fn into_fn(self) -> Option<ReconnectFn<'transport, Transport>> {
+        None
+    }
+}
+
+impl<'transport, Transport> ReconnectOption<'transport, Transport> for WithReconnect<'transport, Transport> {
+    fn into_fn(self) -> Option<ReconnectFn<'transport, Transport>> {
+        Some(self.0)
+    }
+}
+
+/// Secure channel option of [ConnectOptions] for IO transports consisting of `Read` and `Write`.
+///
+/// This is implemented for `()`, when the transport is used as is,
+/// for the TLS options (requires the `tls` feature) and for the Noise option (requires the `noise` feature).
+pub trait IoSecureOption<'transport, Read, Write>: sealed::Sealed + Send {
+    #[doc(hidden)]
+    fn into_fn(self, cfg: &crate::Cfg) -> Option<SecureIoFn<'transport, Read, Write>>;
+}
+
+impl<'transport, Read, Write> IoSecureOption<'transport, Read, Write> for () {
+    fn into_fn(self, _cfg: &crate::Cfg) -> Option<SecureIoFn<'transport, Read, Write>> {
+        None
+    }
+}
+
+impl ConnectOptions {
+    /// Creates connection options using the specified chmux configuration and
+    /// no optional features.
+    pub fn new(cfg: crate::Cfg) -> Self {
+        Self { cfg, handshake: None, reconnect: (), hello: (), secure: () }
+    }
+}
+
+impl<Reconnect, Hello, Secure> ConnectOptions<Reconnect, Hello, Secure> {
+    /// Authenticates the remote endpoint using the specified handshake.
+    ///
+    /// The [handshake](Handshake) is performed after the [chmux](crate::chmux) configuration
+    /// has been exchanged, but before any ports are accepted.
+    /// It must also be configured on the remote endpoint.
+    /// The resulting identity of the remote endpoint is available from
+    /// [Connect::peer_identity].
+    ///
+    /// When the connection is [resumed](Self::reconnect), the handshake is repeated over the
+    /// new transport and the new transport is only used if it leads to the same remote endpoint.
+    pub fn handshake(mut self, handshake: impl Handshake) -> Self {
+        self.handshake = Some(Arc::new(handshake));
+        self
+    }
+
+    /// Resumes the connection over a new transport after the transport has failed.
+    ///
+    /// This enables [session resumption](crate::Cfg::session_resumption), which must
+    /// also be enabled on the remote endpoint.
+    /// When the transport fails, `reconnect` is called to obtain a new transport to the
+    /// remote endpoint and the connection is resumed without losing data.
+    /// Return `None` from `reconnect` to give up; the connection then fails with the
+    /// transport error.
+    /// See [ChMux::run_with_reconnect] for details.
+    ///
+    /// When connecting over TLS or using the Noise protocol, the TLS or Noise handshake is repeated
+    /// over the new transport and the new transport is only used if the remote endpoint presents
+    /// the same certificate or public key.
+    pub fn reconnect<'transport, Transport, ReconnectFut>(
+        self, mut reconnect: impl FnMut() -> ReconnectFut + Send + 'transport,
+    ) -> ConnectOptions<WithReconnect<'transport, Transport>, Hello, Secure>
+    where
+        ReconnectFut: Future<Output = Option<Transport>> + Send + 'transport,
+    {
+        let Self { cfg, handshake, reconnect: _, hello, secure } = self;
+        let reconnect = WithReconnect(Box::new(move || reconnect().boxed()));
+        ConnectOptions { cfg, handshake, reconnect, hello, secure }
+    }
+
+    /// Exchanges an application hello with the remote endpoint.
+    ///
+    /// The hello is exchanged over a dedicated channel before the base channel is opened
+    /// and the hello of the remote endpoint is returned along with the base channel.
+    /// It can be any [remote sendable](RemoteSend) value, for example a struct containing the
+    /// application version and a list of supported features.
+    /// Both endpoints can then pick compatible behavior based on the remote hello without
+    /// sending a custom first message over the base channel.
+    ///
+    /// The remote endpoint must also exchange a hello of the same type.
+    /// If the hello types are incompatible, connecting fails with a [HelloError].
+    pub fn hello<NewHello>(self, hello: NewHello) -> ConnectOptions<Reconnect, WithHello<NewHello>, Secure>
+    where
+        NewHello: RemoteSend,
+    {
+        let Self { cfg, handshake, reconnect, hello: _, secure } = self;
+        ConnectOptions { cfg, handshake, reconnect, hello: WithHello(hello), secure }
+    }
+
+    /// Encrypts the IO transport using TLS as client.
+    ///
+    /// The TLS handshake is performed using the provided rustls configuration,
+    /// verifying that the server certificate is valid for `server_name`.
+    /// Then a [chmux](crate::chmux) connection is established over the encrypted transport.
+    /// The remote endpoint must use [tls_server](Self::tls_server).
+    ///
+    /// The certificate chain presented by the server is available from [Connect::peer_info].
+    /// A failed TLS handshake is reported as a [stream error](ChMuxError::StreamError)
+    /// and a TLS handshake exceeding the [connection timeout](crate::Cfg::connection_timeout)
+    /// fails with [ChMuxError::Timeout].
+    #[cfg(feature = "tls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    pub fn tls_client(
+        self, tls_cfg: Arc<rustls::ClientConfig>, server_name: ServerName<'static>,
+    ) -> ConnectOptions<Reconnect, Hello, TlsClient> {
+        let Self { cfg, handshake, reconnect, hello, secure: _ } = self;
+        ConnectOptions { cfg, handshake, reconnect, hello, secure: TlsClient::new(tls_cfg, server_name) }
+    }
+
+    /// Encrypts the IO transport using TLS as server.
+    ///
+    /// The TLS handshake is performed using the provided rustls configuration.
+    /// Then a [chmux](crate::chmux) connection is established over the encrypted transport.
+    /// The remote endpoint must use [tls_client](Self::tls_client).
+    ///
+    /// If the configuration requests client authentication, the certificate chain presented
+    /// by the client is available from [Connect::peer_info].
+    /// Errors are reported as for [tls_client](Self::tls_client).
+    #[cfg(feature = "tls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    pub fn tls_server(self, tls_cfg: Arc<rustls::ServerConfig>) -> ConnectOptions<Reconnect, Hello, TlsServer> {
+        let Self { cfg, handshake, reconnect, hello, secure: _ } = self;
+        ConnectOptions { cfg, handshake, reconnect, hello, secure: TlsServer::new(tls_cfg) }
+    }
+
+    /// Encrypts and mutually authenticates the transport using the Noise protocol.
+    ///
+    /// The Noise handshake is performed first, then a [chmux](crate::chmux) connection
+    /// is established over the secure channel.
+    /// This works over both framed and IO transports.
+    /// Over a framed transport, the errors of the transport are wrapped in a
+    /// [NoiseError](crate::noise::NoiseError).
+    ///
+    /// The authenticated static public key of the remote endpoint is available from
+    /// [Connect::peer_info].
+    /// A failed or rejected Noise handshake is reported as a [handshake error](ChMuxError::Handshake)
+    /// and a Noise handshake exceeding the [connection timeout](crate::Cfg::connection_timeout)
+    /// fails with [ChMuxError::Timeout].
+    /// See the [noise module](crate::noise) for details.
+    #[cfg(feature = "noise")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+    pub fn noise(self, noise: Noise) -> ConnectOptions<Reconnect, Hello, Noise> {
+        let Self { cfg, handshake, reconnect, hello, secure: _ } = self;
+        ConnectOptions { cfg, handshake, reconnect, hello, secure: noise }
+    }
+
+    /// Resolves the options for a transport of type `Transport`.
+    pub(super) fn resolve<'transport, Transport>(self) -> (Options<'transport, Transport>, Hello, Secure)
+    where
+        Reconnect: ReconnectOption<'transport, Transport>,
+    {
+        let Self { cfg, handshake, reconnect, hello, secure } = self;
+        (Options { cfg, handshake, reconnect: reconnect.into_fn() }, hello, secure)
+    }
+}
+
+impl<Reconnect> ConnectOptions<Reconnect> {
+    /// Establishes a connection over a framed transport (a [sink](Sink) and a [stream](Stream) of binary data) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This establishes a [chmux](crate::chmux) connection using these options over the transport
+    /// and opens a remote channel.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn framed<
+        'transport,
+        TransportSink,
+        TransportStream,
+        TransportSinkError,
+        TransportStreamError,
+        Tx,
+        Rx,
+        Codec,
+    >(
+        self, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, TransportSinkError, TransportStreamError>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+        ),
+        ConnectError<TransportSinkError, TransportStreamError>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (TransportSink, TransportStream)>,
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.mux(transport_sink, transport_stream).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+impl<Reconnect, Secure> ConnectOptions<Reconnect, (), Secure> {
+    /// Establishes a connection over an IO transport (an [AsyncRead] and [AsyncWrite]) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This prepends a length header to each chmux packet for transportation over the unframed connection.
+    /// See [framed](Self::framed) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn io<'transport, Read, Write, Tx, Rx, Codec>(
+        self, input: Read, output: Write,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (Read, Write)>,
+        Secure: IoSecureOption<'transport, Read, Write>,
+        Read: AsyncRead + Send + Sync + Unpin + 'transport,
+        Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), secure) = self.resolve();
+        let (mut connection, client, mut listener) = options.io_mux(secure, input, output).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+impl<Reconnect, Hello> ConnectOptions<Reconnect, WithHello<Hello>>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a connection over a framed transport (a [sink](Sink) and a [stream](Stream) of binary data),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// This establishes a [chmux](crate::chmux) connection using these options over the transport
+    /// and opens a remote channel.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn framed<
+        'transport,
+        TransportSink,
+        TransportStream,
+        TransportSinkError,
+        TransportStreamError,
+        Tx,
+        Rx,
+        Codec,
+    >(
+        self, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (
+            Connect<'transport, TransportSinkError, TransportStreamError>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+            Hello,
+        ),
+        ConnectError<TransportSinkError, TransportStreamError>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (TransportSink, TransportStream)>,
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.mux(transport_sink, transport_stream).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+impl<Reconnect, Hello, Secure> ConnectOptions<Reconnect, WithHello<Hello>, Secure>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a connection over an IO transport (an [AsyncRead] and [AsyncWrite]),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// This prepends a length header to each chmux packet for transportation over the unframed connection.
+    /// See [framed](Self::framed) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn io<'transport, Read, Write, Tx, Rx, Codec>(
+        self, input: Read, output: Write,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Hello),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Reconnect: ReconnectOption<'transport, (Read, Write)>,
+        Secure: IoSecureOption<'transport, Read, Write>,
+        Read: AsyncRead + Send + Sync + Unpin + 'transport,
+        Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), secure) = self.resolve();
+        let (mut connection, client, mut listener) = options.io_mux(secure, input, output).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+/// Exchanges the application hello over a dedicated channel.
+pub(super) async fn exchange_hello<Hello, Codec, TransportSinkError, TransportStreamError>(
+    client: &chmux::Client, listener: &mut chmux::Listener, hello: Hello,
+) -> Result<Hello, ConnectError<TransportSinkError, TransportStreamError>>
+where
+    Hello: RemoteSend,
+    Codec: codec::Codec,
+{
+    let (mut tx, mut rx) = base::connect::<Hello, Hello, Codec>(client, listener).await?;
+    let (sent, received) = tokio::join!(tx.send(hello), rx.recv());
+    sent.map_err(|err| HelloError::Send(err.kind))?;
+    Ok(received.map_err(HelloError::Recv)?.ok_or(HelloError::NotReceived)?)
+}
+
+/// Connection options resolved for a transport of type `Transport`.
+pub(super) struct Options<'transport, Transport> {
+    pub(super) cfg: crate::Cfg,
+    handshake: Option<Arc<dyn Handshake>>,
+    reconnect: Option<ReconnectFn<'transport, Transport>>,
+}
+
+impl<'transport, Transport> Options<'transport, Transport> {
+    /// Converts the transport provided by the reconnect function using `map`.
+    pub(super) fn map_transport<NewTransport, Map>(self, map: Map) -> Options<'transport, NewTransport>
+    where
+        Transport: Send + 'transport,
+        Map: Fn(Transport) -> BoxFuture<'transport, Option<NewTransport>> + Send + Sync + 'transport,
+    {
+        let Self { cfg, handshake, reconnect } = self;
+        let map = Arc::new(map);
+        let reconnect = reconnect.map(|mut reconnect| -> ReconnectFn<'transport, NewTransport> {
+            Box::new(move || {
+                let transport = reconnect();
+                let map = map.clone();
+                async move { map(transport.await?).await }.boxed()
+            })
+        });
+        Options { cfg, handshake, reconnect }
+    }
+
+    /// Maximum length of a frame received over an IO transport.
+    pub(super) fn max_frame_length(&self) -> usize {
+        self.cfg.max_frame_length().try_into().unwrap()
+    }
+}
+
+impl<'transport, TransportSink, TransportStream> Options<'transport, (TransportSink, TransportStream)> {
+    /// Establishes the chmux connection over the framed transport.
+    pub(super) async fn mux<TransportSinkError, TransportStreamError>(
+        self, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<
+        (Connect<'transport, TransportSinkError, TransportStreamError>, chmux::Client, chmux::Listener),
+        ConnectError<TransportSinkError, TransportStreamError>,
+    >
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Sync + Unpin + 'transport,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Sync + Unpin + 'transport,
+        TransportStreamError: Error + Send + Sync + 'static,
+    {
+        let Self { mut cfg, handshake, mut reconnect } = self;
+        cfg.session_resumption |= reconnect.is_some();
+        let reconnect = move || match &mut reconnect {
+            Some(reconnect) => reconnect(),
+            None => future::ready(None).boxed(),
+        };
+
+        let (mux, client, listener) = ChMux::new_impl(cfg, transport_sink, transport_stream, handshake).await?;
+        let stats = mux.stats();
+        let peer_info = mux.peer_info();
+        let connection = Connect { fut: mux.run_with_reconnect(reconnect).boxed(), stats, peer_info };
+        Ok((connection, client, listener))
+    }
+}
+
+impl<'transport, Read, Write> Options<'transport, (Read, Write)> {
+    /// Establishes the chmux connection over the IO transport, secured using `secure`.
+    ///
+    /// The credentials of the remote endpoint are stored in the peer information
+    /// before the connection is returned.
+    async fn io_mux<Secure>(
+        self, secure: Secure, input: Read, output: Write,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, chmux::Client, chmux::Listener),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Secure: IoSecureOption<'transport, Read, Write>,
+        Read: AsyncRead + Send + Sync + Unpin + 'transport,
+        Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+    {
+        let Some(secure) = secure.into_fn(&self.cfg) else {
+            return self.plain_io_mux(input, output).await;
+        };
+
+        let (transport_sink, transport_stream, set_peer_info) = secure(input, output).await?;
+        let (connection, client, listener) = self
+            .map_transport(move |(input, output)| {
+                let secured = secure(input, output);
+                async move {
+                    match secured.await {
+                        Ok((transport_sink, transport_stream, _)) => Some((transport_sink, transport_stream)),
+                        Err(err) => {
+                            tracing::warn!(%err, "securing new transport failed");
+                            None
+                        }
+                    }
+                }
+                .boxed()
+            })
+            .mux(transport_sink, transport_stream)
+            .await?;
+        set_peer_info(&connection.peer_info);
+
+        Ok((connection, client, listener))
+    }
+
+    /// Establishes the chmux connection over the IO transport as is.
+    async fn plain_io_mux(
+        self, input: Read, output: Write,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, chmux::Client, chmux::Listener),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Read: AsyncRead + Send + Sync + Unpin + 'transport,
+        Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+    {
+        let max_frame_length = self.max_frame_length();
+        let (transport_sink, transport_stream) = io_framed_with_limit(max_frame_length, input, output);
+        self.map_transport(move |(input, output)| {
+            future::ready(Some(io_framed_with_limit(max_frame_length, input, output))).boxed()
+        })
+        .mux(transport_sink, transport_stream)
+        .await
+    }
+}
@@ -0,0 +1,319 @@
+//! Child process transport.
+
+use futures::FutureExt;
+use std::{io, process::ExitStatus, process::Stdio};
+use tokio::{
+    io::{BufReader, BufWriter, Stdin, Stdout},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::oneshot,
+};
+
+use super::{Connect, ConnectError, ConnectOptions, IoSecureOption, WithHello};
+use crate::{RemoteSend, chmux::ChMuxError, codec, rch::base};
+
+/// Buffer size for the standard input and output pipes.
+const BUFFER: usize = 8192;
+
+/// Buffered standard output and input of a child process.
+type ChildIo = (BufReader<ChildStdout>, BufWriter<ChildStdin>);
+
+/// Buffered standard input and output of the current process.
+type StdIo = (BufReader<Stdin>, BufWriter<Stdout>);
+
+/// Handle to a child process connected by [ConnectOptions::child_process].
+///
+/// The child process is not killed when this handle is dropped.
+/// Instead its lifetime is bound to the [Connect] future.
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+#[derive(Debug)]
+pub struct ChildProcess {
+    /// Process identifier.
+    id: Option<u32>,
+    /// Kill request to connection future.
+    kill_tx: Option<oneshot::Sender<()>>,
+    /// Exit status from connection future.
+    status_rx: oneshot::Receiver<io::Result<ExitStatus>>,
+}
+
+impl ChildProcess {
+    /// The OS-assigned process identifier of the child process, if available.
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    /// Requests the child process to be killed.
+    ///
+    /// This terminates the connection.
+    /// The [Connect] future must be polled for the request to take effect.
+    pub fn kill(&mut self) {
+        if let Some(kill_tx) = self.kill_tx.take() {
+            let _ = kill_tx.send(());
+        }
+    }
+
+    /// Waits for the child process to exit and returns its exit status.
+    ///
+    /// The [Connect] future must be polled for the child process to be waited for.
+    /// If the [Connect] future is dropped before it has completed, the child process
+    /// is killed and an error is returned.
+    pub async fn wait(self) -> io::Result<ExitStatus> {
+        match self.status_rx.await {
+            Ok(status) => status,
+            Err(_) => Err(io::Error::other("connection dropped before child process exited")),
+        }
+    }
+}
+
+/// Spawns the child process with its standard input and output redirected to pipes.
+///
+/// The child process is killed when it is dropped.
+fn spawn(command: &mut Command) -> io::Result<(Child, ChildIo)> {
+    let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).kill_on_drop(true).spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    Ok((child, (BufReader::with_capacity(BUFFER, stdout), BufWriter::with_capacity(BUFFER, stdin))))
+}
+
+/// Buffers the standard input and output of the current process.
+fn stdio() -> StdIo {
+    (BufReader::with_capacity(BUFFER, tokio::io::stdin()), BufWriter::with_capacity(BUFFER, tokio::io::stdout()))
+}
+
+/// Binds the lifetime of the child process to the connection.
+///
+/// When the connection terminates, the connection future waits for the child process to exit.
+/// If the connection fails, the child process is killed.
+fn supervise(
+    connect: Connect<'static, io::Error, io::Error>, mut child: Child,
+) -> (Connect<'static, io::Error, io::Error>, ChildProcess) {
+    let Connect { fut, stats, peer_info } = connect;
+    let id = child.id();
+
+    let (kill_tx, mut kill_rx) = oneshot::channel();
+    let (status_tx, status_rx) = oneshot::channel();
+    let fut = async move {
+        let mut fut = fut;
+        let res = tokio::select! {
+            res = &mut fut => res,
+            Ok(()) = &mut kill_rx => {
+                let _ = child.start_kill();
+                fut.await
+            }
+        };
+
+        if res.is_err() {
+            let _ = child.start_kill();
+        }
+        let _ = status_tx.send(child.wait().await);
+
+        res
+    };
+
+    let connect = Connect { fut: fut.boxed(), stats, peer_info };
+    let handle = ChildProcess { id, kill_tx: Some(kill_tx), status_rx };
+    (connect, handle)
+}
+
+impl<Secure> ConnectOptions<(), (), Secure> {
+    /// Spawns a child process and establishes a connection over its standard input and output,
+    /// returning a remote [sender](base::Sender) and [receiver](base::Receiver) and
+    /// a [handle](ChildProcess) to the child process.
+    ///
+    /// The standard input and output of the command are redirected to pipes,
+    /// while its standard error is left as configured.
+    /// The child process should use [stdio](Self::stdio) with matching options
+    /// to establish its end of the connection.
+    /// A failure to spawn the child process is reported as a [stream error](ChMuxError::StreamError).
+    ///
+    /// When the connection terminates, the standard input of the child process is closed and
+    /// the [Connect] future waits for the child process to exit before completing.
+    /// If establishing the connection fails, the connection fails or the [Connect] future is dropped,
+    /// the child process is killed.
+    ///
+    /// Since the pipes of a process cannot be replaced, the connection cannot be
+    /// [resumed](Self::reconnect).
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn child_process<Tx, Rx, Codec>(
+        self, mut command: Command,
+    ) -> Result<
+        (
+            Connect<'static, io::Error, io::Error>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+            ChildProcess,
+        ),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Secure: IoSecureOption<'static, BufReader<ChildStdout>, BufWriter<ChildStdin>>,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (child, (input, output)) = spawn(&mut command).map_err(ChMuxError::StreamError)?;
+        let (connect, tx, rx) = self.io(input, output).await?;
+        let (connect, handle) = supervise(connect, child);
+        Ok((connect, tx, rx, handle))
+    }
+
+    /// Establishes a connection over the standard input and output of the current process and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This is the counterpart of [child_process](Self::child_process) to be used
+    /// within the child process.
+    /// Nothing else must be read from standard input or written to standard output
+    /// while the connection is active.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn stdio<Tx, Rx, Codec>(
+        self,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Secure: IoSecureOption<'static, BufReader<Stdin>, BufWriter<Stdout>>,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (input, output) = stdio();
+        self.io(input, output).await
+    }
+}
+
+impl<Hello, Secure> ConnectOptions<(), WithHello<Hello>, Secure>
+where
+    Hello: RemoteSend,
+{
+    /// Spawns a child process, establishes a connection over its standard input and output and
+    /// exchanges the [hello](Self::hello) with it,
+    /// returning a remote [sender](base::Sender) and [receiver](base::Receiver),
+    /// a [handle](ChildProcess) to the child process and the hello of the child process.
+    ///
+    /// See [child_process](ConnectOptions::child_process) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn child_process<Tx, Rx, Codec>(
+        self, mut command: Command,
+    ) -> Result<
+        (
+            Connect<'static, io::Error, io::Error>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+            ChildProcess,
+            Hello,
+        ),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Secure: IoSecureOption<'static, BufReader<ChildStdout>, BufWriter<ChildStdin>>,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (child, (input, output)) = spawn(&mut command).map_err(ChMuxError::StreamError)?;
+        let (connect, tx, rx, remote_hello) = self.io(input, output).await?;
+        let (connect, handle) = supervise(connect, child);
+        Ok((connect, tx, rx, handle, remote_hello))
+    }
+
+    /// Establishes a connection over the standard input and output of the current process,
+    /// exchanges the [hello](Self::hello) with the parent process and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the parent process.
+    ///
+    /// See [stdio](ConnectOptions::stdio) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn stdio<Tx, Rx, Codec>(
+        self,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Hello),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Secure: IoSecureOption<'static, BufReader<Stdin>, BufWriter<Stdout>>,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (input, output) = stdio();
+        self.io(input, output).await
+    }
+}
+
+impl Connect<'static, io::Error, io::Error> {
+    /// Spawns a child process and establishes a connection over its standard input and output,
+    /// returning a remote [sender](base::Sender) and [receiver](base::Receiver) and
+    /// a [handle](ChildProcess) to the child process.
+    ///
+    /// This is a shorthand for [ConnectOptions::child_process], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn child_process<Tx, Rx, Codec>(
+        cfg: crate::Cfg, command: Command,
+    ) -> Result<
+        (
+            Connect<'static, io::Error, io::Error>,
+            base::Sender<Tx, Codec>,
+            base::Receiver<Rx, Codec>,
+            ChildProcess,
+        ),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).child_process(command).await
+    }
+
+    /// Establishes a connection over the standard input and output of the current process and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This is a shorthand for [ConnectOptions::stdio], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    pub async fn stdio<Tx, Rx, Codec>(
+        cfg: crate::Cfg,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).stdio().await
+    }
+}
@@ -0,0 +1,207 @@
+//! QUIC transport.
+
+use bytes::{Bytes, BytesMut};
+use futures::stream::MapOk;
+use quinn::{Connection, RecvStream, SendStream, Side};
+use std::io;
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+
+use super::{
+    Connect, ConnectError, ConnectOptions, io_framed_with_limit,
+    options::{Options, WithHello, exchange_hello},
+};
+use crate::{
+    RemoteSend,
+    chmux::{self, ChMuxError, LaneSink, LaneStream},
+    codec,
+    rch::base,
+};
+
+/// Number of QUIC streams opened per connection.
+const STREAMS: u8 = 8;
+
+/// Framed QUIC streams of a connection combined into lanes.
+type Lanes = (
+    LaneSink<FramedWrite<SendStream, LengthDelimitedCodec>>,
+    LaneStream<MapOk<FramedRead<RecvStream, LengthDelimitedCodec>, fn(BytesMut) -> Bytes>>,
+);
+
+impl<'transport> Options<'transport, Lanes> {
+    /// Establishes the chmux connection over the QUIC connection.
+    ///
+    /// The remote address is stored in the peer information before the connection is returned.
+    ///
+    /// # Panics
+    /// Panics if session resumption is enabled.
+    async fn quic_mux(
+        self, connection: Connection,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, chmux::Client, chmux::Listener),
+        ConnectError<io::Error, io::Error>,
+    > {
+        // Received messages are counted for resuming the session, which requires
+        // them to arrive in the order they were sent.
+        assert!(!self.cfg.session_resumption, "session resumption is not supported over QUIC");
+
+        let (transport_sink, transport_stream) =
+            lanes(&connection, self.max_frame_length()).await.map_err(ChMuxError::StreamError)?;
+        let (conn, client, listener) = self.mux(transport_sink, transport_stream).await?;
+        conn.peer_info.set_addr(connection.remote_address());
+
+        Ok((conn, client, listener))
+    }
+}
+
+impl ConnectOptions {
+    /// Establishes a connection over a [QUIC connection](quinn::Connection) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The client side of the QUIC connection opens a fixed number of bidirectional streams,
+    /// which are accepted by the server side.
+    /// Each [chmux](crate::chmux) port is assigned to one of these streams, so that
+    /// packet loss only delays the ports sharing the affected stream instead of all ports
+    /// of the connection.
+    /// Messages of a single port are always delivered in order.
+    ///
+    /// The remote address of the QUIC connection is available from [Connect::peer_info].
+    ///
+    /// Since messages of different ports are received in a different order than they were sent,
+    /// the connection cannot be [resumed](Self::reconnect).
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid or
+    /// [session resumption](crate::Cfg::session_resumption) is enabled.
+    #[cfg_attr(docsrs, doc(cfg(feature = "quic")))]
+    pub async fn quic<'transport, Tx, Rx, Codec>(
+        self, connection: Connection,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.quic_mux(connection).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+impl<Hello> ConnectOptions<(), WithHello<Hello>>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a connection over a [QUIC connection](quinn::Connection),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// See [quic](ConnectOptions::quic) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "quic")))]
+    pub async fn quic<'transport, Tx, Rx, Codec>(
+        self, connection: Connection,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Hello),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.quic_mux(connection).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a connection over a [QUIC connection](quinn::Connection) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This is a shorthand for [ConnectOptions::quic], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "quic")))]
+    pub async fn quic<Tx, Rx, Codec>(
+        cfg: crate::Cfg, connection: Connection,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).quic(connection).await
+    }
+}
+
+/// Opens or accepts the QUIC streams of a connection, depending on its side,
+/// and combines them into lanes.
+async fn lanes(connection: &Connection, max_frame_length: usize) -> io::Result<Lanes> {
+    let streams = match connection.side() {
+        Side::Client => open_streams(connection).await?,
+        Side::Server => accept_streams(connection).await?,
+    };
+
+    let (sinks, streams): (Vec<_>, Vec<_>) =
+        streams.into_iter().map(|(send, recv)| io_framed_with_limit(max_frame_length, recv, send)).unzip();
+    Ok((LaneSink::new(sinks), LaneStream::new(streams)))
+}
+
+/// Opens the QUIC streams of a connection.
+///
+/// Each stream starts with the total number of streams and its index.
+async fn open_streams(connection: &Connection) -> io::Result<Vec<(SendStream, RecvStream)>> {
+    let mut streams = Vec::new();
+    for index in 0..STREAMS {
+        let (mut send, recv) = connection.open_bi().await?;
+        send.write_all(&[STREAMS, index]).await?;
+        streams.push((send, recv));
+    }
+    Ok(streams)
+}
+
+/// Accepts the QUIC streams of a connection.
+async fn accept_streams(connection: &Connection) -> io::Result<Vec<(SendStream, RecvStream)>> {
+    let mut streams: Vec<Option<(SendStream, RecvStream)>> = Vec::new();
+    loop {
+        let (send, mut recv) = connection.accept_bi().await?;
+        let mut header = [0; 2];
+        recv.read_exact(&mut header).await.map_err(io::Error::other)?;
+        let [count, index] = header;
+
+        if count == 0 || index >= count || (!streams.is_empty() && streams.len() != count as usize) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid QUIC stream header"));
+        }
+        if streams.is_empty() {
+            streams.resize_with(count.into(), || None);
+        }
+        if streams[index as usize].replace((send, recv)).is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "duplicate QUIC stream"));
+        }
+
+        if streams.iter().all(Option::is_some) {
+            return Ok(streams.into_iter().map(Option::unwrap).collect());
+        }
+    }
+}
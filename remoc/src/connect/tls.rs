@@ -0,0 +1,215 @@
+//! TLS transport.
+
+use bytes::Bytes;
+use futures::{FutureExt, future::BoxFuture};
+use std::{
+    fmt, io,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite, Join};
+use tokio_rustls::{
+    TlsAcceptor, TlsConnector, TlsStream,
+    rustls::{self, pki_types::ServerName},
+};
+
+use super::{
+    Connect, ConnectError, ConnectOptions, io_framed_with_limit,
+    options::{IoSecureOption, SecureIo, SecureIoFn, sealed},
+};
+use crate::{RemoteSend, chmux::ChMuxError, codec, exec::time::timeout, rch::base};
+
+/// TLS client configuration of [ConnectOptions].
+///
+/// See [ConnectOptions::tls_client] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+#[derive(Clone)]
+pub struct TlsClient {
+    config: Arc<rustls::ClientConfig>,
+    server_name: ServerName<'static>,
+}
+
+impl fmt::Debug for TlsClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TlsClient").field("server_name", &self.server_name).finish_non_exhaustive()
+    }
+}
+
+impl TlsClient {
+    pub(super) fn new(config: Arc<rustls::ClientConfig>, server_name: ServerName<'static>) -> Self {
+        Self { config, server_name }
+    }
+}
+
+/// TLS server configuration of [ConnectOptions].
+///
+/// See [ConnectOptions::tls_server] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+#[derive(Clone)]
+pub struct TlsServer {
+    config: Arc<rustls::ServerConfig>,
+}
+
+impl fmt::Debug for TlsServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TlsServer").finish_non_exhaustive()
+    }
+}
+
+impl TlsServer {
+    pub(super) fn new(config: Arc<rustls::ServerConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl sealed::Sealed for TlsClient {}
+impl sealed::Sealed for TlsServer {}
+
+impl<'transport, Read, Write> IoSecureOption<'transport, Read, Write> for TlsClient
+where
+    Read: AsyncRead + Send + Sync + Unpin + 'transport,
+    Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+{
+    fn into_fn(self, cfg: &crate::Cfg) -> Option<SecureIoFn<'transport, Read, Write>> {
+        let Self { config, server_name } = self;
+        let (timeout, max_frame_length) = (cfg.connection_timeout, cfg.max_frame_length().try_into().unwrap());
+        let connector = TlsConnector::from(config);
+        let certificate = Arc::new(OnceLock::new());
+        Some(Arc::new(move |input, output| {
+            let stream = connector.connect(server_name.clone(), tokio::io::join(input, output));
+            secure(stream.map(|res| res.map(TlsStream::from)), timeout, max_frame_length, certificate.clone())
+        }))
+    }
+}
+
+impl<'transport, Read, Write> IoSecureOption<'transport, Read, Write> for TlsServer
+where
+    Read: AsyncRead + Send + Sync + Unpin + 'transport,
+    Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+{
+    fn into_fn(self, cfg: &crate::Cfg) -> Option<SecureIoFn<'transport, Read, Write>> {
+        let (timeout, max_frame_length) = (cfg.connection_timeout, cfg.max_frame_length().try_into().unwrap());
+        let acceptor = TlsAcceptor::from(self.config);
+        let certificate = Arc::new(OnceLock::new());
+        Some(Arc::new(move |input, output| {
+            let stream = acceptor.accept(tokio::io::join(input, output));
+            secure(stream.map(|res| res.map(TlsStream::from)), timeout, max_frame_length, certificate.clone())
+        }))
+    }
+}
+
+/// Performs the TLS handshake within the timeout and frames the encrypted stream.
+///
+/// The certificate of the remote endpoint presented during the first handshake is stored in
+/// `certificate` and a later handshake over a new transport fails if it presents a different one.
+fn secure<'transport, Read, Write>(
+    stream: impl Future<Output = io::Result<TlsStream<Join<Read, Write>>>> + Send + 'transport,
+    timeout_dur: Option<Duration>, max_frame_length: usize, certificate: Arc<OnceLock<Option<Bytes>>>,
+) -> BoxFuture<'transport, Result<SecureIo<'transport>, ChMuxError<io::Error, io::Error>>>
+where
+    Read: AsyncRead + Send + Sync + Unpin + 'transport,
+    Write: AsyncWrite + Send + Sync + Unpin + 'transport,
+{
+    async move {
+        let stream = match timeout_dur {
+            Some(dur) => timeout(dur, stream).await.map_err(|_| ChMuxError::Timeout)?,
+            None => stream.await,
+        }
+        .map_err(ChMuxError::StreamError)?;
+
+        let certificates: Vec<Bytes> = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .map(|cert| Bytes::copy_from_slice(cert))
+            .collect();
+        let end_entity = certificates.first().cloned();
+        if *certificate.get_or_init(|| end_entity.clone()) != end_entity {
+            return Err(ChMuxError::StreamError(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "remote endpoint presented a different certificate",
+            )));
+        }
+
+        let (input, output) = tokio::io::split(stream);
+        let (transport_sink, transport_stream) = io_framed_with_limit(max_frame_length, input, output);
+        let secured: SecureIo<'transport> = (
+            Box::pin(transport_sink),
+            Box::pin(transport_stream),
+            Box::new(move |peer_info| peer_info.set_certificates(certificates)),
+        );
+        Ok(secured)
+    }
+    .boxed()
+}
+
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a TLS connection as client over an IO transport, such as a [TCP stream], and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The TLS handshake is performed using the provided rustls configuration,
+    /// verifying that the server certificate is valid for `server_name`.
+    /// Then a [chmux](crate::chmux) connection is established over the encrypted stream
+    /// and a remote channel is opened, as done by [io](Self::io).
+    ///
+    /// This is a shorthand for [ConnectOptions::tls_client], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    ///
+    /// [TCP stream]: https://docs.rs/tokio/1.12.0/tokio/net/struct.TcpStream.html
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    pub async fn tls_client<IO, Tx, Rx, Codec>(
+        cfg: crate::Cfg, tls_cfg: Arc<rustls::ClientConfig>, server_name: ServerName<'static>, io: IO,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        IO: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (input, output) = tokio::io::split(io);
+        ConnectOptions::new(cfg).tls_client(tls_cfg, server_name).io(input, output).await
+    }
+
+    /// Accepts a TLS connection as server over an IO transport, such as a [TCP stream], and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The TLS handshake is performed using the provided rustls configuration.
+    /// Then a [chmux](crate::chmux) connection is established over the encrypted stream
+    /// and a remote channel is opened, as done by [io](Self::io).
+    ///
+    /// This is a shorthand for [ConnectOptions::tls_server], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    ///
+    /// [TCP stream]: https://docs.rs/tokio/1.12.0/tokio/net/struct.TcpStream.html
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    pub async fn tls_server<IO, Tx, Rx, Codec>(
+        cfg: crate::Cfg, tls_cfg: Arc<rustls::ServerConfig>, io: IO,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        IO: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (input, output) = tokio::io::split(io);
+        ConnectOptions::new(cfg).tls_server(tls_cfg).io(input, output).await
+    }
+}
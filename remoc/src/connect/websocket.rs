@@ -0,0 +1,481 @@
+//! WebSocket transport.
+
+use std::io;
+
+use super::{
+    Connect, ConnectError, ConnectOptions,
+    options::{WithHello, exchange_hello},
+};
+use crate::{RemoteSend, codec, rch::base};
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use bytes::Bytes;
+    use futures::{FutureExt, Sink, SinkExt, Stream, StreamExt, future};
+    use std::io;
+    use tokio::io::{AsyncRead, AsyncWrite};
+    use tokio_tungstenite::{
+        WebSocketStream,
+        tungstenite::{Error, Message},
+    };
+
+    use super::super::{Connect, ConnectError, options::Options};
+    use crate::chmux;
+
+    /// Converts a WebSocket error into an IO error.
+    fn io_error(err: Error) -> io::Error {
+        match err {
+            Error::Io(err) => err,
+            err => io::Error::other(err),
+        }
+    }
+
+    /// Checks that the message and frame size limits of the WebSocket stream are
+    /// set to the maximum frame length, as done by [Cfg::websocket_config](crate::Cfg::websocket_config).
+    fn check_limits<S>(websocket: &WebSocketStream<S>, max_frame_length: usize) -> io::Result<()> {
+        let config = websocket.get_config();
+        if config.max_message_size != Some(max_frame_length) || config.max_frame_size != Some(max_frame_length) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "WebSocket message and frame size limits must be {max_frame_length} bytes, \
+                     use Cfg::websocket_config for the handshake"
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Maps binary WebSocket messages to chmux frames.
+    fn transport<S>(
+        websocket: WebSocketStream<S>,
+    ) -> (
+        impl Sink<Bytes, Error = io::Error> + Send + Sync + Unpin,
+        impl Stream<Item = Result<Bytes, io::Error>> + Send + Sync + Unpin,
+    )
+    where
+        S: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        let (sink, stream) = websocket.split();
+        let sink = sink.with(|data| future::ok(Message::Binary(data))).sink_map_err(io_error);
+        let stream = stream.filter_map(|msg| {
+            future::ready(match msg {
+                Ok(Message::Binary(data)) => Some(Ok(data)),
+                Ok(Message::Text(_)) => {
+                    Some(Err(io::Error::new(io::ErrorKind::InvalidData, "received text WebSocket message")))
+                }
+                Ok(Message::Ping(_) | Message::Pong(_) | Message::Close(_) | Message::Frame(_)) => None,
+                Err(err) => Some(Err(io_error(err))),
+            })
+        });
+        (sink, stream)
+    }
+
+    impl<'transport, S> Options<'transport, WebSocketStream<S>>
+    where
+        S: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'transport,
+    {
+        /// Establishes the chmux connection over the WebSocket stream.
+        pub(super) async fn websocket_mux(
+            self, websocket: WebSocketStream<S>,
+        ) -> Result<
+            (Connect<'transport, io::Error, io::Error>, chmux::Client, chmux::Listener),
+            ConnectError<io::Error, io::Error>,
+        > {
+            let max_frame_length = self.max_frame_length();
+            check_limits(&websocket, max_frame_length).map_err(chmux::ChMuxError::StreamError)?;
+
+            let (transport_sink, transport_stream) = transport(websocket);
+            self.map_transport(move |websocket| {
+                let res = match check_limits(&websocket, max_frame_length) {
+                    Ok(()) => Some(transport(websocket)),
+                    Err(err) => {
+                        tracing::warn!(%err, "new WebSocket stream rejected");
+                        None
+                    }
+                };
+                future::ready(res).boxed()
+            })
+            .mux(transport_sink, transport_stream)
+            .await
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<Reconnect> ConnectOptions<Reconnect> {
+    /// Establishes a connection over a [WebSocket stream](tokio_tungstenite::WebSocketStream) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The WebSocket handshake must have been completed using the WebSocket configuration
+    /// obtained from [Cfg::websocket_config](crate::Cfg::websocket_config), for example by using
+    /// `tokio_tungstenite::connect_async_with_config` on the client and
+    /// `tokio_tungstenite::accept_async_with_config` on the server.
+    /// This limits the size of received messages to the maximum chmux frame length;
+    /// a stream with other limits is rejected.
+    /// Each chmux frame is sent as a binary WebSocket message.
+    /// Receiving a text message fails the connection.
+    /// Use a `wss://` URL to encrypt the connection.
+    ///
+    /// When the connection is [resumed](Self::reconnect), the reconnect function must provide
+    /// new WebSocket streams.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub async fn websocket<'transport, S, Tx, Rx, Codec>(
+        self, websocket: tokio_tungstenite::WebSocketStream<S>,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Reconnect: super::ReconnectOption<'transport, tokio_tungstenite::WebSocketStream<S>>,
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.websocket_mux(websocket).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<Reconnect, Hello> ConnectOptions<Reconnect, WithHello<Hello>>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a connection over a [WebSocket stream](tokio_tungstenite::WebSocketStream),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// See [websocket](ConnectOptions::websocket) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub async fn websocket<'transport, S, Tx, Rx, Codec>(
+        self, websocket: tokio_tungstenite::WebSocketStream<S>,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Hello),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Reconnect: super::ReconnectOption<'transport, tokio_tungstenite::WebSocketStream<S>>,
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), ()) = self.resolve();
+        let (mut connection, client, mut listener) = options.websocket_mux(websocket).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a connection over a [WebSocket stream](tokio_tungstenite::WebSocketStream) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This is a shorthand for [ConnectOptions::websocket], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub async fn websocket<S, Tx, Rx, Codec>(
+        cfg: crate::Cfg, websocket: tokio_tungstenite::WebSocketStream<S>,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).websocket(websocket).await
+    }
+}
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+mod js {
+    use bytes::Bytes;
+    use futures::{
+        Sink, SinkExt, Stream, StreamExt,
+        channel::{mpsc, oneshot},
+        future, stream,
+    };
+    use js_sys::{ArrayBuffer, Uint8Array};
+    use std::{
+        io,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+    use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+    use web_sys::{BinaryType, MessageEvent, WebSocket};
+
+    use crate::exec;
+
+    /// Interval for checking whether the data buffered by the WebSocket has been transmitted.
+    const BUFFERED_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Waits for the WebSocket to become open.
+    async fn opened(websocket: &WebSocket) -> io::Result<()> {
+        if websocket.ready_state() == WebSocket::CONNECTING {
+            let (open_tx, open_rx) = oneshot::channel();
+            let mut open_tx = Some(open_tx);
+            let on_event = Closure::<dyn FnMut(JsValue)>::new(move |_| {
+                if let Some(open_tx) = open_tx.take() {
+                    let _ = open_tx.send(());
+                }
+            });
+            websocket.set_onopen(Some(on_event.as_ref().unchecked_ref()));
+            websocket.set_onerror(Some(on_event.as_ref().unchecked_ref()));
+            websocket.set_onclose(Some(on_event.as_ref().unchecked_ref()));
+            let _ = open_rx.await;
+            websocket.set_onopen(None);
+            websocket.set_onerror(None);
+            websocket.set_onclose(None);
+        }
+
+        match websocket.ready_state() {
+            WebSocket::OPEN => Ok(()),
+            _ => Err(io::Error::new(io::ErrorKind::ConnectionRefused, "WebSocket failed to open")),
+        }
+    }
+
+    /// Waits until the data buffered by the WebSocket does not exceed `max_buffered` bytes.
+    ///
+    /// Returns whether the WebSocket is still open.
+    async fn drained(websocket: &WebSocket, max_buffered: usize) -> bool {
+        loop {
+            if websocket.ready_state() != WebSocket::OPEN {
+                return false;
+            }
+            if websocket.buffered_amount() as usize <= max_buffered {
+                return true;
+            }
+            exec::time::sleep(BUFFERED_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Maps binary WebSocket messages to chmux frames.
+    ///
+    /// Since the browser WebSocket cannot be sent between threads, it is owned by a local task
+    /// that is connected to the returned sink and stream by channels.
+    ///
+    /// Outgoing frames are only passed to the WebSocket while it buffers at most `max_buffered` bytes,
+    /// thus the sink becomes ready only when its queue has room again.
+    /// Since a browser WebSocket cannot apply backpressure to incoming messages,
+    /// the connection fails when more than `receive_queue` messages are waiting to be received.
+    pub(super) async fn transport(
+        websocket: WebSocket, send_queue: usize, receive_queue: usize, max_buffered: usize,
+    ) -> io::Result<(
+        impl Sink<Bytes, Error = io::Error> + Send + Sync + Unpin,
+        impl Stream<Item = Result<Bytes, io::Error>> + Send + Sync + Unpin,
+    )> {
+        websocket.set_binary_type(BinaryType::Arraybuffer);
+        opened(&websocket).await?;
+
+        let (in_tx, in_rx) = mpsc::channel(receive_queue);
+        let overflow = Arc::new(AtomicBool::new(false));
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+            let mut in_tx = in_tx.clone();
+            let overflow = overflow.clone();
+            move |event: MessageEvent| {
+                let msg = match event.data().dyn_into::<ArrayBuffer>() {
+                    Ok(buf) => Ok(Bytes::from(Uint8Array::new(&buf).to_vec())),
+                    Err(_) => Err(io::Error::new(io::ErrorKind::InvalidData, "received text WebSocket message")),
+                };
+                if let Err(err) = in_tx.try_send(msg)
+                    && err.is_full()
+                {
+                    overflow.store(true, Ordering::Relaxed);
+                    in_tx.close_channel();
+                }
+            }
+        });
+        let on_error = Closure::<dyn FnMut(JsValue)>::new({
+            let mut in_tx = in_tx.clone();
+            move |_| {
+                let _ = in_tx.try_send(Err(io::Error::new(io::ErrorKind::ConnectionReset, "WebSocket error")));
+                in_tx.close_channel();
+            }
+        });
+        let on_close = Closure::<dyn FnMut(JsValue)>::new({
+            let mut in_tx = in_tx;
+            move |_| in_tx.close_channel()
+        });
+        websocket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        websocket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        websocket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        let (out_tx, mut out_rx) = mpsc::channel::<Bytes>(send_queue);
+        wasm_bindgen_futures::spawn_local(async move {
+            while let Some(data) = out_rx.next().await {
+                if !drained(&websocket, max_buffered).await || websocket.send_with_u8_array(&data).is_err() {
+                    break;
+                }
+            }
+
+            websocket.set_onmessage(None);
+            websocket.set_onerror(None);
+            websocket.set_onclose(None);
+            let _ = websocket.close();
+            drop((on_message, on_error, on_close));
+        });
+
+        let sink = out_tx.sink_map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "WebSocket closed"));
+        let overflowed =
+            stream::once(future::lazy(move |_| overflow.load(Ordering::Relaxed))).filter_map(|overflow| {
+                future::ready(
+                    overflow.then(|| {
+                        Err(io::Error::new(io::ErrorKind::OutOfMemory, "WebSocket receive queue overflow"))
+                    }),
+                )
+            });
+        Ok((sink, in_rx.chain(overflowed)))
+    }
+}
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+impl ConnectOptions {
+    /// Establishes a connection over a browser [WebSocket](web_sys::WebSocket) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// If the WebSocket is still connecting, this waits until it is open.
+    /// Each chmux frame is sent as a binary WebSocket message.
+    /// Receiving a text message fails the connection.
+    /// Frames are only sent while the data buffered by the WebSocket does not exceed
+    /// the [transport send queue](crate::Cfg::transport_send_queue) times the
+    /// [maximum frame length](crate::Cfg::max_frame_length).
+    /// Since a browser WebSocket cannot apply backpressure to received messages, the connection
+    /// fails when more messages than the [transport receive queue](crate::Cfg::transport_receive_queue)
+    /// holds are waiting to be processed.
+    /// Since a browser WebSocket cannot be sent between threads,
+    /// [resuming](Self::reconnect) the connection is not supported.
+    ///
+    /// The WebSocket is closed when the connection terminates.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "websocket", feature = "js"))))]
+    pub async fn websocket<'transport, Tx, Rx, Codec>(
+        self, websocket: web_sys::WebSocket,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, (), ()) = self.resolve();
+        let (transport_sink, transport_stream) = js::transport(
+            websocket,
+            options.cfg.transport_send_queue,
+            options.cfg.transport_receive_queue,
+            options.cfg.transport_send_queue * options.max_frame_length(),
+        )
+        .await
+        .map_err(crate::chmux::ChMuxError::StreamError)?;
+        let (mut connection, client, mut listener) = options.mux(transport_sink, transport_stream).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx))
+    }
+}
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+impl<Hello> ConnectOptions<(), WithHello<Hello>>
+where
+    Hello: RemoteSend,
+{
+    /// Establishes a connection over a browser [WebSocket](web_sys::WebSocket),
+    /// exchanges the [hello](Self::hello) with the remote endpoint and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver)
+    /// together with the hello of the remote endpoint.
+    ///
+    /// See [websocket](ConnectOptions::websocket) for details.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "websocket", feature = "js"))))]
+    pub async fn websocket<'transport, Tx, Rx, Codec>(
+        self, websocket: web_sys::WebSocket,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Hello),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (options, WithHello(hello), ()) = self.resolve();
+        let (transport_sink, transport_stream) = js::transport(
+            websocket,
+            options.cfg.transport_send_queue,
+            options.cfg.transport_receive_queue,
+            options.cfg.transport_send_queue * options.max_frame_length(),
+        )
+        .await
+        .map_err(crate::chmux::ChMuxError::StreamError)?;
+        let (mut connection, client, mut listener) = options.mux(transport_sink, transport_stream).await?;
+        let remote_hello =
+            connection.drive(exchange_hello::<_, Codec, _, _>(&client, &mut listener, hello)).await?;
+        let (tx, rx) = connection.drive(base::connect(&client, &mut listener)).await?;
+        Ok((connection, tx, rx, remote_hello))
+    }
+}
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a connection over a browser [WebSocket](web_sys::WebSocket) and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This is a shorthand for [ConnectOptions::websocket], which can be combined
+    /// with other connection options.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "websocket", feature = "js"))))]
+    pub async fn websocket<Tx, Rx, Codec>(
+        cfg: crate::Cfg, websocket: web_sys::WebSocket,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        ConnectOptions::new(cfg).websocket(websocket).await
+    }
+}
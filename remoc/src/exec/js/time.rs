@@ -5,6 +5,7 @@
 use std::{
     fmt,
     future::{Future, IntoFuture},
+    ops::Add,
     pin::Pin,
     task::{Context, Poll},
     time::Duration,
@@ -267,3 +268,41 @@ where
 {
     Timeout { sleep: sleep(duration), future: future.into_future() }
 }
+
+/// A measurement of the system clock.
+///
+/// JavaScript provides no monotonic clock that is available in all environments,
+/// thus this is based on the system time and durations saturate at zero.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Instant(f64);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    pub fn now() -> Self {
+        Self(js_sys::Date::now())
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one,
+    /// or zero duration if that instant is later than this one.
+    pub fn duration_since(&self, earlier: Self) -> Duration {
+        Duration::from_secs_f64((self.0 - earlier.0).max(0.) / 1000.)
+    }
+
+    /// Returns the amount of time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+
+    /// Returns the instant after the specified duration has elapsed from this instant.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        Some(*self + duration)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        Self(self.0 + rhs.as_secs_f64() * 1000.)
+    }
+}
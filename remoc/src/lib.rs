@@ -103,6 +103,9 @@
 //! Remoc uses the [Tracing crate](tracing) for logging of events.
 //! Setting the log level to `TRACE` logs multiplexer lifetime events and messages as they are being processed.
 //!
+//! Enable the `trace-context` crate feature to propagate the context of spans to remote endpoints,
+//! as described in the documentation of the `trace` module.
+//!
 //! # Example
 //!
 //! This is a short example; for a fully worked remote trait calling (RTC) example
@@ -246,9 +249,30 @@ pub use remote_send::RemoteSend;
 
 #[cfg(feature = "rch")]
 mod connect;
+#[cfg(all(feature = "process", not(target_family = "wasm")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+pub use connect::ChildProcess;
 #[cfg(feature = "rch")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
-pub use connect::{Connect, ConnectError, LoopbackConnect};
+pub use connect::{
+    Connect, ConnectError, ConnectOptions, HelloError, IoSecureOption, LoopbackConnect, ReconnectOption,
+    WithHello, WithReconnect,
+};
+#[cfg(feature = "tls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub use connect::{TlsClient, TlsServer};
+
+#[cfg(feature = "tls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub use tokio_rustls::rustls;
+
+#[cfg(all(feature = "quic", not(target_family = "wasm")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "quic")))]
+pub use quinn;
+
+#[cfg(all(feature = "websocket", not(target_family = "wasm")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub use tokio_tungstenite;
 
 #[cfg(feature = "rch")]
 mod connect_ext;
@@ -272,6 +296,18 @@ pub mod robs;
 #[cfg_attr(docsrs, doc(cfg(feature = "rtc")))]
 pub mod rtc;
 
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub mod registry;
+
+#[cfg(feature = "trace-context")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+pub mod trace;
+
+#[cfg(feature = "noise")]
+#[cfg_attr(docsrs, doc(cfg(feature = "noise")))]
+pub mod noise;
+
 // Re-export serde for remoc_macro used by rtc.
 #[doc(hidden)]
 #[cfg(feature = "rtc")]
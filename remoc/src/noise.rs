@@ -0,0 +1,428 @@
+//! Encrypted and mutually authenticated connections using the Noise protocol.
+//!
+//! When the `noise` crate feature is enabled, [ConnectOptions::noise](crate::ConnectOptions::noise)
+//! establishes a secure channel over the physical transport before the [chmux](crate::chmux)
+//! connection is started.
+//! It can be combined with the other [connection options](crate::ConnectOptions) and
+//! [Connect::framed_with_noise](crate::Connect::framed_with_noise) and
+//! [Connect::io_with_noise](crate::Connect::io_with_noise) are shorthands for it.
+//! This is useful for transports that provide no security of their own, such as message queues,
+//! relays or serial links.
+//!
+//! The secure channel uses the `Noise_XX_25519_ChaChaPoly_BLAKE2s` protocol.
+//! Each endpoint has a static [keypair](Keypair) and both endpoints learn the static public key
+//! of each other during the handshake.
+//! One endpoint must act as [initiator](Noise::initiator) and the other as [responder](Noise::responder).
+//! The authenticated public key of the remote endpoint is available from
+//! [PeerInfo::public_key](crate::chmux::PeerInfo::public_key).
+//! Use [Noise::with_verifier] to reject remote endpoints with unknown keys during the handshake.
+//!
+//! Each chmux frame is encrypted and authenticated.
+//! Frames longer than the maximum Noise message size are split into multiple transport messages.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{Sink, SinkExt, Stream, StreamExt, ready};
+use snow::{Builder, HandshakeState, StatelessTransportState, params::NoiseParams};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt, io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{
+    chmux::{ChMuxError, HandshakeError},
+    exec::time::timeout,
+};
+
+/// Noise protocol used for the secure channel.
+const PROTOCOL: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// Prologue binding the handshake to Remoc.
+const PROLOGUE: &[u8] = b"remoc";
+
+/// Maximum length of a Noise message.
+pub(crate) const MAX_MSG_LEN: usize = 65535;
+
+/// Length of the authentication tag appended to each encrypted message.
+const TAG_LEN: usize = 16;
+
+/// Maximum length of a fragment of a chmux frame within an encrypted message.
+const MAX_FRAGMENT_LEN: usize = MAX_MSG_LEN - TAG_LEN - 1;
+
+/// Flag byte indicating that more fragments of the frame follow.
+const MORE_FRAGMENTS: u8 = 1;
+
+/// Flag byte indicating the last fragment of a frame.
+const LAST_FRAGMENT: u8 = 0;
+
+fn params() -> NoiseParams {
+    PROTOCOL.parse().unwrap()
+}
+
+/// A static X25519 keypair identifying an endpoint.
+#[derive(Clone)]
+pub struct Keypair {
+    private: Vec<u8>,
+    public: Vec<u8>,
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Keypair").field("public", &self.public).finish_non_exhaustive()
+    }
+}
+
+impl Keypair {
+    /// Generates a new random keypair.
+    pub fn generate() -> Self {
+        let snow::Keypair { private, public } =
+            Builder::new(params()).generate_keypair().expect("generating Noise keypair failed");
+        Self { private, public }
+    }
+
+    /// Creates a keypair from an existing private and public key, each 32 bytes long.
+    pub fn new(private: impl Into<Vec<u8>>, public: impl Into<Vec<u8>>) -> Self {
+        Self { private: private.into(), public: public.into() }
+    }
+
+    /// The private key.
+    pub fn private(&self) -> &[u8] {
+        &self.private
+    }
+
+    /// The public key.
+    pub fn public(&self) -> &[u8] {
+        &self.public
+    }
+}
+
+/// Verifier of the static public key of the remote endpoint.
+type Verifier = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Configuration of a Noise secure channel.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone)]
+pub struct Noise {
+    initiator: bool,
+    keypair: Keypair,
+    verifier: Option<Verifier>,
+}
+
+impl fmt::Debug for Noise {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Noise")
+            .field("initiator", &self.initiator)
+            .field("keypair", &self.keypair)
+            .field("verifier", &self.verifier.is_some())
+            .finish()
+    }
+}
+
+impl Noise {
+    /// Configuration for the endpoint that starts the handshake, using the specified static keypair.
+    pub fn initiator(keypair: Keypair) -> Self {
+        Self { initiator: true, keypair, verifier: None }
+    }
+
+    /// Configuration for the endpoint that answers the handshake, using the specified static keypair.
+    pub fn responder(keypair: Keypair) -> Self {
+        Self { initiator: false, keypair, verifier: None }
+    }
+
+    /// Sets a function that is called with the static public key of the remote endpoint
+    /// during the handshake.
+    ///
+    /// If it returns `false`, the handshake fails with a [HandshakeError].
+    /// By default all remote endpoints are accepted.
+    pub fn with_verifier(mut self, verifier: impl Fn(&[u8]) -> bool + Send + Sync + 'static) -> Self {
+        self.verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// The static keypair of this endpoint.
+    pub fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+
+    /// Performs the handshake over the transport.
+    ///
+    /// Returns the encrypting transport and the static public key of the remote endpoint.
+    /// The handshake fails with [ChMuxError::Timeout] if it does not complete within `timeout_dur`.
+    pub(crate) async fn handshake<TransportSink, TransportStream, TransportSinkError, TransportStreamError>(
+        &self, mut transport_sink: TransportSink, mut transport_stream: TransportStream, max_frame_length: usize,
+        timeout_dur: Option<Duration>,
+    ) -> Result<
+        (NoiseSink<TransportSink>, NoiseStream<TransportStream>, Bytes),
+        ChMuxError<TransportSinkError, TransportStreamError>,
+    >
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Unpin,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Unpin,
+    {
+        let exchange = self.exchange(&mut transport_sink, &mut transport_stream);
+        let (transport, remote_key) = match timeout_dur {
+            Some(dur) => timeout(dur, exchange).await.map_err(|_| ChMuxError::Timeout)?,
+            None => exchange.await,
+        }?;
+
+        let sink =
+            NoiseSink { inner: transport_sink, transport: transport.clone(), nonce: 0, queue: VecDeque::new() };
+        let stream =
+            NoiseStream { inner: transport_stream, transport, nonce: 0, buf: BytesMut::new(), max_frame_length };
+        Ok((sink, stream, remote_key))
+    }
+
+    /// Exchanges the handshake messages.
+    ///
+    /// Returns the transport state and the static public key of the remote endpoint.
+    async fn exchange<TransportSink, TransportStream, TransportSinkError, TransportStreamError>(
+        &self, transport_sink: &mut TransportSink, transport_stream: &mut TransportStream,
+    ) -> Result<(Arc<StatelessTransportState>, Bytes), ChMuxError<TransportSinkError, TransportStreamError>>
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Unpin,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Unpin,
+    {
+        let builder = Builder::new(params())
+            .local_private_key(&self.keypair.private)
+            .and_then(|builder| builder.prologue(PROLOGUE))
+            .map_err(noise_failed)?;
+        let mut state = if self.initiator { builder.build_initiator() } else { builder.build_responder() }
+            .map_err(noise_failed)?;
+
+        // Pattern XX: -> e, <- e, ee, s, es, -> s, se
+        let mut write_next = self.initiator;
+        let mut verified = false;
+        while !state.is_handshake_finished() {
+            if write_next {
+                let msg = write_handshake(&mut state)?;
+                transport_sink.send(msg).await.map_err(ChMuxError::SinkError)?;
+            } else {
+                let msg = match transport_stream.next().await {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => return Err(ChMuxError::StreamError(err)),
+                    None => return Err(ChMuxError::StreamClosed),
+                };
+                read_handshake(&mut state, &msg)?;
+            }
+            write_next = !write_next;
+
+            // Verify the remote static key as soon as it is known.
+            if let (false, Some(verifier), Some(remote_key)) =
+                (verified, &self.verifier, state.get_remote_static())
+            {
+                if !verifier(remote_key) {
+                    return Err(ChMuxError::Handshake(HandshakeError::failed(
+                        "static key of remote endpoint was rejected",
+                    )));
+                }
+                verified = true;
+            }
+        }
+
+        let remote_key = Bytes::copy_from_slice(
+            state.get_remote_static().ok_or_else(|| noise_failed("remote endpoint provided no static key"))?,
+        );
+        let transport = Arc::new(state.into_stateless_transport_mode().map_err(noise_failed)?);
+        Ok((transport, remote_key))
+    }
+}
+
+fn noise_failed<SinkError, StreamError>(err: impl fmt::Display) -> ChMuxError<SinkError, StreamError> {
+    ChMuxError::Handshake(HandshakeError::failed(format_args!("Noise handshake failed: {err}")))
+}
+
+fn write_handshake<SinkError, StreamError>(
+    state: &mut HandshakeState,
+) -> Result<Bytes, ChMuxError<SinkError, StreamError>> {
+    let mut buf = vec![0; MAX_MSG_LEN];
+    let len = state.write_message(&[], &mut buf).map_err(noise_failed)?;
+    buf.truncate(len);
+    Ok(buf.into())
+}
+
+fn read_handshake<SinkError, StreamError>(
+    state: &mut HandshakeState, msg: &[u8],
+) -> Result<(), ChMuxError<SinkError, StreamError>> {
+    let mut buf = vec![0; MAX_MSG_LEN];
+    state.read_message(msg, &mut buf).map_err(noise_failed)?;
+    Ok(())
+}
+
+/// An error of a Noise secure channel.
+#[derive(Debug, Clone)]
+pub enum NoiseError<E> {
+    /// The underlying transport failed.
+    Transport(E),
+    /// Encrypting or decrypting a message failed.
+    ///
+    /// Decryption fails if a message was tampered with.
+    Crypto(String),
+    /// A malformed or overlong frame was received.
+    InvalidFrame,
+}
+
+impl<E> fmt::Display for NoiseError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "transport error: {err}"),
+            Self::Crypto(err) => write!(f, "Noise encryption error: {err}"),
+            Self::InvalidFrame => write!(f, "invalid Noise frame received"),
+        }
+    }
+}
+
+impl<E> Error for NoiseError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NoiseError<io::Error>> for io::Error {
+    fn from(err: NoiseError<io::Error>) -> Self {
+        match err {
+            NoiseError::Transport(err) => err,
+            NoiseError::Crypto(_) | NoiseError::InvalidFrame => {
+                io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+            }
+        }
+    }
+}
+
+/// Encrypts chmux frames before sending them over the transport.
+pub(crate) struct NoiseSink<S> {
+    inner: S,
+    transport: Arc<StatelessTransportState>,
+    nonce: u64,
+    queue: VecDeque<Bytes>,
+}
+
+impl<S> NoiseSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+{
+    fn poll_send_queue(&mut self, cx: &mut Context) -> Poll<Result<(), NoiseError<S::Error>>> {
+        while !self.queue.is_empty() {
+            ready!(self.inner.poll_ready_unpin(cx)).map_err(NoiseError::Transport)?;
+            let msg = self.queue.pop_front().unwrap();
+            self.inner.start_send_unpin(msg).map_err(NoiseError::Transport)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn encrypt(&mut self, flag: u8, fragment: &[u8]) -> Result<(), NoiseError<S::Error>> {
+        let mut plain = Vec::with_capacity(fragment.len() + 1);
+        plain.push(flag);
+        plain.extend_from_slice(fragment);
+
+        let mut msg = vec![0; plain.len() + TAG_LEN];
+        let len = self
+            .transport
+            .write_message(self.nonce, &plain, &mut msg)
+            .map_err(|err| NoiseError::Crypto(err.to_string()))?;
+        msg.truncate(len);
+        self.nonce += 1;
+
+        self.queue.push_back(msg.into());
+        Ok(())
+    }
+}
+
+impl<S> Sink<Bytes> for NoiseSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+{
+    type Error = NoiseError<S::Error>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_queue(cx))?;
+        this.inner.poll_ready_unpin(cx).map_err(NoiseError::Transport)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let mut fragments = item.chunks(MAX_FRAGMENT_LEN).peekable();
+        if fragments.peek().is_none() {
+            return this.encrypt(LAST_FRAGMENT, &[]);
+        }
+        while let Some(fragment) = fragments.next() {
+            let flag = if fragments.peek().is_some() { MORE_FRAGMENTS } else { LAST_FRAGMENT };
+            this.encrypt(flag, fragment)?;
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_queue(cx))?;
+        this.inner.poll_flush_unpin(cx).map_err(NoiseError::Transport)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_queue(cx))?;
+        this.inner.poll_close_unpin(cx).map_err(NoiseError::Transport)
+    }
+}
+
+/// Decrypts chmux frames received from the transport.
+pub(crate) struct NoiseStream<R> {
+    inner: R,
+    transport: Arc<StatelessTransportState>,
+    nonce: u64,
+    buf: BytesMut,
+    max_frame_length: usize,
+}
+
+impl<R, E> Stream for NoiseStream<R>
+where
+    R: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, NoiseError<E>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let msg = match ready!(this.inner.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(err)) => return Poll::Ready(Some(Err(NoiseError::Transport(err)))),
+                None => return Poll::Ready(None),
+            };
+
+            let mut plain = vec![0; msg.len()];
+            let len = match this.transport.read_message(this.nonce, &msg, &mut plain) {
+                Ok(len) => len,
+                Err(err) => return Poll::Ready(Some(Err(NoiseError::Crypto(err.to_string())))),
+            };
+            this.nonce += 1;
+
+            let Some((&flag, fragment)) = plain[..len].split_first() else {
+                return Poll::Ready(Some(Err(NoiseError::InvalidFrame)));
+            };
+            if this.buf.len() + fragment.len() > this.max_frame_length {
+                return Poll::Ready(Some(Err(NoiseError::InvalidFrame)));
+            }
+            this.buf.put_slice(fragment);
+
+            match flag {
+                LAST_FRAGMENT => return Poll::Ready(Some(Ok(this.buf.split().freeze()))),
+                MORE_FRAGMENTS => (),
+                _ => return Poll::Ready(Some(Err(NoiseError::InvalidFrame))),
+            }
+        }
+    }
+}
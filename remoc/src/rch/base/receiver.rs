@@ -15,7 +15,11 @@ use std::{
 };
 use tracing::Instrument;
 
-use super::{super::DEFAULT_MAX_ITEM_SIZE, BIG_DATA_CHUNK_QUEUE, io::ChannelBytesReader};
+use super::{
+    super::{ChannelKind, DEFAULT_MAX_ITEM_SIZE},
+    BIG_DATA_CHUNK_QUEUE,
+    io::ChannelBytesReader,
+};
 use crate::{
     chmux::{self, AnyStorage, Received, RecvChunkError},
     codec::{self, DeserializationError, StreamingUnavailable},
@@ -91,6 +95,8 @@ pub struct PortDeserializer {
         ),
     >,
     storage: AnyStorage,
+    channel_priorities: chmux::ChannelPriorities,
+    peer_info: chmux::PeerInfo,
     tasks: Vec<BoxFuture<'static, ()>>,
 }
 
@@ -100,9 +106,18 @@ impl PortDeserializer {
     }
 
     /// Create a new port deserializer and register it as active.
-    fn start(allocator: chmux::PortAllocator, storage: AnyStorage) -> Rc<RefCell<PortDeserializer>> {
-        let this =
-            Rc::new(RefCell::new(Self { allocator, expected: HashMap::new(), storage, tasks: Vec::new() }));
+    fn start(
+        allocator: chmux::PortAllocator, storage: AnyStorage, channel_priorities: chmux::ChannelPriorities,
+        peer_info: chmux::PeerInfo,
+    ) -> Rc<RefCell<PortDeserializer>> {
+        let this = Rc::new(RefCell::new(Self {
+            allocator,
+            expected: HashMap::new(),
+            storage,
+            channel_priorities,
+            peer_info,
+            tasks: Vec::new(),
+        }));
         let weak = Rc::downgrade(&this);
         Self::INSTANCE.with(move |i| i.replace(weak));
         this
@@ -129,6 +144,9 @@ impl PortDeserializer {
 
     /// Accept the chmux port with the specified remote port number sent from the remote endpoint.
     ///
+    /// The port inherits the send priority of the chmux port the object was received over,
+    /// unless the [priority of the request](chmux::Request::set_priority) is changed by the callback.
+    ///
     /// Returns the local port number and calls the specified function with the received connect request.
     pub fn accept<E>(
         remote_port: u32,
@@ -148,6 +166,37 @@ impl PortDeserializer {
         Ok(local_port_num)
     }
 
+    /// Accept the chmux port for a channel of the specified kind with the specified remote port number
+    /// sent from the remote endpoint.
+    ///
+    /// The port uses the send priority configured for the channel kind by
+    /// [Cfg::channel_priorities](chmux::Cfg::channel_priorities).
+    /// If none is configured, the port inherits the send priority of the chmux port
+    /// the object was received over.
+    ///
+    /// Returns the local port number and calls the specified function with the received connect request.
+    pub fn accept_with_kind<E>(
+        remote_port: u32, kind: ChannelKind,
+        callback: impl FnOnce(chmux::PortNumber, chmux::Request) -> BoxFuture<'static, ()> + Send + 'static,
+    ) -> Result<u32, E>
+    where
+        E: serde::de::Error,
+    {
+        let priority = {
+            let this = Self::instance()?;
+            let this =
+                this.try_borrow().expect("PortDeserializer is referenced multiple times during deserialization");
+            kind.priority(&this.channel_priorities)
+        };
+
+        Self::accept(remote_port, move |local_port, mut request| {
+            if let Some(priority) = priority {
+                request.set_priority(priority);
+            }
+            callback(local_port, request)
+        })
+    }
+
     /// Returns the data storage of the channel multiplexer.
     pub fn storage<E>() -> Result<AnyStorage, E>
     where
@@ -160,6 +209,18 @@ impl PortDeserializer {
         Ok(this.storage.clone())
     }
 
+    /// Returns information about the remote endpoint of the channel multiplexer.
+    pub fn peer_info<E>() -> Result<chmux::PeerInfo, E>
+    where
+        E: serde::de::Error,
+    {
+        let this = Self::instance()?;
+        let this =
+            this.try_borrow().expect("PortDeserializer is referenced multiple times during deserialization");
+
+        Ok(this.peer_info.clone())
+    }
+
     /// Spawn a task.
     pub fn spawn<E>(task: impl Future<Output = ()> + Send + 'static) -> Result<(), E>
     where
@@ -254,11 +315,18 @@ where
                             // Start deserialization thread.
                             let allocator = self.receiver.port_allocator();
                             let handle_storage = self.receiver.storage();
+                            let channel_priorities = self.receiver.channel_priorities();
+                            let peer_info = self.receiver.peer_info();
                             let (tx, rx) = tokio::sync::mpsc::channel(BIG_DATA_CHUNK_QUEUE);
                             let task = task::spawn_blocking(move || {
                                 let mut cbr = ChannelBytesReader::new(rx);
 
-                                let pds_ref = PortDeserializer::start(allocator, handle_storage);
+                                let pds_ref = PortDeserializer::start(
+                                    allocator,
+                                    handle_storage,
+                                    channel_priorities,
+                                    peer_info,
+                                );
                                 let item = <Codec as codec::Codec>::deserialize(IoReader::Channel(&mut cbr))?;
                                 let pds = PortDeserializer::finish(pds_ref);
 
@@ -287,8 +355,12 @@ where
                             return Err(RecvError::MaxItemSizeExceeded);
                         }
 
-                        let pdf_ref =
-                            PortDeserializer::start(self.receiver.port_allocator(), self.receiver.storage());
+                        let pdf_ref = PortDeserializer::start(
+                            self.receiver.port_allocator(),
+                            self.receiver.storage(),
+                            self.receiver.channel_priorities(),
+                            self.receiver.peer_info(),
+                        );
                         let item_res =
                             <Codec as codec::Codec>::deserialize(IoReader::DataBuf(&mut data.reader()));
                         self.data = DataSource::None;
@@ -381,9 +453,15 @@ where
                 self.receiver.set_max_ports(pds.expected.len() + self.default_max_ports.unwrap());
 
                 // Receive port requests from chmux.
-                let requests = match self.receiver.recv_any().await? {
-                    Some(chmux::Received::Requests(requests)) => requests,
-                    other => {
+                let requests = match self.receiver.recv_any().await {
+                    Ok(Some(chmux::Received::Requests(requests))) => requests,
+                    Err(err) => {
+                        // The item cannot be completed without its ports.
+                        self.item = None;
+                        self.port_deser = None;
+                        return Err(err.into());
+                    }
+                    Ok(other) => {
                         // Current send operation has been aborted and this is data from
                         // next send operation, so we restart.
                         self.recved = Some(other);
@@ -398,7 +476,7 @@ where
                 // forward compatibility.
                 for request in requests {
                     if let Some((local_port, callback)) = pds.expected.remove(&request.id()) {
-                        exec::spawn(callback(local_port, request).in_current_span());
+                        self.receiver.helper_tasks().spawn(callback(local_port, request).in_current_span());
                     }
                 }
 
@@ -410,7 +488,7 @@ where
 
             // Spawn registered tasks.
             for task in pds.tasks.drain(..) {
-                exec::spawn(task.in_current_span());
+                self.receiver.helper_tasks().spawn(task.in_current_span());
             }
 
             return Ok(Some(self.item.take().unwrap()));
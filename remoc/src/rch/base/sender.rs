@@ -17,7 +17,7 @@ use std::{
 use tracing::Instrument;
 
 use super::{
-    super::{DEFAULT_MAX_ITEM_SIZE, SendErrorExt},
+    super::{ChannelKind, DEFAULT_MAX_ITEM_SIZE, SendErrorExt},
     BIG_DATA_CHUNK_QUEUE, BIG_DATA_LIMIT,
     io::{ChannelBytesWriter, LimitedBytesWriter},
 };
@@ -143,8 +143,9 @@ impl<T> Error for SendError<T> where T: fmt::Debug {}
 pub struct PortSerializer {
     allocator: chmux::PortAllocator,
     #[allow(clippy::type_complexity)]
-    requests:
-        Vec<(chmux::PortNumber, Box<dyn FnOnce(chmux::Connect) -> BoxFuture<'static, ()> + Send + 'static>)>,
+    requests: Vec<(PortReq, Box<dyn FnOnce(chmux::Connect) -> BoxFuture<'static, ()> + Send + 'static>)>,
+    priority: chmux::Priority,
+    channel_priorities: chmux::ChannelPriorities,
     storage: AnyStorage,
     tasks: Vec<BoxFuture<'static, ()>>,
 }
@@ -155,8 +156,18 @@ impl PortSerializer {
     }
 
     /// Create a new port serializer and register it as active.
-    fn start(allocator: chmux::PortAllocator, storage: AnyStorage) -> Rc<RefCell<Self>> {
-        let this = Rc::new(RefCell::new(Self { allocator, requests: Vec::new(), storage, tasks: Vec::new() }));
+    fn start(
+        allocator: chmux::PortAllocator, storage: AnyStorage, priority: chmux::Priority,
+        channel_priorities: chmux::ChannelPriorities,
+    ) -> Rc<RefCell<Self>> {
+        let this = Rc::new(RefCell::new(Self {
+            allocator,
+            requests: Vec::new(),
+            priority,
+            channel_priorities,
+            storage,
+            tasks: Vec::new(),
+        }));
         let weak = Rc::downgrade(&this);
         Self::INSTANCE.with(move |i| i.replace(weak));
         this
@@ -183,10 +194,39 @@ impl PortSerializer {
 
     /// Open a chmux port to the remote endpoint.
     ///
+    /// The port inherits the send priority of the chmux port the object is sent over.
+    ///
     /// Returns the local port number and calls the specified function with the connect object.
     pub fn connect<E>(
         callback: impl FnOnce(chmux::Connect) -> BoxFuture<'static, ()> + Send + 'static,
     ) -> Result<u32, E>
+    where
+        E: serde::ser::Error,
+    {
+        Self::connect_impl(None, callback)
+    }
+
+    /// Open a chmux port for a channel of the specified kind to the remote endpoint.
+    ///
+    /// The port uses the send priority configured for the channel kind by
+    /// [Cfg::channel_priorities](chmux::Cfg::channel_priorities).
+    /// If none is configured, the port inherits the send priority of the chmux port
+    /// the object is sent over.
+    ///
+    /// Returns the local port number and calls the specified function with the connect object.
+    pub fn connect_with_kind<E>(
+        kind: ChannelKind, callback: impl FnOnce(chmux::Connect) -> BoxFuture<'static, ()> + Send + 'static,
+    ) -> Result<u32, E>
+    where
+        E: serde::ser::Error,
+    {
+        Self::connect_impl(Some(kind), callback)
+    }
+
+    fn connect_impl<E>(
+        kind: Option<ChannelKind>,
+        callback: impl FnOnce(chmux::Connect) -> BoxFuture<'static, ()> + Send + 'static,
+    ) -> Result<u32, E>
     where
         E: serde::ser::Error,
     {
@@ -196,7 +236,9 @@ impl PortSerializer {
 
         let local_port = this.allocator.try_allocate().ok_or_else(|| ser::Error::custom("ports exhausted"))?;
         let local_port_num = *local_port;
-        this.requests.push((local_port, Box::new(callback)));
+        let priority = kind.and_then(|kind| kind.priority(&this.channel_priorities)).unwrap_or(this.priority);
+        let req = PortReq::new(local_port).with_priority(priority);
+        this.requests.push((req, Box::new(callback)));
 
         Ok(local_port_num)
     }
@@ -265,10 +307,11 @@ where
     }
 
     fn serialize_buffered(
-        allocator: chmux::PortAllocator, storage: AnyStorage, item: &T, limit: usize,
+        allocator: chmux::PortAllocator, storage: AnyStorage, priority: chmux::Priority,
+        channel_priorities: chmux::ChannelPriorities, item: &T, limit: usize,
     ) -> Result<Option<(BytesMut, PortSerializer)>, SerializationError> {
         let mut lw = LimitedBytesWriter::new(limit);
-        let ps_ref = PortSerializer::start(allocator, storage);
+        let ps_ref = PortSerializer::start(allocator, storage, priority, channel_priorities);
 
         match <Codec as codec::Codec>::serialize(IoWriter::Limited(&mut lw), &item) {
             _ if lw.overflow() => return Ok(None),
@@ -281,7 +324,8 @@ where
     }
 
     async fn serialize_streaming(
-        allocator: chmux::PortAllocator, storage: AnyStorage, item: T, tx: tokio::sync::mpsc::Sender<BytesMut>,
+        allocator: chmux::PortAllocator, storage: AnyStorage, priority: chmux::Priority,
+        channel_priorities: chmux::ChannelPriorities, item: T, tx: tokio::sync::mpsc::Sender<BytesMut>,
         chunk_size: usize,
     ) -> Result<(T, PortSerializer, usize), (SerializationError, T)> {
         if !exec::are_threads_available().await {
@@ -295,7 +339,7 @@ where
         let item_arc_task = item_arc.clone();
 
         let result = task::spawn_blocking(move || {
-            let ps_ref = PortSerializer::start(allocator, storage);
+            let ps_ref = PortSerializer::start(allocator, storage, priority, channel_priorities);
 
             let item = item_arc_task.lock().unwrap();
             <Codec as codec::Codec>::serialize(IoWriter::Channel(&mut cbw), &*item)?;
@@ -337,6 +381,8 @@ where
             match Self::serialize_buffered(
                 self.sender.port_allocator(),
                 self.sender.storage(),
+                self.sender.priority(),
+                self.sender.channel_priorities(),
                 &item,
                 self.sender.max_data_size(),
             ) {
@@ -374,6 +420,8 @@ where
                 let ser_task = Self::serialize_streaming(
                     self.sender.port_allocator(),
                     self.sender.storage(),
+                    self.sender.priority(),
+                    self.sender.channel_priorities(),
                     item,
                     tx,
                     self.sender.chunk_size(),
@@ -435,7 +483,7 @@ where
         let mut ports = Vec::new();
         let mut callbacks = Vec::new();
         for (port, callback) in requests {
-            ports.push(PortReq::new(port));
+            ports.push(port);
             callbacks.push(callback);
         }
 
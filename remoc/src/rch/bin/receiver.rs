@@ -7,7 +7,7 @@ use std::{
 
 use super::{
     super::{
-        ConnectError,
+        ChannelKind, ConnectError,
         base::{PortDeserializer, PortSerializer},
     },
     Interlock, Location,
@@ -112,7 +112,7 @@ impl Serialize for Receiver {
         match (sender_tx, interlock_confirm) {
             // Local-remote connection.
             (Some(sender_tx), Some(interlock_confirm)) => {
-                let port = PortSerializer::connect(|connect| {
+                let port = PortSerializer::connect_with_kind(ChannelKind::Bin, |connect| {
                     async move {
                         let _ = interlock_confirm.send(());
 
@@ -153,7 +153,7 @@ impl<'de> Deserialize<'de> for Receiver {
         let TransportedReceiver { port } = TransportedReceiver::deserialize(deserializer)?;
 
         let (receiver_tx, receiver_rx) = tokio::sync::mpsc::unbounded_channel();
-        PortDeserializer::accept(port, |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Bin, |local_port, request| {
             async move {
                 match request.accept_from(local_port).await {
                     Ok((_, raw_rx)) => {
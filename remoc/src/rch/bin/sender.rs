@@ -7,7 +7,7 @@ use std::{
 
 use super::{
     super::{
-        ConnectError,
+        ChannelKind, ConnectError,
         base::{PortDeserializer, PortSerializer},
     },
     Interlock, Location,
@@ -119,7 +119,7 @@ impl Serialize for Sender {
         match (receiver_tx, interlock_confirm) {
             // Local-remote connection.
             (Some(receiver_tx), Some(interlock_confirm)) => {
-                let port = PortSerializer::connect(|connect| {
+                let port = PortSerializer::connect_with_kind(ChannelKind::Bin, |connect| {
                     async move {
                         let _ = interlock_confirm.send(());
 
@@ -160,7 +160,7 @@ impl<'de> Deserialize<'de> for Sender {
         let TransportedSender { port } = TransportedSender::deserialize(deserializer)?;
 
         let (sender_tx, sender_rx) = tokio::sync::mpsc::unbounded_channel();
-        PortDeserializer::accept(port, |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Bin, |local_port, request| {
             async move {
                 match request.accept_from(local_port).await {
                     Ok((raw_tx, _)) => {
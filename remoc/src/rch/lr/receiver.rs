@@ -9,7 +9,7 @@ use std::{
 
 use super::{
     super::{
-        ConnectError,
+        ChannelKind, ConnectError,
         base::{self, PortDeserializer, PortSerializer},
     },
     Interlock, Location,
@@ -190,7 +190,7 @@ where
             interlock.sender.start_send()
         };
 
-        let port = PortSerializer::connect(move |connect| {
+        let port = PortSerializer::connect_with_kind(ChannelKind::Lr, move |connect| {
             async move {
                 let _ = interlock_confirm.send(());
 
@@ -233,7 +233,7 @@ where
         let max_item_size = usize::try_from(max_item_size).unwrap_or(usize::MAX);
 
         let (receiver_tx, receiver_rx) = tokio::sync::mpsc::unbounded_channel();
-        PortDeserializer::accept(port, move |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Lr, move |local_port, request| {
             async move {
                 match request.accept_from(local_port).await {
                     Ok((_, raw_rx)) => {
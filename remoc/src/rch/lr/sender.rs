@@ -9,7 +9,7 @@ use std::{
 
 use super::{
     super::{
-        ConnectError, SendErrorExt,
+        ChannelKind, ConnectError, SendErrorExt,
         base::{self, PortDeserializer, PortSerializer},
     },
     Interlock, Location,
@@ -235,7 +235,7 @@ where
             interlock.receiver.start_send()
         };
 
-        let port = PortSerializer::connect(move |connect| {
+        let port = PortSerializer::connect_with_kind(ChannelKind::Lr, move |connect| {
             async move {
                 let _ = interlock_confirm.send(());
 
@@ -278,7 +278,7 @@ where
         let max_item_size = usize::try_from(max_item_size).unwrap_or(usize::MAX);
 
         let (sender_tx, sender_rx) = tokio::sync::mpsc::unbounded_channel();
-        PortDeserializer::accept(port, move |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Lr, move |local_port, request| {
             async move {
                 match request.accept_from(local_port).await {
                     Ok((raw_tx, _)) => {
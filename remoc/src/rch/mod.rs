@@ -113,6 +113,7 @@ use std::{
 use crate::chmux;
 
 mod interlock;
+mod priority;
 
 pub mod base;
 pub mod bin;
@@ -123,6 +124,8 @@ pub mod mpsc;
 pub mod oneshot;
 pub mod watch;
 
+pub use priority::ChannelKind;
+
 /// Error connecting a remote channel.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConnectError {
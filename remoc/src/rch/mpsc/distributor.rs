@@ -116,6 +116,9 @@ where
                                 Ok(Some(value)) => value,
                                 _ => return false,
                             };
+                            #[cfg(feature = "trace-context")]
+                            rx.span().in_scope(|| permit.send(value));
+                            #[cfg(not(feature = "trace-context"))]
                             permit.send(value);
                         }
                     }
@@ -47,6 +47,8 @@
 
 use bytes::Buf;
 use futures::FutureExt;
+#[cfg(feature = "trace-context")]
+use serde::Deserialize;
 use serde::{Serialize, de::DeserializeOwned};
 use std::{
     fmt,
@@ -56,6 +58,8 @@ use std::{
 };
 
 use super::{ClosedReason, RemoteSendError, Sending, base};
+#[cfg(feature = "trace-context")]
+use crate::trace::TraceContext;
 use crate::{
     RemoteSend, chmux, codec, exec,
     rch::{BACKCHANNEL_MSG_CLOSE, BACKCHANNEL_MSG_ERROR},
@@ -194,15 +198,22 @@ where
 pub(crate) struct SendReq<T> {
     pub value: Result<T, RecvError>,
     pub result_tx: tokio::sync::oneshot::Sender<Result<(), base::SendError<T>>>,
+    #[cfg(feature = "trace-context")]
+    pub context: TraceContext,
 }
 
 impl<T> SendReq<T> {
     fn new(value: Result<T, RecvError>) -> Self {
-        Self { value, result_tx: tokio::sync::oneshot::channel().0 }
+        Self {
+            value,
+            result_tx: tokio::sync::oneshot::channel().0,
+            #[cfg(feature = "trace-context")]
+            context: TraceContext::default(),
+        }
     }
 
     fn ack(self) -> Result<T, RecvError> {
-        let Self { value, result_tx } = self;
+        let Self { value, result_tx, .. } = self;
         let _ = result_tx.send(Ok(()));
         value
     }
@@ -210,11 +221,114 @@ impl<T> SendReq<T> {
 
 pub(crate) fn send_req<T>(value: Result<T, RecvError>) -> (SendReq<T>, Sending<T>) {
     let (result_tx, result_rx) = tokio::sync::oneshot::channel();
-    let this = SendReq { value, result_tx };
+    let this = SendReq {
+        value,
+        result_tx,
+        #[cfg(feature = "trace-context")]
+        context: TraceContext::current(),
+    };
     let sent = Sending(result_rx);
     (this, sent)
 }
 
+/// Value transmitted to a remote endpoint together with the context of the span it was sent from.
+#[cfg(feature = "trace-context")]
+#[derive(Serialize, Deserialize)]
+struct Traced<T> {
+    value: Result<T, RecvError>,
+    context: TraceContext,
+}
+
+/// Sends values to a remote endpoint.
+///
+/// Span contexts are only transmitted if this has been negotiated with the remote endpoint,
+/// so that the data format matches the one expected by the remote endpoint.
+enum RemoteSender<T, Codec> {
+    /// Values only.
+    Plain(base::Sender<Result<T, RecvError>, Codec>),
+    /// Values and span contexts.
+    #[cfg(feature = "trace-context")]
+    Traced(base::Sender<Traced<T>, Codec>),
+}
+
+impl<T, Codec> RemoteSender<T, Codec>
+where
+    T: Serialize + Send + 'static,
+    Codec: codec::Codec,
+{
+    fn new(raw_tx: chmux::Sender, raw_rx: &chmux::Receiver, max_item_size: usize) -> Self {
+        match raw_rx.peer_info().trace_context() {
+            #[cfg(feature = "trace-context")]
+            true => {
+                let mut tx = base::Sender::new(raw_tx);
+                tx.set_max_item_size(max_item_size);
+                Self::Traced(tx)
+            }
+            _ => {
+                let mut tx = base::Sender::new(raw_tx);
+                tx.set_max_item_size(max_item_size);
+                Self::Plain(tx)
+            }
+        }
+    }
+
+    async fn send(&mut self, req: SendReq<T>) -> Result<(), base::SendError<Result<T, RecvError>>> {
+        match self {
+            Self::Plain(tx) => tx.send(req.value).await,
+            #[cfg(feature = "trace-context")]
+            Self::Traced(tx) => tx
+                .send(Traced { value: req.value, context: req.context })
+                .await
+                .map_err(|err| base::SendError { kind: err.kind, item: err.item.value }),
+        }
+    }
+}
+
+/// Receives values from a remote endpoint.
+///
+/// See [RemoteSender] for details.
+enum RemoteReceiver<T, Codec> {
+    /// Values only.
+    Plain(base::Receiver<Result<T, RecvError>, Codec>),
+    /// Values and span contexts.
+    #[cfg(feature = "trace-context")]
+    Traced(base::Receiver<Traced<T>, Codec>),
+}
+
+impl<T, Codec> RemoteReceiver<T, Codec>
+where
+    T: DeserializeOwned + Send + 'static,
+    Codec: codec::Codec,
+{
+    fn new(raw_rx: chmux::Receiver, max_item_size: usize) -> Self {
+        match raw_rx.peer_info().trace_context() {
+            #[cfg(feature = "trace-context")]
+            true => {
+                let mut rx = base::Receiver::new(raw_rx);
+                rx.set_max_item_size(max_item_size);
+                Self::Traced(rx)
+            }
+            _ => {
+                let mut rx = base::Receiver::new(raw_rx);
+                rx.set_max_item_size(max_item_size);
+                Self::Plain(rx)
+            }
+        }
+    }
+
+    async fn recv(&mut self) -> Result<Option<SendReq<T>>, base::RecvError> {
+        match self {
+            Self::Plain(rx) => Ok(rx.recv().await?.map(SendReq::new)),
+            #[cfg(feature = "trace-context")]
+            Self::Traced(rx) => Ok(rx.recv().await?.map(|Traced { value, context }| SendReq {
+                value,
+                result_tx: tokio::sync::oneshot::channel().0,
+                context,
+            })),
+        }
+    }
+}
+
 /// Send implementation for deserializer of Sender and serializer of Receiver.
 async fn send_impl<T, Codec>(
     mut rx: tokio::sync::mpsc::Receiver<SendReq<T>>, raw_tx: chmux::Sender, mut raw_rx: chmux::Receiver,
@@ -225,8 +339,7 @@ async fn send_impl<T, Codec>(
     Codec: codec::Codec,
 {
     // Encode data using remote sender.
-    let mut remote_tx = base::Sender::<Result<T, RecvError>, Codec>::new(raw_tx);
-    remote_tx.set_max_item_size(max_item_size);
+    let mut remote_tx = RemoteSender::<T, Codec>::new(raw_tx, &raw_rx, max_item_size);
 
     // Process events.
     loop {
@@ -272,8 +385,8 @@ async fn send_impl<T, Codec>(
             // Data to send to remote endpoint.
             value_opt = rx.recv() => {
                 match value_opt {
-                    Some(value) => {
-                        let SendReq { value, result_tx } = value;
+                    Some(mut value) => {
+                        let result_tx = std::mem::replace(&mut value.result_tx, tokio::sync::oneshot::channel().0);
                         match remote_tx.send(value).await {
                             Ok(()) => {
                                 let _ = result_tx.send(Ok(()));
@@ -309,8 +422,7 @@ async fn recv_impl<T, Codec>(
     Codec: codec::Codec,
 {
     // Decode raw received data using remote receiver.
-    let mut remote_rx = base::Receiver::<Result<T, RecvError>, Codec>::new(raw_rx);
-    remote_rx.set_max_item_size(max_item_size);
+    let mut remote_rx = RemoteReceiver::<T, Codec>::new(raw_rx, max_item_size);
 
     // Process events.
     loop {
@@ -347,15 +459,15 @@ async fn recv_impl<T, Codec>(
             // Data received from remote endpoint.
             res = remote_rx.recv() => {
                 let mut is_final_err = false;
-                let value = match res {
-                    Ok(Some(value)) => value,
+                let req = match res {
+                    Ok(Some(req)) => req,
                     Ok(None) => break,
                     Err(err) => {
                         is_final_err = err.is_final();
-                        Err(RecvError::RemoteReceive(err))
+                        SendReq::new(Err(RecvError::RemoteReceive(err)))
                     },
                 };
-                if tx.send(SendReq::new(value)).await.is_err() {
+                if tx.send(req).await.is_err() {
                     break;
                 }
                 if is_final_err {
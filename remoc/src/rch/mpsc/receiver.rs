@@ -12,7 +12,7 @@ use std::{
 
 use super::{
     super::{
-        ClosedReason, DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
+        ChannelKind, ClosedReason, DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
         base::{self, PortDeserializer, PortSerializer},
     },
     Distributor, SendReq,
@@ -132,6 +132,8 @@ pub struct Receiver<
     successor_tx: Mutex<Option<tokio::sync::oneshot::Sender<ReceiverInner<T>>>>,
     final_err: Option<RecvError>,
     remote_max_item_size: Option<usize>,
+    #[cfg(feature = "trace-context")]
+    span: tracing::Span,
     _codec: PhantomData<Codec>,
 }
 
@@ -195,10 +197,22 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
             successor_tx: Mutex::new(None),
             final_err: None,
             remote_max_item_size,
+            #[cfg(feature = "trace-context")]
+            span: tracing::Span::none(),
             _codec: PhantomData,
         }
     }
 
+    /// Acknowledges the reception of a value.
+    fn ack(&mut self, send_req: SendReq<T>) -> Result<T, RecvError> {
+        #[cfg(feature = "trace-context")]
+        {
+            send_req.context.link_to(&tracing::Span::current());
+            self.span = send_req.context.child_span();
+        }
+        send_req.ack()
+    }
+
     /// Receives the next value for this receiver.
     ///
     /// This function returns `Ok(None)` when all channel senders have been dropped.
@@ -213,7 +227,7 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
     pub async fn recv(&mut self) -> Result<Option<T>, RecvError> {
         loop {
             match self.inner.as_mut().unwrap().rx.recv().await {
-                Some(send_req) => match send_req.ack() {
+                Some(send_req) => match self.ack(send_req) {
                     Ok(value_opt) => return Ok(Some(value_opt)),
                     Err(err) => {
                         if err.is_final() {
@@ -244,7 +258,7 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
     pub fn poll_recv(&mut self, cx: &mut Context) -> Poll<Result<Option<T>, RecvError>> {
         loop {
             match ready!(self.inner.as_mut().unwrap().rx.poll_recv(cx)) {
-                Some(send_req) => match send_req.ack() {
+                Some(send_req) => match self.ack(send_req) {
                     Ok(value_opt) => return Poll::Ready(Ok(Some(value_opt))),
                     Err(err) => {
                         if err.is_final() {
@@ -276,7 +290,7 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
     pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
         loop {
             match self.inner.as_mut().unwrap().rx.try_recv() {
-                Ok(send_req) => match send_req.ack() {
+                Ok(send_req) => match self.ack(send_req) {
                     Ok(value_opt) => return Ok(value_opt),
                     Err(err) => {
                         if err.is_final() {
@@ -346,7 +360,7 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
 
         let mut p = 0;
         for send_req in send_req_buf {
-            match send_req.ack() {
+            match self.ack(send_req) {
                 Ok(value_opt) => {
                     buffer.push(value_opt);
                     p += 1;
@@ -366,6 +380,27 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
         Ok(p)
     }
 
+    /// Span for processing the most recently received value.
+    ///
+    /// It is a child of the span that was current when the value was sent.
+    /// See the [trace module](crate::trace) for details.
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+
+    /// Span for processing the most recently received value.
+    ///
+    /// This is the [received span](Self::span), if available, and otherwise the current span.
+    pub(crate) fn processing_span(&self) -> tracing::Span {
+        #[cfg(feature = "trace-context")]
+        if !self.span.is_none() {
+            return self.span.clone();
+        }
+        tracing::Span::current()
+    }
+
     /// Returns the number of values available for receiving.
     ///
     /// This might be over-estimated in case a receive error occured.
@@ -411,6 +446,8 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
             successor_tx: Mutex::new(None),
             final_err: self.final_err.clone(),
             remote_max_item_size: self.remote_max_item_size,
+            #[cfg(feature = "trace-context")]
+            span: self.span.clone(),
             _codec: PhantomData,
         }
     }
@@ -423,6 +460,8 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
             successor_tx: Mutex::new(None),
             final_err: self.final_err.clone(),
             remote_max_item_size: self.remote_max_item_size,
+            #[cfg(feature = "trace-context")]
+            span: self.span.clone(),
             _codec: PhantomData,
         }
     }
@@ -441,6 +480,8 @@ impl<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Receiver<T, Code
             successor_tx: Mutex::new(None),
             final_err: self.final_err.clone(),
             remote_max_item_size: self.remote_max_item_size,
+            #[cfg(feature = "trace-context")]
+            span: self.span.clone(),
             _codec: PhantomData,
         }
     }
@@ -506,7 +547,7 @@ where
         let (successor_tx, successor_rx) = tokio::sync::oneshot::channel();
         *self.successor_tx.lock().unwrap() = Some(successor_tx);
 
-        let port = PortSerializer::connect(|connect| {
+        let port = PortSerializer::connect_with_kind(ChannelKind::Mpsc, |connect| {
             async move {
                 // Receiver has been dropped after sending, so we receive its channels.
                 let ReceiverInner { rx, closed_tx, remote_send_err_tx, closed: _ } = match successor_rx.await {
@@ -571,7 +612,7 @@ where
         let (closed_tx, closed_rx) = tokio::sync::watch::channel(None);
         let (remote_send_err_tx, remote_send_err_rx) = tokio::sync::watch::channel(None);
 
-        PortDeserializer::accept(port, |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Mpsc, |local_port, request| {
             async move {
                 // Accept chmux connection request.
                 let (raw_tx, raw_rx) = match request.accept_from(local_port).await {
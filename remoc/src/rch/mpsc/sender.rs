@@ -13,7 +13,7 @@ use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
     super::{
-        ClosedReason, DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, RemoteSendError, SendErrorExt, Sending,
+        ChannelKind, ClosedReason, DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, RemoteSendError, SendErrorExt, Sending,
         base::{self, PortDeserializer, PortSerializer},
     },
     SendReq,
@@ -253,6 +253,7 @@ pub struct Sender<T, Codec = codec::Default, const BUFFER: usize = DEFAULT_BUFFE
     remote_send_err_rx: tokio::sync::watch::Receiver<Option<RemoteSendError>>,
     dropped_tx: tokio::sync::mpsc::Sender<()>,
     max_item_size: usize,
+    rtc: bool,
     _codec: PhantomData<Codec>,
 }
 
@@ -270,6 +271,7 @@ impl<T, Codec, const BUFFER: usize> Clone for Sender<T, Codec, BUFFER> {
             remote_send_err_rx: self.remote_send_err_rx.clone(),
             dropped_tx: self.dropped_tx.clone(),
             max_item_size: self.max_item_size,
+            rtc: self.rtc,
             _codec: PhantomData,
         }
     }
@@ -287,6 +289,9 @@ pub(crate) struct TransportedSender<T, Codec> {
     /// Maximum item size in bytes.
     #[serde(default = "default_max_item_size")]
     max_item_size: u64,
+    /// Request channel of a remote trait client.
+    #[serde(default)]
+    rtc: bool,
 }
 
 const fn default_max_item_size() -> u64 {
@@ -312,6 +317,7 @@ where
             remote_send_err_rx,
             dropped_tx,
             max_item_size: DEFAULT_MAX_ITEM_SIZE,
+            rtc: false,
             _codec: PhantomData,
         };
 
@@ -344,6 +350,7 @@ where
             remote_send_err_rx: tokio::sync::watch::channel(None).1,
             dropped_tx: tokio::sync::mpsc::channel(1).0,
             max_item_size: DEFAULT_MAX_ITEM_SIZE,
+            rtc: false,
             _codec: PhantomData,
         }
     }
@@ -510,6 +517,7 @@ where
             remote_send_err_rx: self.remote_send_err_rx.clone(),
             dropped_tx: self.dropped_tx.clone(),
             max_item_size: self.max_item_size,
+            rtc: self.rtc,
             _codec: PhantomData,
         }
     }
@@ -523,6 +531,7 @@ where
             remote_send_err_rx: self.remote_send_err_rx.clone(),
             dropped_tx: self.dropped_tx.clone(),
             max_item_size: self.max_item_size,
+            rtc: self.rtc,
             _codec: PhantomData,
         }
     }
@@ -536,6 +545,18 @@ where
     pub fn set_max_item_size(&mut self, max_item_size: usize) {
         self.max_item_size = max_item_size;
     }
+
+    /// Marks this as the request channel of a remote trait client.
+    ///
+    /// Its port then uses the send priority of [ChannelKind::Rtc].
+    #[doc(hidden)]
+    pub fn set_rtc(&mut self) {
+        self.rtc = true;
+    }
+
+    fn kind(&self) -> ChannelKind {
+        if self.rtc { ChannelKind::Rtc } else { ChannelKind::Mpsc }
+    }
 }
 
 /// Owned permit to send one value into the channel.
@@ -577,7 +598,7 @@ where
                 let remote_send_err_rx = self.remote_send_err_rx.clone();
                 let max_item_size = self.max_item_size;
 
-                Some(PortSerializer::connect(move |connect| {
+                Some(PortSerializer::connect_with_kind(self.kind(), move |connect| {
                     async move {
                         // Establish chmux channel.
                         let (raw_tx, raw_rx) = match connect.await {
@@ -613,6 +634,7 @@ where
             data: PhantomData,
             codec: PhantomData,
             max_item_size: self.max_item_size.try_into().unwrap_or(u64::MAX),
+            rtc: self.rtc,
         };
         transported.serialize(serializer)
     }
@@ -631,7 +653,7 @@ where
         assert!(BUFFER > 0, "BUFFER must not be zero");
 
         // Get chmux port number from deserialized transport type.
-        let TransportedSender { port, max_item_size, .. } =
+        let TransportedSender { port, max_item_size, rtc, .. } =
             TransportedSender::<T, Codec>::deserialize(deserializer)?;
         let max_item_size = usize::try_from(max_item_size).unwrap_or(usize::MAX);
 
@@ -644,7 +666,8 @@ where
                 let (remote_send_err_tx, remote_send_err_rx) = tokio::sync::watch::channel(None);
 
                 // Accept chmux port request.
-                PortDeserializer::accept(port, move |local_port, request| {
+                let kind = if rtc { ChannelKind::Rtc } else { ChannelKind::Mpsc };
+                PortDeserializer::accept_with_kind(port, kind, move |local_port, request| {
                     async move {
                         // Accept chmux connection request.
                         let (raw_tx, raw_rx) = match request.accept_from(local_port).await {
@@ -668,7 +691,9 @@ where
                     .boxed()
                 })?;
 
-                Ok(Self::new(tx, closed_rx, remote_send_err_rx))
+                let mut this = Self::new(tx, closed_rx, remote_send_err_rx);
+                this.rtc = rtc;
+                Ok(this)
             }
 
             // Received closed channel.
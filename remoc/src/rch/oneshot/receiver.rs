@@ -189,6 +189,21 @@ where
     pub fn remote_max_item_size(&self) -> Option<usize> {
         self.0.remote_max_item_size()
     }
+
+    /// Span for processing the received value.
+    ///
+    /// It is a child of the span that was current when the value was sent.
+    /// See the [trace module](crate::trace) for details.
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn span(&self) -> &tracing::Span {
+        self.0.span()
+    }
+
+    /// Span for processing the received value, falling back to the current span.
+    pub(crate) fn processing_span(&self) -> tracing::Span {
+        self.0.processing_span()
+    }
 }
 
 impl<T, Codec, const MAX_ITEM_SIZE: usize> Future for Receiver<T, Codec, MAX_ITEM_SIZE>
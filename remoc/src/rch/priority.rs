@@ -0,0 +1,44 @@
+//! Send priorities of channel kinds.
+
+use crate::chmux::{ChannelPriorities, Priority};
+
+/// Kind of remote channel.
+///
+/// A send priority can be assigned to all channels of a kind using
+/// [Cfg::channel_priorities](crate::chmux::Cfg::channel_priorities).
+/// This allows, for example, to prioritize [remote trait calls](crate::rtc) over bulk
+/// data transfers using [binary channels](super::bin).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ChannelKind {
+    /// [Binary channels](super::bin).
+    ///
+    /// These are also used by [IO channels](super::io) and [lazy blobs](crate::robj::lazy_blob).
+    Bin,
+    /// [Local/remote channels](super::lr).
+    Lr,
+    /// [Multi producer, single consumer channels](super::mpsc).
+    ///
+    /// These are also used by [oneshot](super::oneshot) and [broadcast](super::broadcast) channels
+    /// and [remote functions](crate::rfn).
+    Mpsc,
+    /// [Watch channels](super::watch).
+    Watch,
+    /// Request channels of [remote trait clients](crate::rtc).
+    Rtc,
+}
+
+impl ChannelKind {
+    /// Send priority of all channels of this kind within the specified configuration.
+    ///
+    /// If `None`, a channel inherits the priority of the channel it was sent over.
+    pub fn priority(self, priorities: &ChannelPriorities) -> Option<Priority> {
+        match self {
+            Self::Bin => priorities.bin,
+            Self::Lr => priorities.lr,
+            Self::Mpsc => priorities.mpsc,
+            Self::Watch => priorities.watch,
+            Self::Rtc => priorities.rtc.or(priorities.mpsc),
+        }
+    }
+}
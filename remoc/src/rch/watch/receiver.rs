@@ -12,7 +12,7 @@ use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
     super::{
-        DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
+        ChannelKind, DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
         base::{self, PortDeserializer, PortSerializer},
     },
     Ref,
@@ -268,7 +268,7 @@ where
         let data = rx.borrow_and_update().clone();
         let remote_send_err_tx = self.remote_send_err_tx.clone();
 
-        let port = PortSerializer::connect(|connect| {
+        let port = PortSerializer::connect_with_kind(ChannelKind::Watch, |connect| {
             async move {
                 // Establish chmux channel.
                 let (raw_tx, raw_rx) = match connect.await {
@@ -321,7 +321,7 @@ where
         let (tx, rx) = tokio::sync::watch::channel(data);
         let (remote_send_err_tx, remote_send_err_rx) = tokio::sync::mpsc::unbounded_channel();
 
-        PortDeserializer::accept(port, |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Watch, |local_port, request| {
             async move {
                 // Accept chmux connection request.
                 let (raw_tx, raw_rx) = match request.accept_from(local_port).await {
@@ -4,7 +4,7 @@ use std::{error::Error, fmt, marker::PhantomData, sync::Mutex};
 
 use super::{
     super::{
-        RemoteSendError, SendErrorExt,
+        ChannelKind, RemoteSendError, SendErrorExt,
         base::{self, PortDeserializer, PortSerializer},
     },
     Receiver, Ref,
@@ -333,7 +333,7 @@ where
         let (successor_tx, successor_rx) = tokio::sync::oneshot::channel();
         *self.successor_tx.lock().unwrap() = Some(successor_tx);
 
-        let port = PortSerializer::connect(move |connect| {
+        let port = PortSerializer::connect_with_kind(ChannelKind::Watch, move |connect| {
             async move {
                 // Sender has been dropped after sending, so we receive its channels.
                 let SenderInner { tx, remote_send_err_rx, current_err, .. } = match successor_rx.await {
@@ -394,7 +394,7 @@ where
         let remote_send_err_tx2 = remote_send_err_tx.clone();
 
         // Accept chmux port request.
-        PortDeserializer::accept(port, move |local_port, request| {
+        PortDeserializer::accept_with_kind(port, ChannelKind::Watch, move |local_port, request| {
             async move {
                 // Accept chmux connection request.
                 let (raw_tx, raw_rx) = match request.accept_from(local_port).await {
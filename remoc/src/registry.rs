@@ -0,0 +1,551 @@
+//! Registry of named services for discovery by a remote endpoint.
+//!
+//! Instead of sending a hand-written struct containing all remote objects over the
+//! base channel after [establishing a connection](crate::Connect), one side can create
+//! a [Registry] and send its [client](RegistryClient) to the remote endpoint.
+//! Values, such as [RTC clients](crate::rtc), [remote functions](crate::rfn) and
+//! [channels](crate::rch), are then registered under a name on the local side
+//! and looked up by name and type on the remote side.
+//!
+//! Lookups can be performed at any time, thus services registered after the client has
+//! been sent are available as well.
+//! Use [RegistryClient::watch] to obtain a notification stream of additions and removals
+//! or [RegistryClient::wait] to wait until a service has been registered.
+//! A slow watcher does not make the registry buffer events without bound; instead
+//! changes it has not yet received are coalesced.
+//!
+//! A registered value is cloned for each lookup and the clone is sent to the
+//! looking-up endpoint.
+//! Thus registering an RTC client makes all endpoints that look it up talk to the same server.
+//!
+//! # Type tags
+//!
+//! Each service is registered with a type tag derived from the type of the registered value
+//! using [type_name](std::any::type_name), for example `remoc::rfn::RFn<...>` or
+//! `my_crate::GreeterClient` for an RTC client of the `Greeter` trait.
+//! A lookup derives the type tag in the same way from the requested type and
+//! fails with [RegistryError::TypeMismatch] if the tags differ.
+//! Thus a value is never deserialized into a different type than it was registered with.
+//!
+//! Since type names are not guaranteed to be stable between compiler versions,
+//! all endpoints should be built with the same compiler and define the shared types
+//! in a common crate.
+//!
+//! # Lifetime
+//!
+//! When the [Registry] is dropped, all values are unregistered, watch streams end and lookups
+//! fail.
+//! Values that have already been looked up remain valid.
+//!
+//! This module requires the `registry` crate feature.
+//!
+//! # Example
+//!
+//! In the following example the server registers a remote function after sending the
+//! registry client and the client waits for it to become available.
+//!
+//! ```
+//! use remoc::prelude::*;
+//! use remoc::registry::{Registry, RegistryClient};
+//!
+//! type Greet = rfn::RFn<(String,), Result<String, rfn::CallError>>;
+//!
+//! // This would be run on the client.
+//! async fn client(mut rx: rch::base::Receiver<RegistryClient>) {
+//!     let registry = rx.recv().await.unwrap().unwrap();
+//!     let greet: Greet = registry.wait("greet").await.unwrap();
+//!     assert_eq!(greet.call("world".to_string()).await.unwrap(), "Hello, world!");
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut tx: rch::base::Sender<RegistryClient>) {
+//!     let registry = Registry::new();
+//!     tx.send(registry.client()).await.unwrap();
+//!
+//!     let greet: Greet = rfn::RFn::new_1(|name: String| async move { Ok(format!("Hello, {name}!")) });
+//!     registry.register("greet", greet);
+//!
+//!     // Keep the registry alive until the client has disconnected.
+//!     tx.closed().await;
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(server, client));
+//! ```
+
+use futures::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    RemoteSend, chmux, codec, exec,
+    rch::{ConnectError, base, bin, mpsc, oneshot},
+};
+
+/// An error occurred during looking up a service in a registry.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RegistryError {
+    /// No service is registered under the name.
+    NotFound(String),
+    /// The registered service has a different type tag than requested.
+    TypeMismatch {
+        /// Name of the service.
+        name: String,
+        /// Type tag of the registered service.
+        registered: String,
+        /// Type tag requested by the lookup.
+        requested: String,
+    },
+    /// The registry was dropped.
+    Dropped,
+    /// Sending to a remote endpoint failed.
+    RemoteSend(base::SendErrorKind),
+    /// Receiving from a remote endpoint failed.
+    RemoteReceive(base::RecvError),
+    /// Connecting a sent channel failed.
+    RemoteConnect(chmux::ConnectError),
+    /// Listening for a received channel failed.
+    RemoteListen(chmux::ListenerError),
+    /// Forwarding at a remote endpoint to another remote endpoint failed.
+    RemoteForward,
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "service {name} not found"),
+            Self::TypeMismatch { name, registered, requested } => {
+                write!(f, "service {name} has type tag {registered} but {requested} was requested")
+            }
+            Self::Dropped => write!(f, "registry dropped"),
+            Self::RemoteSend(err) => write!(f, "send error: {err}"),
+            Self::RemoteReceive(err) => write!(f, "receive error: {err}"),
+            Self::RemoteConnect(err) => write!(f, "connect error: {err}"),
+            Self::RemoteListen(err) => write!(f, "listen error: {err}"),
+            Self::RemoteForward => write!(f, "forwarding error"),
+        }
+    }
+}
+
+impl Error for RegistryError {}
+
+impl<T> From<mpsc::SendError<T>> for RegistryError {
+    fn from(err: mpsc::SendError<T>) -> Self {
+        match err {
+            mpsc::SendError::Closed(_) => Self::Dropped,
+            mpsc::SendError::RemoteSend(err) => Self::RemoteSend(err),
+            mpsc::SendError::RemoteConnect(err) => Self::RemoteConnect(err),
+            mpsc::SendError::RemoteListen(err) => Self::RemoteListen(err),
+            mpsc::SendError::RemoteForward => Self::RemoteForward,
+        }
+    }
+}
+
+impl From<mpsc::RecvError> for RegistryError {
+    fn from(err: mpsc::RecvError) -> Self {
+        match err {
+            mpsc::RecvError::RemoteReceive(err) => Self::RemoteReceive(err),
+            mpsc::RecvError::RemoteConnect(err) => Self::RemoteConnect(err),
+            mpsc::RecvError::RemoteListen(err) => Self::RemoteListen(err),
+        }
+    }
+}
+
+impl From<oneshot::RecvError> for RegistryError {
+    fn from(err: oneshot::RecvError) -> Self {
+        match err {
+            oneshot::RecvError::Closed => Self::Dropped,
+            oneshot::RecvError::RemoteReceive(err) => Self::RemoteReceive(err),
+            oneshot::RecvError::RemoteConnect(err) => Self::RemoteConnect(err),
+            oneshot::RecvError::RemoteListen(err) => Self::RemoteListen(err),
+        }
+    }
+}
+
+impl From<ConnectError> for RegistryError {
+    fn from(err: ConnectError) -> Self {
+        match err {
+            ConnectError::Dropped => Self::Dropped,
+            ConnectError::Connect(err) => Self::RemoteConnect(err),
+            ConnectError::Listen(err) => Self::RemoteListen(err),
+        }
+    }
+}
+
+impl From<base::RecvError> for RegistryError {
+    fn from(err: base::RecvError) -> Self {
+        Self::RemoteReceive(err)
+    }
+}
+
+/// A change of the services in a registry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegistryEvent {
+    /// A service was registered.
+    Added {
+        /// Name of the service.
+        name: String,
+        /// Type tag of the service.
+        tag: String,
+    },
+    /// A service was unregistered.
+    Removed {
+        /// Name of the service.
+        name: String,
+    },
+}
+
+/// The [type tag](self#type-tags) of a type.
+fn type_tag<T>() -> String {
+    std::any::type_name::<T>().to_string()
+}
+
+/// Sends a clone of a registered value over a binary channel.
+type SendFn = Box<dyn Fn(bin::Sender) -> BoxFuture<'static, ()> + Send + Sync>;
+
+struct Entry {
+    id: u64,
+    tag: String,
+    send: SendFn,
+}
+
+/// Registered services by name, with the id and type tag of each registration.
+type Snapshot = HashMap<String, (u64, String)>;
+
+struct State {
+    entries: HashMap<String, Entry>,
+    next_id: u64,
+    snapshot_tx: tokio::sync::watch::Sender<Snapshot>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            next_id: 0,
+            snapshot_tx: tokio::sync::watch::Sender::new(Snapshot::new()),
+        }
+    }
+}
+
+impl State {
+    /// Publishes the registered services to watchers.
+    fn publish(&self) {
+        let snapshot =
+            self.entries.iter().map(|(name, entry)| (name.clone(), (entry.id, entry.tag.clone()))).collect();
+        self.snapshot_tx.send_replace(snapshot);
+    }
+}
+
+/// Request from a registry client to the registry.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+#[allow(clippy::large_enum_variant)]
+enum Req<Codec> {
+    Lookup {
+        name: String,
+        tag: String,
+        tx: bin::Sender,
+        result_tx: oneshot::Sender<Result<(), RegistryError>, Codec>,
+    },
+    Names {
+        reply_tx: oneshot::Sender<Vec<String>, Codec>,
+    },
+    Watch {
+        tx: mpsc::Sender<RegistryEvent, Codec>,
+    },
+}
+
+/// A registry of named services.
+///
+/// Send its [client](Self::client) to a remote endpoint to make the registered
+/// services available there.
+/// See the [module-level documentation](self) for details.
+pub struct Registry<Codec = codec::Default> {
+    state: Arc<Mutex<State>>,
+    client: RegistryClient<Codec>,
+    _term_tx: tokio::sync::oneshot::Sender<()>,
+}
+
+impl<Codec> fmt::Debug for Registry<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Registry").field("names", &self.names()).finish()
+    }
+}
+
+impl<Codec> Default for Registry<Codec>
+where
+    Codec: codec::Codec,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Codec> Registry<Codec> {
+    /// Names of all registered services.
+    pub fn names(&self) -> Vec<String> {
+        self.state.lock().unwrap().entries.keys().cloned().collect()
+    }
+
+    /// Whether a service is registered under the specified name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.state.lock().unwrap().entries.contains_key(name)
+    }
+
+    /// Unregisters the service with the specified name.
+    ///
+    /// Returns whether a service was registered under the name.
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.entries.remove(name).is_none() {
+            return false;
+        }
+        state.publish();
+        true
+    }
+
+    /// Client for looking up services.
+    ///
+    /// The client can be sent to a remote endpoint.
+    pub fn client(&self) -> RegistryClient<Codec> {
+        self.client.clone()
+    }
+}
+
+impl<Codec> Registry<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        let (req_tx, req_rx) = mpsc::channel(1);
+        let (term_tx, term_rx) = tokio::sync::oneshot::channel();
+        let state = Arc::new(Mutex::new(State::default()));
+
+        exec::spawn(Self::serve(state.clone(), req_rx, term_rx));
+
+        Self { state, client: RegistryClient { req_tx }, _term_tx: term_tx }
+    }
+
+    /// Registers a service under the specified name.
+    ///
+    /// The [type tag](self#type-tags) is derived from the type of the value.
+    /// A clone of the value is sent to each endpoint that looks it up.
+    /// If a service is already registered under the name, it is replaced and
+    /// `true` is returned.
+    pub fn register<T>(&self, name: impl Into<String>, value: T) -> bool
+    where
+        T: RemoteSend + Clone,
+    {
+        let value = Mutex::new(value);
+        let send: SendFn = Box::new(move |tx: bin::Sender| {
+            let value = value.lock().unwrap().clone();
+            async move {
+                match tx.into_inner().await {
+                    Ok(raw_tx) => {
+                        let mut tx = base::Sender::<T, Codec>::new(raw_tx);
+                        if let Err(err) = tx.send(value).await {
+                            tracing::warn!(%err.kind, "sending registered service failed");
+                        }
+                    }
+                    Err(err) => tracing::warn!(%err, "connecting for sending registered service failed"),
+                }
+            }
+            .boxed()
+        });
+
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let replaced = state.entries.insert(name.into(), Entry { id, tag: type_tag::<T>(), send }).is_some();
+        state.publish();
+        replaced
+    }
+
+    async fn serve(
+        state: Arc<Mutex<State>>, mut req_rx: mpsc::Receiver<Req<Codec>, Codec>,
+        mut term_rx: tokio::sync::oneshot::Receiver<()>,
+    ) {
+        loop {
+            let req = tokio::select! {
+                biased;
+                _ = &mut term_rx => break,
+                req = req_rx.recv() => req,
+            };
+
+            match req {
+                Ok(Some(req)) => Self::handle(&state, req).await,
+                Ok(None) => break,
+                Err(err) if err.is_final() => break,
+                Err(err) => tracing::warn!(%err, "receiving registry request failed"),
+            }
+        }
+    }
+
+    async fn handle(state: &Mutex<State>, req: Req<Codec>) {
+        match req {
+            Req::Lookup { name, tag, tx, result_tx } => {
+                let res = {
+                    let state = state.lock().unwrap();
+                    match state.entries.get(&name) {
+                        Some(entry) if entry.tag == tag => Ok((entry.send)(tx)),
+                        Some(entry) => Err(RegistryError::TypeMismatch {
+                            name,
+                            registered: entry.tag.clone(),
+                            requested: tag,
+                        }),
+                        None => Err(RegistryError::NotFound(name)),
+                    }
+                };
+                match res {
+                    Ok(send) => {
+                        if result_tx.send(Ok(())).is_ok() {
+                            exec::spawn(send);
+                        }
+                    }
+                    Err(err) => {
+                        let _ = result_tx.send(Err(err));
+                    }
+                }
+            }
+            Req::Names { reply_tx } => {
+                let names = state.lock().unwrap().entries.keys().cloned().collect();
+                let _ = reply_tx.send(names);
+            }
+            Req::Watch { tx } => {
+                let snapshot_rx = state.lock().unwrap().snapshot_tx.subscribe();
+                exec::spawn(Self::watch(snapshot_rx, tx));
+            }
+        }
+    }
+
+    /// Sends events for the changes between successive snapshots to a watcher.
+    ///
+    /// Only the latest snapshot is kept, so that a slow watcher cannot make the registry
+    /// buffer events without bound.
+    async fn watch(
+        mut snapshot_rx: tokio::sync::watch::Receiver<Snapshot>, tx: mpsc::Sender<RegistryEvent, Codec>,
+    ) {
+        let mut known = Snapshot::new();
+        loop {
+            let snapshot = snapshot_rx.borrow_and_update().clone();
+
+            let removed =
+                known.iter().filter(|(name, (id, _))| snapshot.get(*name).is_none_or(|(cur, _)| cur != id));
+            let mut events: Vec<_> =
+                removed.map(|(name, _)| RegistryEvent::Removed { name: name.clone() }).collect();
+            let added =
+                snapshot.iter().filter(|(name, (id, _))| known.get(*name).is_none_or(|(old, _)| old != id));
+            events.extend(
+                added.map(|(name, (_, tag))| RegistryEvent::Added { name: name.clone(), tag: tag.clone() }),
+            );
+
+            for event in events {
+                if tx.send(event).await.is_err() {
+                    return;
+                }
+            }
+            known = snapshot;
+
+            tokio::select! {
+                res = snapshot_rx.changed() => {
+                    if res.is_err() {
+                        return;
+                    }
+                }
+                () = tx.closed() => return,
+            }
+        }
+    }
+}
+
+/// Client of a [Registry] for looking up services.
+///
+/// It can be cloned and sent to a remote endpoint.
+/// See the [module-level documentation](self) for details.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct RegistryClient<Codec = codec::Default> {
+    req_tx: mpsc::Sender<Req<Codec>, Codec>,
+}
+
+impl<Codec> Clone for RegistryClient<Codec> {
+    fn clone(&self) -> Self {
+        Self { req_tx: self.req_tx.clone() }
+    }
+}
+
+impl<Codec> fmt::Debug for RegistryClient<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RegistryClient").finish()
+    }
+}
+
+impl<Codec> RegistryClient<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Looks up the service with the specified name.
+    ///
+    /// Fails with [RegistryError::TypeMismatch] if the service was registered with
+    /// a different [type](self#type-tags).
+    pub async fn lookup<T>(&self, name: &str) -> Result<T, RegistryError>
+    where
+        T: RemoteSend,
+    {
+        let (tx, rx) = bin::channel();
+        let (result_tx, result_rx) = oneshot::channel();
+        self.req_tx.send(Req::Lookup { name: name.to_string(), tag: type_tag::<T>(), tx, result_tx }).await?;
+        result_rx.await??;
+
+        let mut rx = base::Receiver::<T, Codec>::new(rx.into_inner().await?);
+        rx.recv().await?.ok_or(RegistryError::Dropped)
+    }
+
+    /// Names of all registered services.
+    pub async fn names(&self) -> Result<Vec<String>, RegistryError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.req_tx.send(Req::Names { reply_tx }).await?;
+        Ok(reply_rx.await?)
+    }
+
+    /// Watches the registry for changes.
+    ///
+    /// The returned receiver first yields an [added event](RegistryEvent::Added) for each
+    /// currently registered service, followed by events for subsequent changes.
+    /// Changes made while previous events have not yet been received are coalesced,
+    /// i.e. a service that is registered and unregistered in quick succession may not
+    /// produce any events and replacing a service produces a
+    /// [removed event](RegistryEvent::Removed) followed by an added event.
+    /// It ends when the registry is dropped.
+    pub async fn watch(&self) -> Result<mpsc::Receiver<RegistryEvent, Codec>, RegistryError> {
+        let (tx, rx) = mpsc::channel(16);
+        self.req_tx.send(Req::Watch { tx }).await?;
+        Ok(rx)
+    }
+
+    /// Waits until a service is registered under the specified name and looks it up.
+    ///
+    /// Returns immediately if the service is already registered.
+    pub async fn wait<T>(&self, name: &str) -> Result<T, RegistryError>
+    where
+        T: RemoteSend,
+    {
+        let mut events = self.watch().await?;
+        loop {
+            match events.recv().await {
+                Ok(Some(RegistryEvent::Added { name: added, .. })) if added == name => break,
+                Ok(Some(_)) => (),
+                Ok(None) => return Err(RegistryError::Dropped),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        self.lookup(name).await
+    }
+}
@@ -188,7 +188,7 @@ where
                                         let _permit = semaphore.acquire().await.ok();
                                         let result = fun_task(argument).await;
                                         let _ = result_tx.send(result);
-                                    }.in_current_span());
+                                    }.instrument(request_rx.processing_span()));
                                 }
                                 Ok(None) => break,
                                 Err(err) if err.is_final() => break,
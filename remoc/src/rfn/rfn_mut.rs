@@ -138,7 +138,7 @@ where
                         req_res = request_rx.recv() => {
                             match req_res {
                                 Ok(Some(RFnRequest {argument, result_tx})) => {
-                                    let result = fun(argument).await;
+                                    let result = fun(argument).instrument(request_rx.processing_span()).await;
                                     let _ = result_tx.send(result);
                                 }
                                 Ok(None) => break,
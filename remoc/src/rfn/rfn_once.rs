@@ -107,7 +107,7 @@ where
         F: FnOnce(A) -> Fut + Send + 'static,
         Fut: Future<Output = R> + Send,
     {
-        let (request_tx, request_rx) = oneshot::channel();
+        let (request_tx, mut request_rx) = oneshot::channel();
         let (keep_tx, keep_rx) = tokio::sync::oneshot::channel();
 
         exec::spawn(
@@ -117,9 +117,9 @@ where
 
                     Err(_) = keep_rx => (),
 
-                    req_res = request_rx => {
+                    req_res = &mut request_rx => {
                         if let Ok(RFnRequest {argument, result_tx}) = req_res {
-                            let result = fun(argument).await;
+                            let result = fun(argument).instrument(request_rx.processing_span()).await;
                             let _ = result_tx.send(result);
                         }
                     }
@@ -0,0 +1,228 @@
+//! Balanced client pools spreading calls across multiple servers of the same remote trait.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, Weak},
+};
+
+use super::Client;
+use crate::exec;
+
+/// Policy for selecting a client of a [balanced client pool](Balanced).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BalancePolicy {
+    /// Select the clients in turn.
+    #[default]
+    RoundRobin,
+    /// Select the client with the fewest outstanding calls made through the pool,
+    /// i.e. the fewest [handles](Pooled) returned by [get](Balanced::get) that are still in use.
+    ///
+    /// Clients with equal load are selected in turn.
+    LeastLoaded,
+}
+
+struct Entry<C> {
+    id: u64,
+    client: C,
+    load: Arc<()>,
+}
+
+struct Inner<C> {
+    entries: Vec<Entry<C>>,
+    next_id: u64,
+    next: usize,
+}
+
+/// A pool of clients for the same remote trait that spreads calls across them.
+///
+/// Each call is made on the client returned by [get](Self::get), which is chosen
+/// according to the [balance policy](BalancePolicy).
+/// The returned [handle](Pooled) counts as an outstanding call until it is dropped.
+/// Clients are removed from the pool once their [closed](Client::closed) future resolves,
+/// i.e. when their server has been dropped or the connection to it has been lost.
+///
+/// The pool can be cloned and all clones share the same set of clients.
+/// A call is made through the pool by, for example,
+/// `pool.get().ok_or(CallError::Dropped)?.method().await`.
+pub struct Balanced<C> {
+    inner: Arc<Mutex<Inner<C>>>,
+    policy: BalancePolicy,
+}
+
+impl<C> Clone for Balanced<C> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), policy: self.policy }
+    }
+}
+
+impl<C> fmt::Debug for Balanced<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Balanced").field("len", &self.len()).field("policy", &self.policy).finish()
+    }
+}
+
+impl<C> Default for Balanced<C> {
+    fn default() -> Self {
+        Self::new(BalancePolicy::default())
+    }
+}
+
+impl<C> Balanced<C> {
+    /// Creates an empty pool using the specified balance policy.
+    pub fn new(policy: BalancePolicy) -> Self {
+        Self { inner: Arc::new(Mutex::new(Inner { entries: Vec::new(), next_id: 0, next: 0 })), policy }
+    }
+
+    /// The balance policy.
+    pub fn policy(&self) -> BalancePolicy {
+        self.policy
+    }
+
+    /// The number of clients in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// Whether the pool contains no clients.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<C> Balanced<C>
+where
+    C: Client + Clone + Send + 'static,
+{
+    /// Adds a client to the pool.
+    ///
+    /// The client is removed when its [closed](Client::closed) future resolves.
+    ///
+    /// # Panics
+    /// This method panics if called outside of an async runtime, since it spawns a task
+    /// that watches for the client being closed.
+    pub fn push(&self, client: C) {
+        let closed = client.closed();
+
+        let id = {
+            let mut inner = self.inner.lock().unwrap();
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.entries.push(Entry { id, client, load: Arc::new(()) });
+            id
+        };
+
+        let inner = Arc::downgrade(&self.inner);
+        exec::spawn(async move {
+            closed.await;
+            Self::remove(&inner, id);
+        });
+    }
+
+    fn remove(inner: &Weak<Mutex<Inner<C>>>, id: u64) {
+        let Some(inner) = inner.upgrade() else { return };
+        let mut inner = inner.lock().unwrap();
+        inner.entries.retain(|entry| entry.id != id);
+        tracing::debug!(id, remaining = inner.entries.len(), "removed closed client from pool");
+    }
+
+    /// Selects a client for making a call according to the [balance policy](BalancePolicy).
+    ///
+    /// Returns `None` if the pool contains no open clients.
+    pub fn get(&self) -> Option<Pooled<C>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.retain(|entry| !entry.client.is_closed());
+
+        let len = inner.entries.len();
+        if len == 0 {
+            return None;
+        }
+
+        let start = inner.next % len;
+        let idx = match self.policy {
+            BalancePolicy::RoundRobin => start,
+            BalancePolicy::LeastLoaded => (0..len)
+                .map(|n| (start + n) % len)
+                .min_by_key(|&idx| Arc::strong_count(&inner.entries[idx].load))
+                .unwrap_or(start),
+        };
+        inner.next = idx + 1;
+
+        let entry = &inner.entries[idx];
+        Some(Pooled { client: entry.client.clone(), _load: entry.load.clone() })
+    }
+
+    /// All clients currently in the pool.
+    pub fn clients(&self) -> Vec<C> {
+        self.inner.lock().unwrap().entries.iter().map(|entry| entry.client.clone()).collect()
+    }
+}
+
+impl<C> Extend<C> for Balanced<C>
+where
+    C: Client + Clone + Send + 'static,
+{
+    /// Adds the clients to the pool.
+    ///
+    /// # Panics
+    /// This method panics if called outside of an async runtime, see [push](Self::push).
+    fn extend<T: IntoIterator<Item = C>>(&mut self, iter: T) {
+        for client in iter {
+            self.push(client);
+        }
+    }
+}
+
+impl<C> FromIterator<C> for Balanced<C>
+where
+    C: Client + Clone + Send + 'static,
+{
+    /// Creates a pool using the default balance policy from the clients.
+    ///
+    /// # Panics
+    /// This method panics if called outside of an async runtime, see [push](Self::push).
+    fn from_iter<T: IntoIterator<Item = C>>(iter: T) -> Self {
+        let mut pool = Self::default();
+        pool.extend(iter);
+        pool
+    }
+}
+
+/// A client selected from a [balanced client pool](Balanced).
+///
+/// It dereferences to the client and counts as an outstanding call of the client
+/// until it is dropped.
+pub struct Pooled<C> {
+    client: C,
+    _load: Arc<()>,
+}
+
+impl<C> fmt::Debug for Pooled<C>
+where
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Pooled").field(&self.client).finish()
+    }
+}
+
+impl<C> Pooled<C> {
+    /// Returns the client, which then no longer counts towards the load of the pool.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C> Deref for Pooled<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.client
+    }
+}
+
+impl<C> DerefMut for Pooled<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.client
+    }
+}
@@ -67,6 +67,17 @@
 //!
 //! See [ReqReceiver] for details.
 //!
+//! # Call and reply types
+//!
+//! Assuming the trait is called `Trait`, the call and reply types will be called `TraitCall`
+//! and `TraitReply`.
+//!
+//! `TraitCall` is an enum containing a variant holding the arguments of each method and
+//! `TraitReply` is an enum containing a variant holding the result of each method.
+//! They allow to represent a method call as a value, which is performed on a client using
+//! [Callable::invoke].
+//! Streaming methods and methods taking stream arguments have no variants.
+//!
 //! # Usage
 //!
 //! Tag your trait with the [remote attribute](remote).
@@ -81,11 +92,120 @@
 //! of all trait functions must always be of the [Result] type.
 //! The error type must be able to convert from [CallError] and thus absorb the remote calling error.
 //!
-//! There is no timeout imposed on a remote call, but the underlying [chmux] connection
+//! By default there is no timeout imposed on a remote call, but the underlying [chmux] connection
 //! [pings the remote endpoint](chmux::Cfg::connection_timeout) by default.
 //! If the underlying connection fails, all remote calls will automatically fail.
-//! You can wrap remote calls using [tokio::time::timeout] if you need to use
-//! per-call timeouts.
+//! See the [timeouts section](self#timeouts) for limiting the duration of remote calls.
+//!
+//! # Timeouts
+//!
+//! A timeout can be set for all calls made through a client using [ConfigurableClient::set_timeout]
+//! or for a single call using [ConfigurableClient::with_timeout], for example
+//! `client.clone().with_timeout(Duration::from_secs(5)).method()`.
+//! The `#[timeout = "5s"]` attribute on a trait method specifies the timeout of that method
+//! when none is set on the client.
+//! Durations are specified as an integer followed by one of the units `ms`, `s`, `m` or `h`.
+//!
+//! The time remaining until the deadline of the call is transmitted to the server along with
+//! the request.
+//! It is measured when the request is serialized, so that the time the client spent waiting
+//! to send the request is subtracted, and converted back into a deadline when the server
+//! receives the request.
+//! Thus the time a request spends queued on the server, for example due to a
+//! [concurrency limit](self#concurrency-limits), counts towards the timeout.
+//! The transit time over the connection is not known and thus not subtracted;
+//! since only durations are exchanged, the clocks of client and server need not be synchronized.
+//!
+//! Once the deadline has passed, the server cancels the trait method and the call fails
+//! with [CallError::Timeout].
+//! A call whose deadline has passed before it is dispatched is not executed at all.
+//! Methods marked with `#[no_cancel]` are otherwise always run to completion, but the client still
+//! stops waiting for the reply.
+//! Within the trait method, [CallContext::remaining] provides the time left until the deadline.
+//! Calls made through a client from within a trait method are limited to that time,
+//! so that the deadline propagates to nested remote calls.
+//!
+//! # Retries
+//!
+//! Methods marked with the `#[idempotent]` attribute can be retried transparently by the client
+//! when a call fails with an error that a further attempt may not encounter, i.e. with
+//! [CallError::Timeout], [CallError::Dropped], [CallError::RemoteConnect] or
+//! [CallError::RemoteSend], unless the latter is caused by the arguments.
+//! A call is only retried while the request channel to the server is open.
+//! Since a client is bound to the server it was created for, the loss of the connection
+//! or of the server is not retried; a new client must be obtained to recover from it.
+//! Retries are enabled by setting a [RetryPolicy] on the client using
+//! [ConfigurableClient::set_retry_policy] or [ConfigurableClient::with_retry_policy].
+//! Calls of other methods always fail on the first error.
+//! Since the arguments are sent again on each attempt, they must implement [Clone].
+//! The timeout of a call applies to each attempt separately, i.e. each attempt is given the full
+//! timeout and its deadline is sent to the server along with the request.
+//! Thus a call may take up to [RetryPolicy::max_attempts] times the timeout in total, plus the
+//! delays between the attempts.
+//!
+//! # Streaming
+//!
+//! A trait method declared as `fn` with return type `impl Stream<Item = Result<T, E>> + Send`
+//! streams its items to the client as they are produced.
+//! On the client the method returns a stream that sends the request when it is first polled.
+//! Items are transmitted over a [remote mpsc channel](crate::rch::mpsc) with a buffer of one item,
+//! so that the server only produces items as fast as the client consumes them.
+//! When the client drops the stream, the stream returned by the trait method on the server is dropped.
+//! Errors occurring while transmitting the request or the items are returned as stream items.
+//!
+//! The [timeout](self#timeouts) and [retry policy](self#retries) of the client do not apply to
+//! streaming methods, since the duration of a stream is unbounded and items may already have been
+//! received when an error occurs.
+//! Consequently the `#[timeout]`, `#[no_cancel]` and `#[idempotent]` attributes are rejected
+//! on streaming methods.
+//! To stop a streaming call, the client drops the stream.
+//!
+//! A method may also take arguments of type `impl Stream<Item = T> + Send`.
+//! The client forwards the items of the argument to the server while the call is in progress.
+//! Methods taking stream arguments cannot be `#[idempotent]`, since the items cannot be sent again.
+//! Streaming methods may take stream arguments as well, allowing bidirectional streaming;
+//! their items are forwarded while the returned stream is being polled.
+//! Streaming is not supported when the trait is declared with `async_trait`.
+//!
+//! # Concurrency limits
+//!
+//! When serving with `spawn` set to true, the [shared](ServerShared) and
+//! [shared mutable](ServerSharedMut) servers spawn a task per call taking `&self`.
+//! By default the number of concurrently executing calls is unlimited.
+//! It can be limited using [LimitableServer::set_max_concurrency].
+//! Once the limit is reached, the server either waits for a running call to complete
+//! before accepting the next request or rejects further calls with [CallError::Overloaded],
+//! as configured by [LimitableServer::set_limit_behavior].
+//! Rejections are sent by the serving loop itself without spawning a task.
+//! Thus the number of spawned tasks never exceeds the limit.
+//!
+//! # Interceptors
+//!
+//! An [Interceptor] wraps each method call executed by a server.
+//! It can observe the call before and after execution, measure its duration,
+//! inspect the arguments and result serialized using the codec of the server
+//! if it [opts in](Interceptor::inspects_payload) and short-circuit it by replying with
+//! a [CallError] or a [result](CallOutcome::reply) instead of invoking the trait method.
+//! An interceptor that neither invokes the trait method nor replies fails the call
+//! with [CallError::Intercepted].
+//! This allows implementing authorization, metrics, rate limiting and logging once and
+//! applying them to every server type using [InterceptableServer::add_interceptor].
+//! Interceptors are executed in the order they were added, with the first interceptor
+//! being the outermost.
+//!
+//! # Load balancing
+//!
+//! A [Balanced] pool holds several clients for the same remote trait, for example
+//! connected to identical workers, and spreads calls across them according to a
+//! [BalancePolicy].
+//! Clients are removed from the pool when their server is dropped or the connection
+//! to it is lost.
+//!
+//! # Tower integration
+//!
+//! Enable the `tower` crate feature to use generated clients and request receivers
+//! with [Tower](https://docs.rs/tower) services and layers, as described in the
+//! documentation of the `rtc::tower` module.
 //!
 //! # Cancellation
 //!
@@ -93,6 +213,25 @@
 //! the trait function on the server is automatically cancelled at the next `await` point.
 //! You can apply the `#[no_cancel]` attribute to a method to always run it to completion.
 //!
+//! # Call context
+//!
+//! Within a trait method invoked by a server, [CallContext::current] provides information
+//! about the connection the call was received over.
+//! This includes the [identity](chmux::PeerIdentity) of the remote endpoint established by
+//! a [handshake](chmux::Handshake) as well as the address and metadata set on the
+//! [peer information](chmux::PeerInfo) of the connection.
+//! Use it for authorization or audit logging when serving multiple remote endpoints.
+//!
+//! # Metadata
+//!
+//! Each request carries [metadata](Metadata), i.e. a map of string keys to string values,
+//! which can be used to propagate tracing ids, deadlines or authentication tokens.
+//! The client sends the metadata set by [ConfigurableClient::with_metadata] or
+//! [ConfigurableClient::call_options_mut]
+//! along with every call.
+//! On the server, the metadata is available from [CallContext::metadata] within the called
+//! method and from [Req::metadata] within [ServerMonitor::pre_dispatch].
+//!
 //! # Associated types
 //!
 //! A remote trait may declare associated types (`type Item: RemoteSend;`).
@@ -233,17 +372,31 @@
 //! ```
 //!
 
-use futures::future::BoxFuture;
+use bytes::Bytes;
+use futures::{
+    future,
+    stream::{self, BoxStream},
+};
 use std::{
+    collections::BTreeMap,
     error::Error,
     fmt,
     future::Future,
+    num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 use tokio_util::sync::ReusableBoxFuture;
 
+mod balanced;
+#[cfg(feature = "tower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+pub mod tower;
+
+pub use balanced::{BalancePolicy, Balanced, Pooled};
+
 use crate::{
     RemoteSend, chmux, codec, exec,
     rch::{SendingError, SendingErrorKind, base, mpsc, oneshot},
@@ -264,10 +417,13 @@ use crate::{
 ///
 ///   * an `async fn` and have return type `Result<T, E>`,
 ///   * a `fn` and have return type `impl Future<Output = Result<T, E>> + Send`,
+///   * a `fn` and have return type `impl Stream<Item = Result<T, E>> + Send`,
 ///
 /// where `T` and `E` are [remote sendable](crate::RemoteSend) and `E` must
 /// implemented [`From`]`<`[`CallError`]`>`.
-/// All arguments must also be [remote sendable](crate::RemoteSend).
+/// All arguments must also be [remote sendable](crate::RemoteSend) or be of the form
+/// `impl Stream<Item = T> + Send` with `T` remote sendable.
+/// See [streaming](self#streaming) for details.
 /// Of course, you can use all remote types from Remoc in your arguments and return type,
 /// for example [remote channels](crate::rch) and [remote objects](crate::rch).
 ///
@@ -322,6 +478,11 @@ use crate::{
 /// If the `#[no_cancel]` attribute is applied on a trait method, it will run to completion,
 /// even if the client cancels the request by dropping the future.
 ///
+/// The `#[idempotent]` attribute marks a trait method as safe to call more than once.
+/// Failed calls of such a method are retried according to the [retry policy](RetryPolicy)
+/// of the client.
+/// The method must take `self` by reference and all its arguments must be [clonable](Clone).
+///
 /// All [serde field attributes](https://serde.rs/field-attrs.html) `#[serde(...)]`
 /// are allowed on the arguments of the functions.
 /// They will be transferred to the respective field of the request struct that will
@@ -349,6 +510,14 @@ pub enum CallError {
     RemoteListen(chmux::ListenerError),
     /// Forwarding at a remote endpoint to another remote endpoint failed.
     RemoteForward,
+    /// The [timeout](self#timeouts) of the call elapsed.
+    Timeout,
+    /// The server rejected the call because its [concurrency limit](self#concurrency-limits)
+    /// was reached.
+    Overloaded,
+    /// An [interceptor](Interceptor) completed the call without executing the trait method
+    /// or replying in its place.
+    Intercepted,
 }
 
 impl fmt::Display for CallError {
@@ -360,6 +529,9 @@ impl fmt::Display for CallError {
             Self::RemoteConnect(err) => write!(f, "connect error: {err}"),
             Self::RemoteListen(err) => write!(f, "listen error: {err}"),
             Self::RemoteForward => write!(f, "forwarding error"),
+            Self::Timeout => write!(f, "call timed out"),
+            Self::Overloaded => write!(f, "server overloaded"),
+            Self::Intercepted => write!(f, "call intercepted without reply"),
         }
     }
 }
@@ -378,6 +550,16 @@ impl<T> From<mpsc::SendError<T>> for CallError {
     }
 }
 
+impl From<mpsc::RecvError> for CallError {
+    fn from(err: mpsc::RecvError) -> Self {
+        match err {
+            mpsc::RecvError::RemoteReceive(err) => Self::RemoteReceive(err),
+            mpsc::RecvError::RemoteConnect(err) => Self::RemoteConnect(err),
+            mpsc::RecvError::RemoteListen(err) => Self::RemoteListen(err),
+        }
+    }
+}
+
 impl From<oneshot::RecvError> for CallError {
     fn from(err: oneshot::RecvError) -> Self {
         match err {
@@ -389,6 +571,136 @@ impl From<oneshot::RecvError> for CallError {
     }
 }
 
+tokio::task_local! {
+    static CALL_CONTEXT: CallContext;
+}
+
+/// Context of a remote call executed by a server.
+///
+/// See the [module-level documentation](self#call-context) for details.
+#[derive(Clone, Debug, Default)]
+pub struct CallContext {
+    peer_info: Option<chmux::PeerInfo>,
+    metadata: Metadata,
+    deadline: Deadline,
+}
+
+impl CallContext {
+    /// Returns the context of the remote call currently being executed.
+    ///
+    /// Returns `None` if not called from within a trait method invoked by a server.
+    pub fn current() -> Option<Self> {
+        CALL_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    /// Information about the remote endpoint that made the call.
+    ///
+    /// Returns `None` if the call was not received from a remote endpoint,
+    /// i.e. the client is located on the same endpoint as the server.
+    pub fn peer_info(&self) -> Option<&chmux::PeerInfo> {
+        self.peer_info.as_ref()
+    }
+
+    /// Identity of the remote endpoint that made the call, as established by the
+    /// [handshake](chmux::Handshake).
+    pub fn peer_identity(&self) -> Option<&chmux::PeerIdentity> {
+        self.peer_info.as_ref().and_then(|peer_info| peer_info.identity())
+    }
+
+    /// Metadata sent by the client along with the call.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Timeout of the call, i.e. the time that remained until its deadline when the
+    /// request was received.
+    ///
+    /// See the [module-level documentation](self#timeouts) for details.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.deadline.timeout
+    }
+
+    /// Time remaining until the deadline of the call passes and it is cancelled.
+    ///
+    /// Returns `None` if the call has no timeout.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.remaining()
+    }
+
+    /// Captures the context of a request while it is being deserialized.
+    #[doc(hidden)]
+    pub fn received() -> Self {
+        Self {
+            peer_info: base::PortDeserializer::peer_info::<serde::de::value::Error>().ok(),
+            ..Default::default()
+        }
+    }
+
+    /// Executes the future of a call with the specified metadata and deadline within this context.
+    #[doc(hidden)]
+    pub fn scope<F>(
+        mut self, metadata: Metadata, deadline: Deadline, fut: F,
+    ) -> impl Future<Output = F::Output> + Send
+    where
+        F: Future + Send,
+    {
+        self.metadata = metadata;
+        self.deadline = deadline;
+        CALL_CONTEXT.scope(self, fut)
+    }
+}
+
+/// Deadline of a call.
+///
+/// It is transmitted as the time remaining until the deadline, measured when it is serialized,
+/// and converted back into a deadline when it is deserialized.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Deadline {
+    /// Timeout the deadline was created from.
+    timeout: Option<Duration>,
+    /// Point in time when the deadline passes.
+    at: Option<exec::time::Instant>,
+}
+
+impl Deadline {
+    /// Deadline after the specified timeout has elapsed from now.
+    pub fn after(timeout: Option<Duration>) -> Self {
+        Self { timeout, at: timeout.and_then(|timeout| exec::time::Instant::now().checked_add(timeout)) }
+    }
+
+    /// Time remaining until the deadline passes.
+    pub fn remaining(&self) -> Option<Duration> {
+        match (self.timeout, self.at) {
+            (Some(_), Some(at)) => Some(at.duration_since(exec::time::Instant::now())),
+            (timeout, _) => timeout,
+        }
+    }
+
+    /// Whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_some_and(|remaining| remaining.is_zero())
+    }
+}
+
+impl Serialize for Deadline {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.remaining().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deadline {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Self::after(Option::<Duration>::deserialize(deserializer)?))
+    }
+}
+
 /// A request from client to server.
 #[derive(Serialize, Deserialize)]
 pub enum Req<V, R, M> {
@@ -400,6 +712,79 @@ pub enum Req<V, R, M> {
     RefMut(M),
 }
 
+impl<V, R, M> Req<V, R, M>
+where
+    V: ReqMetadata,
+    R: ReqMetadata,
+    M: ReqMetadata,
+{
+    /// Metadata sent by the client along with the request.
+    pub fn metadata(&self) -> &Metadata {
+        match self {
+            Self::Value(req) => req.metadata(),
+            Self::Ref(req) => req.metadata(),
+            Self::RefMut(req) => req.metadata(),
+        }
+    }
+}
+
+/// Metadata sent along with a request.
+///
+/// See the [module-level documentation](self#metadata) for details.
+pub type Metadata = BTreeMap<String, String>;
+
+/// Policy for retrying failed calls of idempotent methods.
+///
+/// See the [module-level documentation](self#retries) for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the initial call.
+    ///
+    /// The default value is 3.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    ///
+    /// The delay doubles with each further retry.
+    /// The default value is 100 milliseconds.
+    pub initial_backoff: Duration,
+    /// Maximum delay between two attempts.
+    ///
+    /// The default value is 5 seconds.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_backoff: Duration::from_millis(100), max_backoff: Duration::from_secs(5) }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt after the specified number of attempts have failed.
+    ///
+    /// Returns `None` if no more attempts should be made.
+    pub fn backoff(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        Some(self.initial_backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+/// Provides the [metadata](Metadata) of a request.
+///
+/// This is implemented by the request types generated by the [remote attribute](remote).
+pub trait ReqMetadata {
+    /// Metadata sent by the client along with the request.
+    fn metadata(&self) -> &Metadata;
+}
+
+/// Empty metadata.
+#[doc(hidden)]
+pub static EMPTY_METADATA: Metadata = Metadata::new();
+
 /// Client of a remotable trait.
 pub trait Client {
     /// Returns the current capacity of the channel for sending requests to
@@ -433,8 +818,133 @@ pub trait Client {
 
     /// Sets the maximum allowed size of a reply in bytes.
     fn set_max_reply_size(&mut self, max_reply_size: usize);
+
+    /// The options applied to every call made through this client.
+    ///
+    /// The default implementation returns the [default options](CallOptions::new),
+    /// which is appropriate for clients that do not support call options.
+    fn call_options(&self) -> &CallOptions {
+        &DEFAULT_CALL_OPTIONS
+    }
+
+    /// The metadata sent along with every request.
+    fn metadata(&self) -> &Metadata {
+        &self.call_options().metadata
+    }
+
+    /// The timeout of calls made through this client.
+    ///
+    /// If `None`, the timeout specified on the trait method is used, if any.
+    fn timeout(&self) -> Option<Duration> {
+        self.call_options().timeout
+    }
+
+    /// The policy for retrying failed calls of idempotent methods.
+    ///
+    /// If `None`, failed calls are not retried.
+    fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.call_options().retry_policy.as_ref()
+    }
+}
+
+/// Client of a remotable trait whose method calls can be represented as values.
+///
+/// This is implemented by the clients generated by the [remote attribute](remote).
+/// See the [module-level documentation](self#call-and-reply-types) for details.
+pub trait Callable: Client {
+    /// Method call, i.e. the generated `TraitCall` enum.
+    type Call: Send + 'static;
+    /// Result of a method call, i.e. the generated `TraitReply` enum.
+    type Reply: Send + 'static;
+
+    /// Performs the method call on this client and returns its result.
+    fn invoke(self, call: Self::Call) -> BoxFuture<'static, Self::Reply>;
 }
 
+/// Client of a remotable trait whose [call options](CallOptions) can be modified.
+///
+/// This is implemented by the clients generated by the [remote attribute](remote).
+pub trait ConfigurableClient: Client {
+    /// Mutable reference to the options applied to every call made through this client.
+    fn call_options_mut(&mut self) -> &mut CallOptions;
+
+    /// Sets the metadata value for the specified key, which is sent along with
+    /// every request, and returns the modified client.
+    ///
+    /// This allows setting metadata for a single call, for example
+    /// `client.clone().with_metadata("trace-id", id).method()`.
+    fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self
+    where
+        Self: Sized,
+    {
+        self.call_options_mut().metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the timeout of calls made through this client.
+    ///
+    /// The timeout does not apply to [streaming methods](self#streaming).
+    /// See the [module-level documentation](self#timeouts) for details.
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.call_options_mut().timeout = timeout;
+    }
+
+    /// Sets the timeout of calls made through this client and returns the modified client.
+    ///
+    /// This allows setting a timeout for a single call, for example
+    /// `client.clone().with_timeout(Duration::from_secs(5)).method()`.
+    fn with_timeout(mut self, timeout: Duration) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_timeout(Some(timeout));
+        self
+    }
+
+    /// Sets the policy for retrying failed calls of idempotent methods.
+    ///
+    /// Calls of [streaming methods](self#streaming) are never retried.
+    /// See the [module-level documentation](self#retries) for details.
+    fn set_retry_policy(&mut self, retry_policy: Option<RetryPolicy>) {
+        self.call_options_mut().retry_policy = retry_policy;
+    }
+
+    /// Sets the policy for retrying failed calls of idempotent methods and returns the modified client.
+    fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_retry_policy(Some(retry_policy));
+        self
+    }
+}
+
+/// Options applied to every call made through a [client](Client).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CallOptions {
+    /// Metadata sent along with every request.
+    pub metadata: Metadata,
+    /// Timeout of calls.
+    ///
+    /// If `None`, the timeout specified on the trait method is used, if any.
+    pub timeout: Option<Duration>,
+    /// Policy for retrying failed calls of idempotent methods.
+    ///
+    /// If `None`, failed calls are not retried.
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+impl CallOptions {
+    /// Default call options: no metadata, no timeout and no retries.
+    pub const fn new() -> Self {
+        Self { metadata: Metadata::new(), timeout: None, retry_policy: None }
+    }
+}
+
+/// Default call options.
+static DEFAULT_CALL_OPTIONS: CallOptions = CallOptions::new();
+
 /// A future that completes when the server or client has been dropped
 /// or the connection between them has been lost.
 ///
@@ -584,6 +1094,97 @@ pub trait MonitorableServer {
     fn set_monitor(&mut self, monitor: impl ServerMonitor<Self::Value, Self::Ref, Self::RefMut> + 'static);
 }
 
+/// Allows limiting the number of concurrently executing calls of a server.
+///
+/// This is implemented by servers that spawn a task per call.
+/// See the [module-level documentation](self#concurrency-limits) for details.
+pub trait LimitableServer {
+    /// The maximum number of concurrently executing calls.
+    ///
+    /// If `None`, the number of concurrent calls is unlimited.
+    fn max_concurrency(&self) -> Option<NonZeroUsize>;
+
+    /// Sets the maximum number of concurrently executing calls.
+    ///
+    /// The default is `None`, i.e. unlimited.
+    /// The limit only applies when serving with `spawn` set to true.
+    fn set_max_concurrency(&mut self, limit: Option<NonZeroUsize>);
+
+    /// The behavior when the maximum number of concurrent calls has been reached.
+    fn limit_behavior(&self) -> LimitBehavior;
+
+    /// Sets the behavior when the maximum number of concurrent calls has been reached.
+    fn set_limit_behavior(&mut self, behavior: LimitBehavior);
+}
+
+/// Behavior of a server when the [maximum number of concurrent calls](LimitableServer::set_max_concurrency)
+/// has been reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitBehavior {
+    /// Wait until a running call has completed before accepting the next request.
+    ///
+    /// Further requests are queued in the request channel and clients wait
+    /// when it is full.
+    /// The time a call spends queued counts towards its [timeout](self#timeouts).
+    #[default]
+    Queue,
+    /// Reject further calls with [CallError::Overloaded].
+    Reject,
+}
+
+/// Limits the number of concurrently executing calls of a server.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct Limiter {
+    semaphore: Option<Arc<tokio::sync::Semaphore>>,
+    max_concurrency: Option<NonZeroUsize>,
+    behavior: LimitBehavior,
+}
+
+impl Limiter {
+    /// The maximum number of concurrently executing calls.
+    pub fn max_concurrency(&self) -> Option<NonZeroUsize> {
+        self.max_concurrency
+    }
+
+    /// Sets the maximum number of concurrently executing calls.
+    pub fn set_max_concurrency(&mut self, limit: Option<NonZeroUsize>) {
+        self.max_concurrency = limit;
+        self.semaphore = limit.map(|limit| Arc::new(tokio::sync::Semaphore::new(limit.get())));
+    }
+
+    /// The behavior when the limit has been reached.
+    pub fn behavior(&self) -> LimitBehavior {
+        self.behavior
+    }
+
+    /// Sets the behavior when the limit has been reached.
+    pub fn set_behavior(&mut self, behavior: LimitBehavior) {
+        self.behavior = behavior;
+    }
+
+    /// Obtains the permit for executing a call.
+    ///
+    /// In queue mode this waits until a running call has completed.
+    /// Returns `None` if the call should be rejected.
+    pub async fn acquire(&self) -> Option<LimiterPermit> {
+        let Some(semaphore) = &self.semaphore else { return Some(LimiterPermit { _permit: None }) };
+
+        let permit = match self.behavior {
+            LimitBehavior::Queue => semaphore.clone().acquire_owned().await.ok()?,
+            LimitBehavior::Reject => semaphore.clone().try_acquire_owned().ok()?,
+        };
+        Some(LimiterPermit { _permit: Some(permit) })
+    }
+}
+
+/// Permit for executing a call, released when dropped.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct LimiterPermit {
+    _permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
 /// Server request monitor.
 ///
 /// Allows monitoring each request a server handles.
@@ -631,6 +1232,295 @@ macro_rules! server_monitor_pre_dispatch {
 #[doc(hidden)]
 pub use crate::server_monitor_pre_dispatch;
 
+/// Allows adding [interceptors](Interceptor) to a server.
+pub trait InterceptableServer {
+    /// Adds an interceptor that wraps each method call executed by the server.
+    ///
+    /// See the [module-level documentation](self#interceptors) for details.
+    fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static);
+}
+
+/// Intercepts method calls executed by a server.
+///
+/// See the [module-level documentation](self#interceptors) for details.
+///
+/// # Example
+///
+/// The following interceptor rejects calls without an authorization token in the metadata,
+/// answers calls of the `greet` method with the argument `"cached"` itself
+/// and logs the duration of all other calls.
+///
+/// ```
+/// use futures::future::{BoxFuture, FutureExt};
+/// use remoc::{
+///     codec::{self, Codec},
+///     rtc::{CallError, CallInfo, CallOutcome, Interceptor, Next},
+/// };
+///
+/// struct Auth;
+///
+/// impl Interceptor for Auth {
+///     fn inspects_payload(&self) -> bool {
+///         true
+///     }
+///
+///     fn intercept<'a>(
+///         &'a self, call: &'a CallInfo, next: Next<'a>,
+///     ) -> BoxFuture<'a, Result<CallOutcome, CallError>> {
+///         async move {
+///             if !call.context().metadata().contains_key("token") {
+///                 return Err(CallError::Dropped);
+///             }
+///             if call.method() == "greet" {
+///                 if let Some(request) = call.request() {
+///                     if let Ok((name,)) = codec::Default::deserialize::<_, (String,)>(request) {
+///                         if name == "cached" {
+///                             let reply: Result<String, CallError> = Ok("Hello cached".to_string());
+///                             return CallOutcome::reply::<codec::Default, _, _>(&reply);
+///                         }
+///                     }
+///                 }
+///             }
+///             let outcome = next.run().await?;
+///             println!("{}::{} took {:?}", call.trait_name(), call.method(), outcome.elapsed());
+///             Ok(outcome)
+///         }
+///         .boxed()
+///     }
+/// }
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Intercepts a method call.
+    ///
+    /// Calling [Next::run] executes the remaining interceptors and the trait method.
+    /// If an error is returned, it is sent as reply to the client instead of the result
+    /// of the trait method.
+    /// If an outcome created by [CallOutcome::reply] is returned, its result is sent as reply
+    /// to the client, regardless of whether [Next::run] has been called.
+    /// If any other outcome is returned, for example one retained from a previous call,
+    /// without [Next::run] having executed the trait method, the call fails with
+    /// [CallError::Intercepted].
+    /// If the call is cancelled, the returned future is dropped.
+    fn intercept<'a>(
+        &'a self, call: &'a CallInfo, next: Next<'a>,
+    ) -> BoxFuture<'a, Result<CallOutcome, CallError>>;
+
+    /// Whether the interceptor inspects the serialized arguments and result of calls.
+    ///
+    /// Serializing the arguments and result of each call is costly, thus it is only done
+    /// if at least one interceptor of the server returns `true`.
+    /// Otherwise [CallInfo::request] and [CallOutcome::result] return `None`.
+    ///
+    /// The default implementation returns `false`.
+    fn inspects_payload(&self) -> bool {
+        false
+    }
+}
+
+/// Information about a method call passed to an [Interceptor].
+#[derive(Clone, Debug)]
+pub struct CallInfo {
+    trait_name: &'static str,
+    method: &'static str,
+    context: CallContext,
+    request: Option<Bytes>,
+}
+
+impl CallInfo {
+    /// Name of the remote trait.
+    pub fn trait_name(&self) -> &'static str {
+        self.trait_name
+    }
+
+    /// Name of the called method.
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    /// Context of the call.
+    pub fn context(&self) -> &CallContext {
+        &self.context
+    }
+
+    /// Arguments of the call serialized using the codec of the server.
+    ///
+    /// The arguments are serialized as a tuple in declaration order,
+    /// excluding stream arguments.
+    /// This is `None` if no interceptor of the server [inspects the payload](Interceptor::inspects_payload)
+    /// or if the arguments could not be serialized, for example because
+    /// they contain remote channels, which can only be serialized for sending.
+    pub fn request(&self) -> Option<&[u8]> {
+        self.request.as_deref()
+    }
+}
+
+/// Outcome of an executed method call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallOutcome {
+    ok: bool,
+    elapsed: Duration,
+    result: Option<Bytes>,
+    reply: bool,
+}
+
+impl CallOutcome {
+    /// Creates an outcome that replies to the client with the specified result
+    /// instead of the result of the trait method.
+    ///
+    /// The result must be of the return type of the called method.
+    /// For streaming methods it must be of type `Result<(), CallError>` and an error is sent
+    /// to the client as the only item of the stream.
+    /// `Codec` must be the codec of the server.
+    pub fn reply<Codec, T, E>(result: &Result<T, E>) -> Result<Self, CallError>
+    where
+        Codec: codec::Codec,
+        T: serde::Serialize,
+        E: serde::Serialize,
+    {
+        let mut data = Vec::new();
+        <Codec as codec::Codec>::serialize(&mut data, result)
+            .map_err(|err| CallError::RemoteSend(base::SendErrorKind::Serialize(err)))?;
+        Ok(Self { ok: result.is_ok(), elapsed: Duration::ZERO, result: Some(data.into()), reply: true })
+    }
+
+    /// Whether the trait method returned `Ok`.
+    pub fn is_ok(&self) -> bool {
+        self.ok
+    }
+
+    /// Whether the trait method returned `Err`.
+    pub fn is_err(&self) -> bool {
+        !self.ok
+    }
+
+    /// Execution time of the trait method.
+    ///
+    /// This is zero for an outcome created by [reply](Self::reply).
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Result of the call serialized using the codec of the server.
+    ///
+    /// For streaming methods the result is of type `Result<(), CallError>` and becomes
+    /// available once all items of the stream have been forwarded.
+    /// This is `None` if no interceptor of the server [inspects the payload](Interceptor::inspects_payload)
+    /// or if the result could not be serialized, for example because
+    /// it contains remote channels, which can only be serialized for sending.
+    pub fn result(&self) -> Option<&[u8]> {
+        self.result.as_deref()
+    }
+}
+
+/// The remaining [interceptors](Interceptor) and the trait method of a call.
+pub struct Next<'a> {
+    interceptors: &'a [Arc<dyn Interceptor>],
+    call: &'a CallInfo,
+    inner: BoxFuture<'a, CallOutcome>,
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Next").field("interceptors", &self.interceptors.len()).finish()
+    }
+}
+
+impl Next<'_> {
+    /// Executes the remaining interceptors and the trait method.
+    pub async fn run(self) -> Result<CallOutcome, CallError> {
+        let Self { interceptors, call, inner } = self;
+        match interceptors.split_first() {
+            Some((first, rest)) => first.intercept(call, Next { interceptors: rest, call, inner }).await,
+            None => Ok(inner.await),
+        }
+    }
+}
+
+/// Interceptors of a server.
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub struct Interceptors {
+    interceptors: Arc<Vec<Arc<dyn Interceptor>>>,
+    payload: bool,
+}
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Interceptors")
+            .field("interceptors", &self.interceptors.len())
+            .field("payload", &self.payload)
+            .finish()
+    }
+}
+
+impl Interceptors {
+    /// Adds an interceptor.
+    pub fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.payload |= interceptor.inspects_payload();
+        Arc::make_mut(&mut self.interceptors).push(Arc::new(interceptor));
+    }
+
+    /// Serializes the arguments of a call, if an interceptor inspects them.
+    pub fn request<Codec, A>(&self, args: &A) -> Option<Bytes>
+    where
+        Codec: codec::Codec,
+        A: serde::Serialize,
+    {
+        if !self.payload {
+            return None;
+        }
+
+        let mut data = Vec::new();
+        <Codec as codec::Codec>::serialize(&mut data, args).ok()?;
+        Some(data.into())
+    }
+}
+
+/// Executes a method call through the interceptors of a server.
+#[doc(hidden)]
+pub async fn intercept<Codec, T, E>(
+    interceptors: &Interceptors, trait_name: &'static str, method: &'static str, request: Option<Bytes>,
+    call: impl Future<Output = Result<T, E>> + Send,
+) -> Result<T, E>
+where
+    Codec: codec::Codec,
+    T: serde::Serialize + serde::de::DeserializeOwned + Send,
+    E: serde::Serialize + serde::de::DeserializeOwned + From<CallError> + Send,
+{
+    if interceptors.interceptors.is_empty() {
+        return call.await;
+    }
+
+    let info = CallInfo { trait_name, method, context: CallContext::current().unwrap_or_default(), request };
+    let mut result = None;
+    let inner = async {
+        let started = exec::time::Instant::now();
+        let res = call.await;
+        let elapsed = started.elapsed();
+        let data = if interceptors.payload {
+            let mut data = Vec::new();
+            <Codec as codec::Codec>::serialize(&mut data, &res).ok().map(|()| data.into())
+        } else {
+            None
+        };
+        let outcome = CallOutcome { ok: res.is_ok(), elapsed, result: data, reply: false };
+        result = Some(res);
+        outcome
+    };
+    let next = Next { interceptors: &interceptors.interceptors, call: &info, inner: inner.boxed() };
+
+    match next.run().await {
+        Ok(CallOutcome { result: Some(data), reply: true, .. }) => {
+            match <Codec as codec::Codec>::deserialize(&data[..]) {
+                Ok(res) => res,
+                Err(err) => Err(CallError::RemoteReceive(base::RecvError::Deserialize(err)).into()),
+            }
+        }
+        Ok(_) => result.unwrap_or_else(|| Err(CallError::Intercepted.into())),
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// The default [server request monitor](ServerMonitor).
 ///
 /// It handles all requests.
@@ -703,6 +1593,8 @@ pub use tokio::sync::mpsc as local_mpsc;
 #[doc(hidden)]
 pub type ReplyErrorSender = tokio::sync::mpsc::Sender<SendingErrorKind>;
 #[doc(hidden)]
+pub use futures::future::BoxFuture;
+#[doc(hidden)]
 pub use futures::future::FutureExt;
 #[doc(hidden)]
 pub use futures::stream::Stream;
@@ -755,6 +1647,207 @@ where
     );
 }
 
+/// Span for dispatching the request most recently received by a server.
+#[doc(hidden)]
+pub fn received_span<T, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize>(
+    req_rx: &mpsc::Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>,
+) -> tracing::Span {
+    req_rx.processing_span()
+}
+
+/// Awaits the future of a call, failing with [CallError::Timeout] once the timeout elapses.
+#[doc(hidden)]
+pub async fn with_timeout<T>(
+    timeout: Option<Duration>, fut: impl Future<Output = Result<T, CallError>>,
+) -> Result<T, CallError> {
+    match timeout {
+        Some(timeout) => exec::time::timeout(timeout, fut).await.unwrap_or(Err(CallError::Timeout)),
+        None => fut.await,
+    }
+}
+
+/// Limits the timeout of a call to the time remaining for the call currently being executed,
+/// so that its deadline propagates to nested calls.
+#[doc(hidden)]
+pub fn call_timeout(timeout: Option<Duration>) -> Option<Duration> {
+    match (timeout, CallContext::current().and_then(|ctx| ctx.remaining())) {
+        (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+        (timeout, remaining) => timeout.or(remaining),
+    }
+}
+
+/// Completes once the deadline of a call has passed.
+#[doc(hidden)]
+pub async fn expired(deadline: Deadline) {
+    match deadline.remaining() {
+        Some(remaining) => exec::time::sleep(remaining).await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Reply sent when the timeout of a call has elapsed.
+#[doc(hidden)]
+pub fn timeout_reply<T, E>() -> Result<T, E>
+where
+    E: From<CallError>,
+{
+    Err(CallError::Timeout.into())
+}
+
+/// Reply sent when a call is short-circuited by an interceptor.
+#[doc(hidden)]
+pub fn error_reply<T, E>(err: CallError) -> Result<T, E>
+where
+    E: From<CallError>,
+{
+    Err(err.into())
+}
+
+/// Reply sent when a call is rejected because the server is overloaded.
+#[doc(hidden)]
+pub fn overloaded_reply<T, E>() -> Result<T, E>
+where
+    E: From<CallError>,
+{
+    Err(CallError::Overloaded.into())
+}
+
+/// Calls an idempotent method, retrying failed attempts according to the retry policy.
+///
+/// Each attempt sends the request over `req_tx` and awaits the reply.
+/// A failed attempt is only retried if the request channel is still open.
+#[doc(hidden)]
+pub async fn call_idempotent<T, E, Fut, R, Codec, const BUFFER: usize>(
+    retry_policy: Option<&RetryPolicy>, req_tx: &mpsc::Sender<R, Codec, BUFFER>, mut attempt: impl FnMut() -> Fut,
+) -> Result<T, E>
+where
+    Fut: Future<Output = Result<Result<T, E>, CallError>>,
+    E: From<CallError>,
+    R: Send + 'static,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let err = match attempt().await {
+            Ok(reply) => return reply,
+            Err(err) => err,
+        };
+
+        let retryable = match &err {
+            CallError::Dropped | CallError::Timeout | CallError::RemoteConnect(_) => true,
+            CallError::RemoteSend(err) => !err.is_item_specific(),
+            _ => false,
+        };
+        let Some(backoff) = retry_policy.and_then(|policy| policy.backoff(attempts)) else {
+            return Err(err.into());
+        };
+        if !retryable || req_tx.is_closed() {
+            return Err(err.into());
+        }
+
+        tracing::debug!(%err, attempts, ?backoff, "retrying call of idempotent method");
+        exec::time::sleep(backoff).await;
+    }
+}
+
+/// Stream of the items returned by a streaming method.
+///
+/// The request is sent when the stream is polled for the first time.
+/// Afterwards the stream arguments are forwarded while the stream is being polled.
+#[doc(hidden)]
+pub fn reply_stream<T, E, Codec>(
+    send: impl Future<Output = Result<(), CallError>> + Send, reply_rx: mpsc::Receiver<Result<T, E>, Codec>,
+    forwarding: impl Future<Output = ()> + Send,
+) -> Pin<Box<impl Stream<Item = Result<T, E>> + Send>>
+where
+    T: RemoteSend,
+    E: RemoteSend + From<CallError>,
+    Codec: codec::Codec,
+{
+    let replies = async move {
+        if let Err(err) = send.await {
+            return stream::once(future::ready(Err(E::from(err)))).left_stream();
+        }
+
+        let replies = stream::unfold(Some(reply_rx), |reply_rx| async move {
+            let mut reply_rx = reply_rx?;
+            match reply_rx.recv().await {
+                Ok(Some(item)) => Some((item, Some(reply_rx))),
+                Ok(None) => None,
+                Err(err) => Some((Err(E::from(CallError::from(err))), None)),
+            }
+        });
+        let forwarding = stream::once(forwarding).filter_map(|()| future::ready(None));
+        stream::select(replies, forwarding).right_stream()
+    };
+    Box::pin(FutureExt::flatten_stream(replies))
+}
+
+/// Forwards the items of a stream over a remote mpsc channel.
+///
+/// Completes when the stream ends or the receiver is closed.
+#[doc(hidden)]
+pub async fn forward_stream<S, T, Codec>(stream: S, tx: mpsc::Sender<T, Codec>)
+where
+    S: Stream<Item = T>,
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    let mut stream = std::pin::pin!(stream);
+    loop {
+        tokio::select! {
+            biased;
+            () = tx.closed() => break,
+            item = stream.next() => {
+                let Some(item) = item else { break };
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Stream argument of a streaming method, received over a remote mpsc channel.
+///
+/// The stream ends when receiving fails.
+#[doc(hidden)]
+pub fn arg_stream<T, Codec>(rx: mpsc::Receiver<T, Codec>) -> BoxStream<'static, T>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    let items = stream::unfold(rx, |mut rx| async move {
+        match rx.recv().await {
+            Ok(Some(item)) => Some((item, rx)),
+            Ok(None) => None,
+            Err(err) => {
+                tracing::warn!(%err, "receiving stream argument failed");
+                None
+            }
+        }
+    });
+    futures::StreamExt::boxed(items)
+}
+
+/// Forwards two stream arguments concurrently.
+#[doc(hidden)]
+pub async fn join_forwarding(a: impl Future<Output = ()>, b: impl Future<Output = ()>) {
+    future::join(a, b).await;
+}
+
+/// Awaits the future of a call while forwarding its stream arguments.
+///
+/// Forwarding stops once the call has completed.
+#[doc(hidden)]
+pub async fn with_forwarding<R>(forwards: Vec<BoxFuture<'_, ()>>, call: impl Future<Output = R>) -> R {
+    let mut call = std::pin::pin!(call);
+    tokio::select! {
+        res = &mut call => res,
+        _ = future::join_all(forwards) => call.await,
+    }
+}
+
 /// Serialization for `max_reply_size` field.
 #[doc(hidden)]
 pub mod serde_max_reply_size {
@@ -0,0 +1,164 @@
+//! Integration with the Tower service abstraction.
+//!
+//! This allows applying [Tower](https://docs.rs/tower) layers, such as timeouts, load balancing
+//! and retries, to remote trait calling.
+//!
+//! On the client side, [ClientService] wraps a generated client into a [Service].
+//! Assuming the trait is called `Trait`, the request type is the generated `TraitCall` enum
+//! and the response type is the generated `TraitReply` enum, for example
+//! `CounterCall::Value {}` is answered by `CounterReply::Value(result)`.
+//! Each call is performed on a clone of the client using [Callable::invoke].
+//! The result of the method, including its error, is contained in the response, while
+//! the service itself only fails when the client has been disconnected.
+//!
+//! On the server side, [serve] passes each request received by a [request receiver](super::ReqReceiver)
+//! to a [Service], which must then reply on the reply channel contained in the request.
+//!
+//! This module requires the `tower` crate feature.
+
+use futures::{
+    Stream, StreamExt,
+    future::{BoxFuture, FutureExt, poll_fn},
+    stream::FuturesUnordered,
+};
+use std::{
+    error::Error,
+    fmt,
+    pin::pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+use super::{CallError, Callable};
+use crate::rch::mpsc;
+
+/// A generated client of a remote trait exposed as a [Service].
+///
+/// A request is a [call](Callable::Call) of a method, which is performed on a clone
+/// of the client, and the response is its [reply](Callable::Reply).
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Debug)]
+pub struct ClientService<C> {
+    client: C,
+}
+
+impl<C> ClientService<C> {
+    /// Wraps the specified client.
+    pub fn new(client: C) -> Self {
+        Self { client }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn get_ref(&self) -> &C {
+        &self.client
+    }
+
+    /// Returns a mutable reference to the wrapped client.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.client
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C> From<C> for ClientService<C> {
+    fn from(client: C) -> Self {
+        Self::new(client)
+    }
+}
+
+impl<C> Service<C::Call> for ClientService<C>
+where
+    C: Callable + Clone,
+{
+    type Response = C::Reply;
+    type Error = CallError;
+    type Future = BoxFuture<'static, Result<C::Reply, CallError>>;
+
+    /// Fails with [CallError::Dropped] when the server has been dropped
+    /// or the connection to it has been lost.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.client.is_closed() {
+            return Poll::Ready(Err(CallError::Dropped));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: C::Call) -> Self::Future {
+        self.client.clone().invoke(req).map(Ok).boxed()
+    }
+}
+
+/// Serving requests using a [Service] failed.
+#[derive(Debug)]
+pub enum ServiceError<E> {
+    /// Receiving a request from the client failed.
+    ReqReceive(mpsc::RecvError),
+    /// The service failed.
+    Service(E),
+}
+
+impl<E> fmt::Display for ServiceError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ReqReceive(err) => write!(f, "failed to receive RTC request: {err}"),
+            Self::Service(err) => write!(f, "service failed: {err}"),
+        }
+    }
+}
+
+impl<E> Error for ServiceError<E> where E: Error {}
+
+/// Passes each request received by a [request receiver](super::ReqReceiver) to the service.
+///
+/// The service must reply to each request on the reply channel contained in it.
+/// Requests are only received while the service is ready and the returned
+/// futures are executed concurrently.
+///
+/// Serving ends when the client is dropped and all outstanding calls have completed.
+/// It fails when the service fails or a non-final receive error occurs.
+pub async fn serve<R, Req, S>(receiver: R, mut service: S) -> Result<(), ServiceError<S::Error>>
+where
+    R: Stream<Item = Result<Req, mpsc::RecvError>>,
+    S: Service<Req, Response = ()>,
+{
+    let mut receiver = pin!(receiver);
+    let mut calls = FuturesUnordered::<S::Future>::new();
+
+    loop {
+        // Wait for the service to become ready while driving outstanding calls.
+        let ready = loop {
+            tokio::select! {
+                Some(res) = calls.next(), if !calls.is_empty() => res.map_err(ServiceError::Service)?,
+                ready = poll_fn(|cx| service.poll_ready(cx)) => break ready,
+            }
+        };
+        ready.map_err(ServiceError::Service)?;
+
+        // Receive the next request while driving outstanding calls.
+        let req = loop {
+            tokio::select! {
+                Some(res) = calls.next(), if !calls.is_empty() => res.map_err(ServiceError::Service)?,
+                req = receiver.next() => break req,
+            }
+        };
+        match req {
+            Some(Ok(req)) => calls.push(service.call(req)),
+            Some(Err(err)) if err.is_final() => break,
+            Some(Err(err)) => return Err(ServiceError::ReqReceive(err)),
+            None => break,
+        }
+    }
+
+    while let Some(res) = calls.next().await {
+        res.map_err(ServiceError::Service)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Distributed tracing context propagation.
+//!
+//! When the `trace-context` crate feature is enabled, the context of the [current span](Span::current)
+//! is transmitted along with each value sent over a [remote mpsc channel](crate::rch::mpsc),
+//! each call of a [remote function](crate::rfn) and each call of a
+//! [remote trait method](crate::rtc).
+//! The receiving side creates a span that is a child of the sending span.
+//! For remote mpsc channels it is available from [Receiver::span](crate::rch::mpsc::Receiver::span),
+//! while remote functions and remote trait methods are executed within it.
+//! Additionally, the span that is current when a value is received from a remote mpsc channel
+//! is linked to the sending span, so that the trace is connected even if the received span
+//! is not used.
+//!
+//! The span context is serialized using the [global text map propagator](opentelemetry::global)
+//! of OpenTelemetry.
+//! Thus, to make use of this feature, a propagator must be
+//! [registered](opentelemetry::global::set_text_map_propagator)
+//! and the [OpenTelemetry layer](tracing_opentelemetry::layer) must be added to the tracing subscriber
+//! on both endpoints.
+//!
+//! Support for this feature is negotiated when the [chmux](crate::chmux) connection is established.
+//! Span contexts are only transmitted if both endpoints have enabled it, as reported by
+//! [PeerInfo::trace_context](crate::chmux::PeerInfo::trace_context).
+//! Otherwise the data format is unchanged, so that endpoints with and without this feature
+//! can be connected.
+
+use opentelemetry::{global, trace::TraceContextExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Serialized context of a span for transmission to a remote endpoint.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext(HashMap<String, String>);
+
+impl TraceContext {
+    /// Captures the context of the [current span](Span::current).
+    pub fn current() -> Self {
+        Self::of(&Span::current())
+    }
+
+    /// Captures the context of the specified span.
+    pub fn of(span: &Span) -> Self {
+        let cx = span.context();
+        let mut fields = HashMap::new();
+        global::get_text_map_propagator(|propagator| propagator.inject_context(&cx, &mut fields));
+        Self(fields)
+    }
+
+    /// Whether no span context was captured.
+    ///
+    /// This is the case if no span was active or no propagator is registered.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Makes the span described by this context the parent of the specified span.
+    pub fn set_parent_of(&self, span: &Span) {
+        if self.is_empty() {
+            return;
+        }
+
+        let cx = global::get_text_map_propagator(|propagator| propagator.extract(&self.0));
+        let _ = span.set_parent(cx);
+    }
+
+    /// Adds a link to the span described by this context to the specified span.
+    pub fn link_to(&self, span: &Span) {
+        if self.is_empty() {
+            return;
+        }
+
+        let cx = global::get_text_map_propagator(|propagator| propagator.extract(&self.0));
+        span.add_link(cx.span().span_context().clone());
+    }
+
+    /// Creates a span for processing a received value that is a child of the span
+    /// described by this context.
+    ///
+    /// Returns a disabled span if this context is empty.
+    pub fn child_span(&self) -> Span {
+        if self.is_empty() {
+            return Span::none();
+        }
+
+        let span = tracing::info_span!("remoc receive");
+        self.set_parent_of(&span);
+        span
+    }
+}
@@ -0,0 +1,91 @@
+use bytes::{Buf, Bytes};
+use futures::{SinkExt, StreamExt, channel::mpsc as transport_mpsc, future::try_join};
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use remoc::{chmux, exec};
+
+/// Transfers compressible and incompressible data and returns the number of bytes
+/// sent over the transport from A to B.
+async fn transfer(compression: chmux::Compression) -> usize {
+    crate::init();
+
+    // Transport from A to B counts transmitted bytes.
+    let (a_tx, mut fw_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (mut fw_tx, b_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (b_tx, a_rx) = transport_mpsc::channel::<Bytes>(0);
+    let a_rx = a_rx.map(Ok::<_, std::io::Error>);
+    let b_rx = b_rx.map(Ok::<_, std::io::Error>);
+
+    let transmitted = Arc::new(AtomicUsize::new(0));
+    let fw_transmitted = transmitted.clone();
+    exec::spawn(async move {
+        while let Some(frame) = fw_rx.next().await {
+            fw_transmitted.fetch_add(frame.len(), Ordering::Relaxed);
+            if fw_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let cfg = chmux::Cfg { compression: Some(compression), ..Default::default() };
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    exec::spawn(a_mux.run());
+    exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (mut a_tx, _a_rx) = a_conn.unwrap();
+    let (_b_tx, mut b_rx) = b_conn;
+
+    let compressible: Bytes = "remote multiplexed objects and channels ".repeat(5_000).into();
+    let mut state = 0x2545_f491_u32;
+    let incompressible: Bytes = (0..100_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    let small: Bytes = "small".into();
+
+    for data in [compressible, incompressible, small] {
+        let (_, received) = tokio::join!(async { a_tx.send(data.clone()).await.unwrap() }, async {
+            b_rx.recv().await.unwrap().unwrap()
+        });
+        let mut received = received;
+        assert_eq!(received.copy_to_bytes(received.remaining()), data);
+    }
+
+    let transmitted = transmitted.load(Ordering::Relaxed);
+    println!("{compression:?}: transmitted {transmitted} bytes");
+    transmitted
+}
+
+#[cfg(feature = "compress-deflate")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn deflate() {
+    assert!(transfer(chmux::Compression::Deflate).await < 150_000);
+}
+
+#[cfg(feature = "compress-lz4")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn lz4() {
+    assert!(transfer(chmux::Compression::Lz4).await < 150_000);
+}
+
+#[cfg(feature = "compress-zstd")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn zstd() {
+    assert!(transfer(chmux::Compression::Zstd).await < 150_000);
+}
@@ -0,0 +1,76 @@
+use bytes::Buf;
+use futures::{future::try_join, stream::StreamExt};
+use std::time::Duration;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    chmux, exec,
+    exec::time::{Instant, sleep},
+};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn drain() {
+    crate::init();
+
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, mut a_listener), (b_mux, b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (mut a_tx, a_rx) = a_conn.unwrap();
+    let (b_tx, mut b_rx) = b_conn;
+
+    println!("Draining");
+    b_client.drain(Duration::from_secs(10));
+    sleep(Duration::from_millis(100)).await;
+
+    println!("Connecting while draining");
+    assert!(matches!(a_client.connect().await, Err(chmux::ConnectError::Rejected)));
+    assert!(matches!(b_client.connect().await, Err(chmux::ConnectError::Rejected)));
+
+    println!("Sending over open port");
+    a_tx.send(vec![1, 2, 3].into()).await.unwrap();
+    let received = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(received.remaining(), 3);
+
+    println!("Closing port");
+    drop((a_tx, a_rx, b_tx, b_rx));
+    b_run.await.unwrap().unwrap();
+    a_run.await.unwrap().unwrap();
+    assert!(a_listener.accept().await.is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn drain_timeout() {
+    crate::init();
+
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (_a_tx, _a_rx) = a_conn.unwrap();
+    let (_b_tx, mut b_rx) = b_conn;
+
+    println!("Draining with open port");
+    let start = Instant::now();
+    a_client.drain(Duration::from_millis(200));
+
+    a_run.await.unwrap().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    b_run.await.unwrap().unwrap();
+    assert!(b_rx.recv().await.is_err());
+}
@@ -0,0 +1,224 @@
+use futures::{
+    future::{self, join},
+    stream::StreamExt,
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    chmux,
+    chmux::{HandshakeChannel, HandshakeError, PeerIdentity},
+    exec,
+    exec::time::{sleep, timeout},
+};
+
+/// Handshake that sends a name and token and accepts the remote endpoint if its token matches.
+fn token_handshake(
+    name: &'static str, token: &'static str, expected_token: &'static str,
+) -> impl chmux::Handshake {
+    move |mut channel: HandshakeChannel| async move {
+        channel.send(name).await?;
+        channel.send(token).await?;
+
+        let remote_name = channel.recv().await?;
+        let remote_token = channel.recv().await?;
+        if remote_token != expected_token.as_bytes() {
+            return Err(HandshakeError::failed("invalid token"));
+        }
+
+        Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn handshake() {
+    crate::init();
+
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        chmux::ChMux::new_with_handshake(cfg.clone(), a_tx, a_rx, token_handshake("a", "secret", "secret")),
+        chmux::ChMux::new_with_handshake(cfg, b_tx, b_rx, token_handshake("b", "secret", "secret")),
+    )
+    .await;
+    let (a_mux, a_client, a_listener) = a.unwrap();
+    let (b_mux, b_client, mut b_listener) = b.unwrap();
+
+    assert_eq!(a_mux.peer_identity(), Some(&PeerIdentity::new("b")));
+    assert_eq!(b_mux.peer_identity(), Some(&PeerIdentity::new("a")));
+    assert_eq!(a_listener.peer_identity().map(|id| id.as_str()), Some("b"));
+    assert_eq!(b_listener.peer_identity().map(|id| id.as_str()), Some("a"));
+
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    println!("Connecting");
+    let (a_conn, b_conn) = join(a_client.connect(), async {
+        let b_req = b_listener.inspect().await.unwrap().unwrap();
+        println!("Request: {b_req:?}");
+        assert_eq!(b_req.peer_identity(), Some(&PeerIdentity::new("a")));
+        b_req.accept().await.unwrap()
+    })
+    .await;
+    let (mut a_tx, _a_rx) = a_conn.unwrap();
+    let (_b_tx, mut b_rx) = b_conn;
+
+    println!("Sending");
+    a_tx.send("data".into()).await.unwrap();
+    assert_eq!(Vec::from(b_rx.recv().await.unwrap().unwrap()), b"data");
+
+    println!("Terminating");
+    b_client.terminate();
+    drop((a_client, a_listener, b_listener));
+    let _ = a_run.await.unwrap();
+    let _ = b_run.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn handshake_rejected() {
+    crate::init();
+
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        chmux::ChMux::new_with_handshake(cfg.clone(), a_tx, a_rx, token_handshake("a", "wrong", "secret")),
+        chmux::ChMux::new_with_handshake(cfg, b_tx, b_rx, token_handshake("b", "secret", "secret")),
+    )
+    .await;
+
+    let a_err = a.unwrap_err();
+    let b_err = b.unwrap_err();
+    println!("A error: {a_err}");
+    println!("B error: {b_err}");
+    assert!(matches!(a_err, chmux::ChMuxError::Handshake(HandshakeError::RemoteFailed)));
+    assert!(matches!(b_err, chmux::ChMuxError::Handshake(HandshakeError::Failed(_))));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn handshake_mismatch() {
+    crate::init();
+
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        chmux::ChMux::new_with_handshake(cfg.clone(), a_tx, a_rx, token_handshake("a", "secret", "secret")),
+        chmux::ChMux::new(cfg, b_tx, b_rx),
+    )
+    .await;
+
+    assert!(matches!(a.unwrap_err(), chmux::ChMuxError::Handshake(HandshakeError::Mismatch)));
+    assert!(matches!(b.unwrap_err(), chmux::ChMuxError::Handshake(HandshakeError::Mismatch)));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn handshake_rejected_locally() {
+    crate::init();
+
+    let cfg = chmux::Cfg { connection_timeout: None, ..Default::default() };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    // Remote handshake never finishes.
+    let _b = exec::spawn(chmux::ChMux::new_with_handshake(cfg.clone(), b_tx, b_rx, |_channel| {
+        future::pending::<Result<PeerIdentity, HandshakeError>>()
+    }));
+
+    let a = timeout(
+        Duration::from_secs(10),
+        chmux::ChMux::new_with_handshake(cfg, a_tx, a_rx, |_channel| async {
+            Err(HandshakeError::failed("rejected"))
+        }),
+    )
+    .await
+    .expect("local rejection did not end handshake");
+    assert!(matches!(a.unwrap_err(), chmux::ChMuxError::Handshake(HandshakeError::Failed(_))));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn handshake_flood() {
+    crate::init();
+
+    const N_MSG: usize = 1000;
+
+    let sent = Arc::new(AtomicUsize::new(0));
+    let cfg = chmux::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        chmux::ChMux::new_with_handshake(cfg.clone(), a_tx, a_rx, {
+            let sent = sent.clone();
+            move |mut channel: HandshakeChannel| {
+                let sent = sent.clone();
+                async move {
+                    for _ in 0..N_MSG {
+                        channel.send("flood").await?;
+                        sent.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(PeerIdentity::new("b"))
+                }
+            }
+        }),
+        chmux::ChMux::new_with_handshake(cfg, b_tx, b_rx, {
+            let sent = sent.clone();
+            move |mut channel: HandshakeChannel| {
+                let sent = sent.clone();
+                async move {
+                    // Receiving slowly must hold back the remote endpoint instead of buffering its messages.
+                    for i in 0..N_MSG {
+                        if i % 100 == 0 {
+                            sleep(Duration::from_millis(10)).await;
+                            let ahead = sent.load(Ordering::SeqCst) - i;
+                            assert!(ahead <= 8, "remote endpoint is {ahead} messages ahead");
+                        }
+                        assert_eq!(channel.recv().await?, "flood");
+                    }
+                    Ok(PeerIdentity::new("a"))
+                }
+            }
+        }),
+    )
+    .await;
+    let _a = a.unwrap();
+    let _b = b.unwrap();
+}
+
+#[cfg(feature = "rch")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn connect_with_handshake() {
+    use remoc::rch::base;
+
+    crate::init();
+
+    let cfg = remoc::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        remoc::ConnectOptions::new(cfg.clone())
+            .handshake(token_handshake("a", "secret", "secret"))
+            .framed(a_tx, a_rx),
+        remoc::ConnectOptions::new(cfg).handshake(token_handshake("b", "secret", "secret")).framed(b_tx, b_rx),
+    )
+    .await;
+    let (a_conn, mut a_tx, _a_rx): (_, base::Sender<u32>, base::Receiver<u32>) = a.unwrap();
+    let (b_conn, _b_tx, mut b_rx): (_, base::Sender<u32>, base::Receiver<u32>) = b.unwrap();
+
+    assert_eq!(a_conn.peer_identity().map(|id| id.as_str()), Some("b"));
+    assert_eq!(b_conn.peer_identity().map(|id| id.as_str()), Some("a"));
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    a_tx.send(123).await.unwrap();
+    assert_eq!(b_rx.recv().await.unwrap(), Some(123));
+}
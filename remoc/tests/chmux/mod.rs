@@ -1,4 +1,13 @@
 mod channel;
+#[cfg(any(feature = "compress-deflate", feature = "compress-lz4", feature = "compress-zstd"))]
+mod compress;
+mod drain;
+mod handshake;
+mod ping;
+mod priority;
+mod quota;
+mod session;
+mod stats;
 
 #[cfg(not(target_family = "wasm"))]
 mod tcp;
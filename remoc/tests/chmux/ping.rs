@@ -0,0 +1,60 @@
+use futures::{future::try_join, stream::StreamExt};
+use std::time::Duration;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{chmux, exec, exec::time::timeout};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn ping() {
+    crate::init();
+
+    let cfg = chmux::Cfg { connection_timeout: None, ..Default::default() };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, a_listener), (b_mux, b_client, b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    let a_stats = a_mux.stats();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    assert_eq!(a_stats.ping_rtt(), None);
+    for _ in 0..3 {
+        let rtt = a_client.ping().await.unwrap();
+        println!("A ping rtt: {rtt:?}");
+        assert!(rtt < Duration::from_secs(1));
+    }
+    assert!(a_stats.ping_rtt().is_some());
+
+    let rtt = b_client.ping().await.unwrap();
+    println!("B ping rtt: {rtt:?}");
+
+    println!("Terminating");
+    b_client.terminate();
+    drop((a_listener, b_listener, b_client));
+    let _ = a_run.await.unwrap();
+    let _ = b_run.await.unwrap();
+    assert!(matches!(a_client.ping().await, Err(chmux::PingError::ChMux)));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn ping_timeout() {
+    crate::init();
+
+    let cfg =
+        chmux::Cfg { connection_timeout: None, ping_timeout: Duration::from_millis(200), ..Default::default() };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, _b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    exec::spawn(a_mux.run());
+
+    println!("Pinging remote endpoint that does not run its multiplexer");
+    let res = timeout(Duration::from_secs(10), a_client.ping()).await.expect("ping did not time out");
+    println!("Ping result: {res:?}");
+    assert!(matches!(res, Err(chmux::PingError::Timeout)));
+
+    drop(b_mux);
+}
@@ -0,0 +1,178 @@
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt, channel::mpsc as transport_mpsc, future::try_join};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::watch;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use remoc::{
+    chmux, exec,
+    exec::time::{sleep, timeout},
+};
+
+/// Waits until the specified data has been transmitted and returns its frame index.
+async fn frame_position(frames: &Mutex<Vec<Bytes>>, data: &[u8]) -> usize {
+    loop {
+        if let Some(pos) = frames.lock().unwrap().iter().position(|frame| frame == data) {
+            return pos;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn high_priority_overtakes() {
+    crate::init();
+
+    // Transport from A to B is forwarded by a task that records all frames
+    // and can be paused.
+    let (a_tx, mut fw_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (mut fw_tx, b_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (b_tx, a_rx) = transport_mpsc::channel::<Bytes>(0);
+    let a_rx = a_rx.map(Ok::<_, std::io::Error>);
+    let b_rx = b_rx.map(Ok::<_, std::io::Error>);
+
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let (pause_tx, mut pause_rx) = watch::channel(false);
+    let fw_frames = frames.clone();
+    exec::spawn(async move {
+        loop {
+            let _ = pause_rx.wait_for(|paused| !paused).await;
+            let Some(frame) = fw_rx.next().await else { break };
+            fw_frames.lock().unwrap().push(frame.clone());
+            if fw_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let cfg = chmux::Cfg::default();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    exec::spawn(a_mux.run());
+    exec::spawn(b_mux.run());
+
+    println!("Connecting ports");
+    let connect_low = async {
+        let low = a_client.port_allocator().allocate().await;
+        a_client
+            .connect_ext(Some(chmux::PortReq::new(low).with_priority(chmux::Priority::Low)), true)
+            .await?
+            .await
+    };
+    let connect_high = async {
+        let high = a_client.port_allocator().allocate().await;
+        a_client
+            .connect_ext(Some(chmux::PortReq::new(high).with_priority(chmux::Priority::High)), true)
+            .await?
+            .await
+    };
+    let accept = async {
+        let (_, b_rx1) = b_listener.accept().await.unwrap().unwrap();
+        let (_, b_rx2) = b_listener.accept().await.unwrap().unwrap();
+        (b_rx1, b_rx2)
+    };
+    let ((mut low_tx, _low_rx), (mut high_tx, _high_rx), (_b_rx1, _b_rx2)) =
+        tokio::join!(async { connect_low.await.unwrap() }, async { connect_high.await.unwrap() }, accept);
+    assert_eq!(low_tx.priority(), chmux::Priority::Low);
+    assert_eq!(high_tx.priority(), chmux::Priority::High);
+
+    println!("Pausing transport and queueing data");
+    pause_tx.send(true).unwrap();
+    const N_LOW: usize = 32;
+    for i in 0..N_LOW {
+        low_tx.send(format!("low {i}").into()).await.unwrap();
+    }
+    high_tx.send("high".into()).await.unwrap();
+
+    println!("Resuming transport");
+    frames.lock().unwrap().clear();
+    pause_tx.send(false).unwrap();
+
+    let high_pos = frame_position(&frames, b"high").await;
+    let last_low_pos = frame_position(&frames, format!("low {}", N_LOW - 1).as_bytes()).await;
+
+    println!("high data at frame {high_pos}, last low data at frame {last_low_pos}");
+    assert!(high_pos < last_low_pos, "high priority data did not overtake low priority data");
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn control_not_blocked_by_low_priority() {
+    crate::init();
+
+    // Transport from A to B is forwarded by a task that can be paused.
+    let (a_tx, mut fw_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (mut fw_tx, b_rx) = transport_mpsc::channel::<Bytes>(0);
+    let (b_tx, a_rx) = transport_mpsc::channel::<Bytes>(0);
+    let a_rx = a_rx.map(Ok::<_, std::io::Error>);
+    let b_rx = b_rx.map(Ok::<_, std::io::Error>);
+
+    let (pause_tx, mut pause_rx) = watch::channel(false);
+    exec::spawn(async move {
+        loop {
+            let _ = pause_rx.wait_for(|paused| !paused).await;
+            let Some(frame) = fw_rx.next().await else { break };
+            if fw_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let cfg = chmux::Cfg { shared_send_queue: 4, transport_send_queue: 4, ..Default::default() };
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+    exec::spawn(a_mux.run());
+    exec::spawn(b_mux.run());
+
+    println!("Connecting low priority port");
+    let connect_low = async {
+        let low = a_client.port_allocator().allocate().await;
+        a_client
+            .connect_ext(Some(chmux::PortReq::new(low).with_priority(chmux::Priority::Low)), true)
+            .await?
+            .await
+    };
+    let ((mut low_tx, _low_rx), (_b_tx1, mut b_rx1)) =
+        tokio::join!(async { connect_low.await.unwrap() }, async { b_listener.accept().await.unwrap().unwrap() });
+
+    println!("Pausing transport and saturating low priority queue");
+    pause_tx.send(true).unwrap();
+    const N_LOW: usize = 64;
+    let low_sent = Arc::new(AtomicUsize::new(0));
+    let task_low_sent = low_sent.clone();
+    let low_task = exec::spawn(async move {
+        for i in 0..N_LOW {
+            low_tx.send(format!("low {i}").into()).await.unwrap();
+            task_low_sent.fetch_add(1, Ordering::SeqCst);
+        }
+        low_tx
+    });
+    sleep(Duration::from_millis(100)).await;
+    assert!(low_sent.load(Ordering::SeqCst) < N_LOW, "low priority queue is not saturated");
+
+    println!("Requesting connection while low priority queue is saturated");
+    let mut connect = a_client.connect_ext(None, true).await.unwrap();
+    timeout(Duration::from_secs(1), connect.sent()).await.expect("connect request blocked by low priority queue");
+
+    println!("Resuming transport");
+    pause_tx.send(false).unwrap();
+    let accept = async {
+        let (_b_tx2, _b_rx2) = b_listener.accept().await.unwrap().unwrap();
+        for i in 0..N_LOW {
+            let data = Bytes::from(b_rx1.recv().await.unwrap().unwrap());
+            assert_eq!(data, format!("low {i}").as_bytes());
+        }
+    };
+    let (res, ()) = tokio::join!(connect, accept);
+    res.unwrap();
+    low_task.await.unwrap();
+}
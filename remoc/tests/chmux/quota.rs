@@ -0,0 +1,110 @@
+use futures::{future::try_join, stream::StreamExt};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{chmux, exec};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn pending_port_requests_reject() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg::default();
+    let b_cfg = chmux::Cfg { max_pending_port_requests: Some(1), ..Default::default() };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _), (b_mux, _, mut b_listener)) =
+        try_join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(b_cfg, b_tx, b_rx)).await.unwrap();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    println!("Connecting twice without accepting");
+    let first = exec::spawn({
+        let a_client = a_client.clone();
+        async move { a_client.connect().await }
+    });
+    tokio::task::yield_now().await;
+    let second = a_client.connect().await;
+    assert!(matches!(second, Err(chmux::ConnectError::Rejected)), "second connect: {second:?}");
+
+    println!("Accepting first request");
+    let b_conn1 = b_listener.accept().await.unwrap().unwrap();
+    let a_conn1 = first.await.unwrap().unwrap();
+
+    println!("Connecting after pending request was accepted");
+    let (third, b_conn) = tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let a_conn2 = third.unwrap();
+
+    drop((a_conn1, a_conn2, b_conn1, b_conn));
+    drop(a_client);
+    drop(b_listener);
+    a_run.await.unwrap().unwrap();
+    b_run.await.unwrap().unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn receive_buffer_memory_terminate() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg::default();
+    let b_cfg = chmux::Cfg {
+        receive_buffer: 1024,
+        max_receive_buffer_memory: Some(1024),
+        quota_policy: chmux::QuotaPolicy::Terminate,
+        ..Default::default()
+    };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(b_cfg, b_tx, b_rx)).await.unwrap();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (_a_tx, _a_rx) = a_conn.unwrap();
+    let (_b_tx, _b_rx) = b_conn;
+
+    println!("Connecting beyond receive buffer memory quota");
+    assert!(a_client.connect().await.is_err());
+
+    let err = b_run.await.unwrap().unwrap_err();
+    println!("B error: {err}");
+    assert!(matches!(err, chmux::ChMuxError::QuotaExceeded(chmux::Quota::ReceiveBufferMemory)));
+    let _ = a_run.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn pending_port_requests_reject_port_data() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg::default();
+    let b_cfg = chmux::Cfg { max_pending_port_requests: Some(1), ..Default::default() };
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(b_cfg, b_tx, b_rx)).await.unwrap();
+    let _a_run = exec::spawn(a_mux.run());
+    let _b_run = exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (mut a_tx, _a_rx) = a_conn.unwrap();
+    let (_b_tx, mut b_rx) = b_conn;
+
+    println!("Sending two ports over established port");
+    let allocator = a_tx.port_allocator();
+    let ports =
+        vec![chmux::PortReq::new(allocator.allocate().await), chmux::PortReq::new(allocator.allocate().await)];
+    let connects = a_tx.connect(ports, false).await.unwrap();
+
+    let res = b_rx.recv_any().await;
+    println!("B receive: {res:?}");
+    assert!(matches!(res, Err(chmux::RecvError::QuotaExceeded(chmux::Quota::PendingPortRequests))));
+    assert!(res.unwrap_err().to_string().contains("pending port requests"));
+
+    for connect in connects {
+        assert!(matches!(connect.await, Err(chmux::ConnectError::Rejected)));
+    }
+}
@@ -0,0 +1,329 @@
+use bytes::Bytes;
+use futures::{
+    channel::mpsc as transport_mpsc,
+    future::{AbortHandle, Abortable, try_join},
+    stream::StreamExt,
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::{Mutex, mpsc};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use remoc::{chmux, exec, exec::time::sleep};
+
+type TransportSink = transport_mpsc::Sender<Bytes>;
+type TransportStream =
+    Abortable<futures::stream::Map<transport_mpsc::Receiver<Bytes>, fn(Bytes) -> Result<Bytes, std::io::Error>>>;
+type Transport = (TransportSink, TransportStream);
+
+/// Creates a loopback transport that breaks when the returned handle is aborted.
+fn transport() -> (Transport, Transport, AbortHandle) {
+    let (a_tx, b_rx) = transport_mpsc::channel(0);
+    let (b_tx, a_rx) = transport_mpsc::channel(0);
+
+    let (abort, a_reg) = AbortHandle::new_pair();
+    let (_, b_reg) = AbortHandle::new_pair();
+    let a_rx = Abortable::new(a_rx.map(Ok as fn(Bytes) -> Result<Bytes, std::io::Error>), a_reg);
+    let b_rx = Abortable::new(b_rx.map(Ok as fn(Bytes) -> Result<Bytes, std::io::Error>), b_reg);
+
+    ((a_tx, a_rx), (b_tx, b_rx), abort)
+}
+
+/// Returns a reconnect function that takes new transports from a channel.
+fn reconnector(
+    rx: mpsc::UnboundedReceiver<Transport>,
+) -> impl FnMut() -> futures::future::BoxFuture<'static, Option<Transport>> {
+    let rx = Arc::new(Mutex::new(rx));
+    move || {
+        let rx = rx.clone();
+        Box::pin(async move { rx.lock().await.recv().await })
+    }
+}
+
+fn cfg() -> chmux::Cfg {
+    chmux::Cfg {
+        connection_timeout: Some(Duration::from_secs(1)),
+        chunk_size: 16,
+        receive_buffer: 64,
+        session_resumption: true,
+        ..Default::default()
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resume() {
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), mut abort) = transport();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg(), a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+
+    let session_id = a_mux.session_id().expect("no session");
+    assert_eq!(b_mux.session_id(), Some(session_id));
+
+    let (a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    exec::spawn(a_mux.run_with_reconnect(reconnector(a_reconnect_rx)));
+    exec::spawn(b_mux.run_with_reconnect(reconnector(b_reconnect_rx)));
+
+    const N_MSG: usize = 2000;
+
+    let server = exec::spawn(async move {
+        let (_tx, mut rx) = b_listener.accept().await.unwrap().unwrap();
+        for i in 0..N_MSG {
+            let msg = rx.recv().await.unwrap().expect("channel closed");
+            assert_eq!(String::from_utf8(Vec::from(msg)).unwrap(), format!("message no {i}"));
+        }
+        println!("Server received all messages");
+    });
+
+    let (mut tx, _rx) = a_client.connect().await.unwrap();
+    let client = exec::spawn(async move {
+        for i in 0..N_MSG {
+            tx.send(format!("message no {i}").into()).await.unwrap();
+        }
+        println!("Client sent all messages");
+    });
+
+    for _ in 0..3 {
+        sleep(Duration::from_millis(10)).await;
+
+        println!("Breaking transport");
+        abort.abort();
+
+        let (a, b, new_abort) = transport();
+        a_reconnect_tx.send(a).unwrap();
+        b_reconnect_tx.send(b).unwrap();
+        abort = new_abort;
+    }
+
+    client.await.unwrap();
+    server.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resume_unavailable() {
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), abort) = transport();
+    let ((a_mux, _a_client, _a_listener), (b_mux, _b_client, _b_listener)) =
+        try_join(chmux::ChMux::new(cfg(), a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+
+    let (_a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    let a_run = exec::spawn(a_mux.run_with_reconnect(reconnector(a_reconnect_rx)));
+    let b_run = exec::spawn(b_mux.run_with_reconnect(reconnector(b_reconnect_rx)));
+
+    println!("Breaking transport without providing a new one to B");
+    abort.abort();
+    drop(b_reconnect_tx);
+
+    let res = b_run.await.unwrap();
+    println!("B mux result: {res:?}");
+    assert!(matches!(res, Err(chmux::ChMuxError::StreamClosed | chmux::ChMuxError::SinkError(_))));
+
+    // A will wait for a new transport until its reconnect function gives up.
+    drop(a_run);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resume_wrong_session() {
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), abort) = transport();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg(), a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+
+    let (a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    exec::spawn(a_mux.run_with_reconnect(reconnector(a_reconnect_rx)));
+    exec::spawn(b_mux.run_with_reconnect(reconnector(b_reconnect_rx)));
+
+    println!("Breaking transport and providing a transport to another session to A");
+    abort.abort();
+    let ((c_tx, c_rx), (d_tx, d_rx), _abort) = transport();
+    a_reconnect_tx.send((c_tx, c_rx)).unwrap();
+    let res = chmux::ChMux::new(cfg(), d_tx, d_rx).await;
+    assert!(matches!(res, Err(chmux::ChMuxError::SessionLost)));
+
+    println!("Providing new transport");
+    let (a, b, _abort) = transport();
+    a_reconnect_tx.send(a).unwrap();
+    b_reconnect_tx.send(b).unwrap();
+
+    let (a_port, b_port) = tokio::join!(a_client.connect(), b_listener.accept());
+    let (mut tx, _rx) = a_port.unwrap();
+    let (_tx, mut rx) = b_port.unwrap().unwrap();
+    tx.send("after".into()).await.unwrap();
+    let msg = rx.recv().await.unwrap().unwrap();
+    assert_eq!(String::from_utf8(Vec::from(msg)).unwrap(), "after");
+}
+
+/// Handshake that sends the names in order, one per handshake, and
+/// accepts the name sent by the remote endpoint as its identity.
+fn name_handshake(names: &'static [&'static str]) -> impl chmux::Handshake {
+    let count = Arc::new(AtomicUsize::new(0));
+    move |mut channel: chmux::HandshakeChannel| {
+        let name = names[count.fetch_add(1, Ordering::SeqCst).min(names.len() - 1)];
+        async move {
+            channel.send(name).await?;
+            let remote_name = channel.recv().await?;
+            Ok(chmux::PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resume_peer_identity_changed() {
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), abort) = transport();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) = try_join(
+        chmux::ChMux::new_with_handshake(cfg(), a_tx, a_rx, name_handshake(&["a"])),
+        chmux::ChMux::new_with_handshake(cfg(), b_tx, b_rx, name_handshake(&["b", "impostor", "b"])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(a_mux.peer_identity().map(|id| id.as_str()), Some("b"));
+
+    let (a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    let a_reconnect = reconnector(a_reconnect_rx);
+    let reconnects = Arc::new(AtomicUsize::new(0));
+    exec::spawn(a_mux.run_with_reconnect({
+        let reconnects = reconnects.clone();
+        let mut a_reconnect = a_reconnect;
+        move || {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            a_reconnect()
+        }
+    }));
+    exec::spawn(b_mux.run_with_reconnect(reconnector(b_reconnect_rx)));
+
+    println!("Breaking transport and providing a transport to an endpoint with another identity");
+    abort.abort();
+    let (a, b, _abort1) = transport();
+    a_reconnect_tx.send(a).unwrap();
+    b_reconnect_tx.send(b).unwrap();
+
+    println!("Providing new transport");
+    let (a, b, _abort2) = transport();
+    a_reconnect_tx.send(a).unwrap();
+    b_reconnect_tx.send(b).unwrap();
+
+    let (a_port, b_port) = tokio::join!(a_client.connect(), b_listener.accept());
+    let (mut tx, _rx) = a_port.unwrap();
+    let (_tx, mut rx) = b_port.unwrap().unwrap();
+    tx.send("after".into()).await.unwrap();
+    let msg = rx.recv().await.unwrap().unwrap();
+    assert_eq!(String::from_utf8(Vec::from(msg)).unwrap(), "after");
+    assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn ping_during_resume() {
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), abort) = transport();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, _b_listener)) =
+        try_join(chmux::ChMux::new(cfg(), a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+
+    let (a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    exec::spawn(a_mux.run_with_reconnect(reconnector(a_reconnect_rx)));
+    exec::spawn(b_mux.run_with_reconnect(reconnector(b_reconnect_rx)));
+
+    println!("Breaking transport and pinging");
+    abort.abort();
+    let ping = exec::spawn(async move { a_client.ping().await });
+    sleep(Duration::from_millis(100)).await;
+
+    println!("Providing new transport");
+    let (a, b, _abort) = transport();
+    a_reconnect_tx.send(a).unwrap();
+    b_reconnect_tx.send(b).unwrap();
+
+    let rtt = ping.await.unwrap().unwrap();
+    println!("Ping rtt: {rtt:?}");
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn session_buffer_exceeded() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg { session_buffer: 8, ..cfg() };
+    let ((a_tx, a_rx), (b_tx, b_rx), _abort) = transport();
+    let ((a_mux, a_client, _a_listener), (b_mux, _b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+
+    let a_run = exec::spawn(a_mux.run());
+    exec::spawn(b_mux.run());
+
+    exec::spawn(async move {
+        let (_tx, mut rx) = b_listener.accept().await.unwrap().unwrap();
+        while let Ok(Some(_)) = rx.recv().await {}
+    });
+
+    let (mut tx, _rx) = a_client.connect().await.unwrap();
+    exec::spawn(async move {
+        for i in 0..32 {
+            if tx.send(format!("message no {i}").into()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let res = a_run.await.unwrap();
+    println!("A mux result: {res:?}");
+    assert!(matches!(res, Err(chmux::ChMuxError::SessionLost)));
+}
+
+#[cfg(feature = "rch")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn connect_resume() {
+    use remoc::ConnectOptions;
+
+    crate::init();
+
+    let ((a_tx, a_rx), (b_tx, b_rx), abort) = transport();
+    let (a_reconnect_tx, a_reconnect_rx) = mpsc::unbounded_channel();
+    let (b_reconnect_tx, b_reconnect_rx) = mpsc::unbounded_channel();
+    let (a, b) = tokio::join!(
+        ConnectOptions::new(cfg())
+            .reconnect(reconnector(a_reconnect_rx))
+            .framed::<_, _, _, _, String, (), remoc::codec::Default>(a_tx, a_rx),
+        ConnectOptions::new(cfg())
+            .reconnect(reconnector(b_reconnect_rx))
+            .framed::<_, _, _, _, (), String, remoc::codec::Default>(b_tx, b_rx),
+    );
+    let (a_conn, mut a_base_tx, _a_base_rx) = a.unwrap();
+    let (b_conn, _b_base_tx, mut b_base_rx) = b.unwrap();
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    a_base_tx.send("before".to_string()).await.unwrap();
+    assert_eq!(b_base_rx.recv().await.unwrap(), Some("before".to_string()));
+
+    println!("Breaking transport");
+    abort.abort();
+    let (a, b, _abort) = transport();
+    a_reconnect_tx.send(a).unwrap();
+    b_reconnect_tx.send(b).unwrap();
+
+    a_base_tx.send("after".to_string()).await.unwrap();
+    assert_eq!(b_base_rx.recv().await.unwrap(), Some("after".to_string()));
+}
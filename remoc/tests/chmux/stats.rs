@@ -0,0 +1,79 @@
+use bytes::Buf;
+use futures::{future::try_join, stream::StreamExt};
+use std::time::Duration;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{chmux, exec, exec::time::sleep};
+
+fn cfg() -> chmux::Cfg {
+    chmux::Cfg {
+        connection_timeout: Some(Duration::from_millis(200)),
+        chunk_size: 4,
+        receive_buffer: 8,
+        ..Default::default()
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn stats() {
+    crate::init();
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, a_listener), (b_mux, b_client, mut b_listener)) =
+        try_join(chmux::ChMux::new(cfg(), a_tx, a_rx), chmux::ChMux::new(cfg(), b_tx, b_rx)).await.unwrap();
+    let a_stats = a_mux.stats();
+    let b_stats = b_mux.stats();
+    let a_run = exec::spawn(a_mux.run());
+    let b_run = exec::spawn(b_mux.run());
+
+    let (a_conn, b_conn) =
+        tokio::join!(a_client.connect(), async { b_listener.accept().await.unwrap().unwrap() });
+    let (mut a_tx, a_rx) = a_conn.unwrap();
+    let (b_tx, mut b_rx) = b_conn;
+    assert_eq!(a_stats.open_ports(), 1);
+    assert_eq!(b_stats.open_ports(), 1);
+
+    println!("Sending data exceeding receive buffer");
+    let data = vec![1; 20];
+    let sender = exec::spawn(async move {
+        a_tx.send(data.into()).await.unwrap();
+        a_tx
+    });
+    sleep(Duration::from_millis(100)).await;
+    let received = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(received.remaining(), 20);
+    let a_tx = sender.await.unwrap();
+
+    let a_ports = a_stats.ports();
+    println!("A ports: {a_ports:?}");
+    assert_eq!(a_ports.len(), 1);
+    assert_eq!(a_ports[0].local_port, a_tx.local_port());
+    assert_eq!(a_ports[0].remote_port, a_tx.remote_port());
+    assert_eq!(a_ports[0].bytes_sent, 20);
+    assert!(a_ports[0].credit_stalls > 0);
+    assert_eq!(a_stats.bytes_sent(), 20);
+    assert_eq!(a_stats.credit_stalls(), a_ports[0].credit_stalls);
+
+    let b_ports = b_stats.ports();
+    println!("B ports: {b_ports:?}");
+    assert_eq!(b_ports[0].bytes_received, 20);
+    assert_eq!(b_stats.bytes_received(), 20);
+
+    println!("Waiting for ping round trip");
+    sleep(Duration::from_millis(300)).await;
+    println!("A stats: {a_stats:?}");
+    assert!(a_stats.ping_rtt().is_some());
+    assert!(a_stats.age() >= Duration::from_millis(400));
+
+    println!("Closing ports");
+    drop((a_tx, a_rx, b_tx, b_rx, a_client, a_listener, b_client, b_listener));
+    a_run.await.unwrap().unwrap();
+    b_run.await.unwrap().unwrap();
+    assert_eq!(a_stats.open_ports(), 0);
+    assert_eq!(b_stats.open_ports(), 0);
+    assert_eq!(a_stats.bytes_sent(), 20);
+}
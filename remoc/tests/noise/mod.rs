@@ -0,0 +1,146 @@
+use futures::stream::StreamExt;
+use std::time::Duration;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    ConnectOptions,
+    chmux::{self, HandshakeChannel, PeerIdentity},
+    exec,
+    noise::{Keypair, Noise},
+    rch::base,
+};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn noise() {
+    crate::init();
+
+    let a_keypair = Keypair::generate();
+    let b_keypair = Keypair::generate();
+    let a_public = a_keypair.public().to_vec();
+    let b_public = b_keypair.public().to_vec();
+
+    // Large chunks make chmux frames exceed the maximum Noise message size.
+    let cfg = remoc::Cfg { chunk_size: 100_000, ..Default::default() };
+    let (a_io, b_io) = tokio::io::duplex(4096);
+    let (a_input, a_output) = tokio::io::split(a_io);
+    let (b_input, b_output) = tokio::io::split(b_io);
+
+    let (a, b) = tokio::join!(
+        remoc::Connect::io_with_noise(cfg.clone(), Noise::initiator(a_keypair), a_input, a_output),
+        remoc::Connect::io_with_noise(cfg, Noise::responder(b_keypair), b_input, b_output),
+    );
+    let (a_conn, mut a_tx, _a_rx): (_, base::Sender<Vec<u8>>, base::Receiver<()>) = a.unwrap();
+    let (b_conn, _b_tx, mut b_rx): (_, base::Sender<()>, base::Receiver<Vec<u8>>) = b.unwrap();
+
+    assert_eq!(a_conn.peer_info().public_key().unwrap(), b_public);
+    assert_eq!(b_conn.peer_info().public_key().unwrap(), a_public);
+
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    println!("Sending large message over Noise channel");
+    let data: Vec<u8> = (0..300_000).map(|i| i as u8).collect();
+    a_tx.send(data.clone()).await.unwrap();
+    assert_eq!(b_rx.recv().await.unwrap(), Some(data));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn noise_rejected() {
+    crate::init();
+
+    let a_keypair = Keypair::generate();
+    let b_keypair = Keypair::generate();
+    let trusted = Keypair::generate().public().to_vec();
+
+    loop_transport!(4, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = tokio::join!(
+        remoc::Connect::framed_with_noise::<_, _, (), (), remoc::codec::Default>(
+            Default::default(),
+            Noise::initiator(a_keypair).with_verifier(move |key| key == trusted),
+            a_tx,
+            a_rx
+        ),
+        remoc::Connect::framed_with_noise::<_, _, (), (), remoc::codec::Default>(
+            Default::default(),
+            Noise::responder(b_keypair),
+            b_tx,
+            b_rx
+        ),
+    );
+
+    let Err(err) = a else { panic!("initiator accepted untrusted responder") };
+    println!("Initiator error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(chmux::ChMuxError::Handshake(_))));
+    assert!(b.is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn noise_with_options() {
+    crate::init();
+
+    let handshake = |name: &'static str| {
+        move |mut channel: HandshakeChannel| async move {
+            channel.send(name).await?;
+            let remote_name = channel.recv().await?;
+            Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+        }
+    };
+
+    let a_keypair = Keypair::generate();
+    let b_keypair = Keypair::generate();
+    let b_public = b_keypair.public().to_vec();
+
+    loop_transport!(4, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = tokio::join!(
+        ConnectOptions::new(Default::default())
+            .noise(Noise::initiator(a_keypair))
+            .handshake(handshake("a"))
+            .hello(1u32)
+            .framed::<_, _, _, _, String, (), remoc::codec::Default>(a_tx, a_rx),
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("b"))
+            .hello(2u32)
+            .noise(Noise::responder(b_keypair))
+            .framed::<_, _, _, _, (), String, remoc::codec::Default>(b_tx, b_rx),
+    );
+    let (a_conn, mut a_tx, _a_rx, a_remote_hello) = a.unwrap();
+    let (b_conn, _b_tx, mut b_rx, b_remote_hello) = b.unwrap();
+
+    assert_eq!(a_conn.peer_identity().unwrap().as_str(), "b");
+    assert_eq!(b_conn.peer_identity().unwrap().as_str(), "a");
+    assert_eq!(a_remote_hello, 2);
+    assert_eq!(b_remote_hello, 1);
+    assert_eq!(a_conn.peer_info().public_key().unwrap(), b_public);
+
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    println!("Sending over Noise channel");
+    a_tx.send("Hello Noise".to_string()).await.unwrap();
+    assert_eq!(b_rx.recv().await.unwrap(), Some("Hello Noise".to_string()));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn noise_timeout() {
+    crate::init();
+
+    let cfg = remoc::Cfg { connection_timeout: Some(Duration::from_millis(500)), ..Default::default() };
+
+    // The remote endpoint never answers the Noise handshake.
+    loop_transport!(4, a_tx, a_rx, _b_tx, _b_rx);
+    let res = ConnectOptions::new(cfg)
+        .noise(Noise::initiator(Keypair::generate()))
+        .framed::<_, _, _, _, (), (), remoc::codec::Default>(a_tx, a_rx)
+        .await;
+
+    let Err(err) = res else { panic!("Noise handshake without remote endpoint succeeded") };
+    println!("Error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(chmux::ChMuxError::Timeout)));
+}
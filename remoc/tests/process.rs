@@ -0,0 +1,157 @@
+//! Child process tests.
+//!
+//! This test does not use the default test harness, since the test binary is spawned
+//! again as the child process and the harness would write to standard output.
+
+use std::{env, process::ExitCode};
+use tokio::process::Command;
+
+use remoc::{
+    ConnectOptions,
+    chmux::{HandshakeChannel, PeerIdentity},
+    rch::base,
+};
+
+/// Environment variable selecting the behavior of the child process.
+const CHILD_ENV: &str = "REMOC_TEST_CHILD";
+
+/// Child that doubles all received numbers.
+async fn child_double() {
+    let (conn, mut tx, mut rx): (_, base::Sender<u32>, base::Receiver<u32>) =
+        remoc::Connect::stdio(Default::default()).await.unwrap();
+    let conn = tokio::spawn(conn);
+
+    while let Some(value) = rx.recv().await.unwrap() {
+        tx.send(2 * value).await.unwrap();
+    }
+
+    drop((tx, rx));
+    conn.await.unwrap().unwrap();
+}
+
+/// Child that never terminates on its own.
+async fn child_hang() {
+    let (conn, _tx, _rx): (_, base::Sender<()>, base::Receiver<()>) =
+        remoc::Connect::stdio(Default::default()).await.unwrap();
+    let _ = conn.await;
+    std::future::pending::<()>().await;
+}
+
+/// Handshake that exchanges the names of the endpoints.
+fn name_handshake(name: &'static str) -> impl remoc::chmux::Handshake {
+    move |mut channel: HandshakeChannel| async move {
+        channel.send(name).await?;
+        let remote_name = channel.recv().await?;
+        Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+    }
+}
+
+/// Child that authenticates itself, exchanges a hello and echoes its peer identity.
+async fn child_hello() {
+    let (conn, mut tx, rx, remote_hello): (_, base::Sender<String>, base::Receiver<()>, u32) =
+        ConnectOptions::new(Default::default())
+            .handshake(name_handshake("child"))
+            .hello(2u32)
+            .stdio()
+            .await
+            .unwrap();
+    let identity = conn.peer_identity().unwrap().as_str().to_string();
+    let conn = tokio::spawn(conn);
+
+    tx.send(format!("{identity} {remote_hello}")).await.unwrap();
+
+    drop((tx, rx));
+    conn.await.unwrap().unwrap();
+}
+
+fn command(behavior: &str) -> Command {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command.env(CHILD_ENV, behavior);
+    command
+}
+
+async fn child_process() {
+    println!("child_process");
+
+    let (conn, mut tx, mut rx, child): (_, base::Sender<u32>, base::Receiver<u32>, _) =
+        remoc::Connect::child_process(Default::default(), command("double")).await.unwrap();
+    assert!(child.id().is_some());
+    let conn = tokio::spawn(conn);
+
+    for value in 0..100 {
+        tx.send(value).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), Some(2 * value));
+    }
+
+    drop((tx, rx));
+    conn.await.unwrap().unwrap();
+    let status = child.wait().await.unwrap();
+    assert!(status.success(), "child failed: {status}");
+}
+
+async fn child_process_with_options() {
+    println!("child_process_with_options");
+
+    let (conn, tx, mut rx, child, remote_hello): (_, base::Sender<()>, base::Receiver<String>, _, u32) =
+        ConnectOptions::new(Default::default())
+            .handshake(name_handshake("parent"))
+            .hello(1u32)
+            .child_process(command("hello"))
+            .await
+            .unwrap();
+    assert_eq!(conn.peer_identity().unwrap().as_str(), "child");
+    assert_eq!(remote_hello, 2);
+    let conn = tokio::spawn(conn);
+
+    assert_eq!(rx.recv().await.unwrap(), Some("parent 1".to_string()));
+    assert_eq!(rx.recv().await.unwrap(), None);
+
+    drop((tx, rx));
+    conn.await.unwrap().unwrap();
+    let status = child.wait().await.unwrap();
+    assert!(status.success(), "child failed: {status}");
+}
+
+async fn child_process_kill() {
+    println!("child_process_kill");
+
+    let (conn, tx, rx, mut child): (_, base::Sender<()>, base::Receiver<()>, _) =
+        remoc::Connect::child_process(Default::default(), command("hang")).await.unwrap();
+    let conn = tokio::spawn(conn);
+
+    child.kill();
+    assert!(conn.await.unwrap().is_err());
+    let status = child.wait().await.unwrap();
+    assert!(!status.success(), "killed child succeeded");
+    drop((tx, rx));
+}
+
+async fn child_process_spawn_failure() {
+    println!("child_process_spawn_failure");
+
+    let res = remoc::Connect::child_process::<(), (), remoc::codec::Default>(
+        Default::default(),
+        Command::new("/nonexistent/remoc-test-child"),
+    )
+    .await;
+    assert!(matches!(res, Err(remoc::ConnectError::ChMux(remoc::chmux::ChMuxError::StreamError(_)))));
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match env::var(CHILD_ENV).as_deref() {
+        Ok("double") => child_double().await,
+        Ok("hang") => child_hang().await,
+        Ok("hello") => child_hello().await,
+        Ok(other) => panic!("unknown child behavior {other}"),
+        Err(_) => {
+            child_process().await;
+            child_process_with_options().await;
+            child_process_kill().await;
+            child_process_spawn_failure().await;
+            println!("all child process tests passed");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
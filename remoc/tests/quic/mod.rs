@@ -0,0 +1,161 @@
+use futures::future;
+use rcgen::CertifiedKey;
+use std::{net::Ipv4Addr, sync::Arc};
+
+use remoc::{
+    ConnectOptions,
+    chmux::{HandshakeChannel, PeerIdentity},
+    quinn::{
+        ClientConfig, Connection, Endpoint, ServerConfig,
+        rustls::{RootCertStore, pki_types::PrivatePkcs8KeyDer},
+    },
+    rch::{base, mpsc},
+};
+
+/// Establishes a QUIC connection over the loopback interface.
+async fn quic_pair() -> (Connection, Connection) {
+    let CertifiedKey { cert, signing_key } =
+        rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let key = PrivatePkcs8KeyDer::from(signing_key.serialize_der()).into();
+    let server_cfg = ServerConfig::with_single_cert(vec![cert.der().clone()], key).unwrap();
+    let server = Endpoint::server(server_cfg, (Ipv4Addr::LOCALHOST, 0).into()).unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(cert.der().clone()).unwrap();
+    let mut client = Endpoint::client((Ipv4Addr::LOCALHOST, 0).into()).unwrap();
+    client.set_default_client_config(ClientConfig::with_root_certificates(Arc::new(roots)).unwrap());
+
+    let server_addr = server.local_addr().unwrap();
+    let (client_conn, server_conn) =
+        tokio::join!(async { client.connect(server_addr, "localhost").unwrap().await.unwrap() }, async {
+            server.accept().await.unwrap().await.unwrap()
+        },);
+    (client_conn, server_conn)
+}
+
+#[tokio::test]
+async fn quic() {
+    crate::init();
+
+    let (client_quic, server_quic) = quic_pair().await;
+    let client_addr = server_quic.remote_address();
+    let (client, server) = tokio::join!(
+        remoc::Connect::quic(Default::default(), client_quic),
+        remoc::Connect::quic(Default::default(), server_quic),
+    );
+    let (client_conn, mut client_tx, mut client_rx): (_, base::Sender<String>, base::Receiver<u32>) =
+        client.unwrap();
+    let (server_conn, mut server_tx, mut server_rx): (_, base::Sender<u32>, base::Receiver<String>) =
+        server.unwrap();
+
+    assert_eq!(server_conn.peer_info().addr(), Some(client_addr));
+
+    let client_task = tokio::spawn(client_conn);
+    let server_task = tokio::spawn(server_conn);
+
+    println!("Sending over QUIC");
+    client_tx.send("Hello QUIC".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello QUIC".to_string()));
+
+    server_tx.send(123).await.unwrap();
+    assert_eq!(client_rx.recv().await.unwrap(), Some(123));
+
+    println!("Closing connection");
+    drop((client_tx, client_rx, server_tx, server_rx));
+    client_task.await.unwrap().unwrap();
+    server_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn quic_many_channels() {
+    crate::init();
+
+    let (client_quic, server_quic) = quic_pair().await;
+    let (client, server) = tokio::join!(
+        remoc::Connect::quic(Default::default(), client_quic),
+        remoc::Connect::quic(Default::default(), server_quic),
+    );
+    let (client_conn, mut client_tx, client_rx): (_, base::Sender<mpsc::Receiver<Vec<u8>>>, base::Receiver<()>) =
+        client.unwrap();
+    let (server_conn, server_tx, mut server_rx): (_, base::Sender<()>, base::Receiver<mpsc::Receiver<Vec<u8>>>) =
+        server.unwrap();
+
+    let client_task = tokio::spawn(client_conn);
+    let server_task = tokio::spawn(server_conn);
+
+    println!("Sending data over many channels");
+    let mut senders = Vec::new();
+    let mut receivers = Vec::new();
+    for _ in 0..32 {
+        let (tx, rx) = mpsc::channel(16);
+        client_tx.send(rx).await.unwrap();
+        senders.push(tx);
+        receivers.push(server_rx.recv().await.unwrap().unwrap());
+    }
+
+    let send = future::join_all(senders.into_iter().enumerate().map(|(i, tx)| async move {
+        for j in 0..16 {
+            tx.send(vec![i as u8; 1000 * j]).await.unwrap();
+        }
+    }));
+    let recv = future::join_all(receivers.into_iter().enumerate().map(|(i, mut rx)| async move {
+        for j in 0..16 {
+            assert_eq!(rx.recv().await.unwrap(), Some(vec![i as u8; 1000 * j]));
+        }
+        assert_eq!(rx.recv().await.unwrap(), None);
+    }));
+    tokio::join!(send, recv);
+
+    println!("Closing connection");
+    drop((client_tx, client_rx, server_tx, server_rx));
+    client_task.await.unwrap().unwrap();
+    server_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn quic_with_options() {
+    crate::init();
+
+    let handshake = |name: &'static str| {
+        move |mut channel: HandshakeChannel| async move {
+            channel.send(name).await?;
+            let remote_name = channel.recv().await?;
+            Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+        }
+    };
+
+    let (client_quic, server_quic) = quic_pair().await;
+    let (client, server) = tokio::join!(
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("client"))
+            .hello(1u32)
+            .quic::<String, (), remoc::codec::Default>(client_quic),
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("server"))
+            .hello(2u32)
+            .quic::<(), String, remoc::codec::Default>(server_quic),
+    );
+    let (client_conn, mut client_tx, _client_rx, client_remote_hello) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx, server_remote_hello) = server.unwrap();
+
+    assert_eq!(client_conn.peer_identity().unwrap().as_str(), "server");
+    assert_eq!(server_conn.peer_identity().unwrap().as_str(), "client");
+    assert_eq!(client_remote_hello, 2);
+    assert_eq!(server_remote_hello, 1);
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    client_tx.send("Hello QUIC".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello QUIC".to_string()));
+}
+
+#[tokio::test]
+#[should_panic(expected = "session resumption is not supported over QUIC")]
+async fn quic_session_resumption() {
+    crate::init();
+
+    let cfg = remoc::Cfg { session_resumption: true, ..Default::default() };
+    let (client_quic, _server_quic) = quic_pair().await;
+    let _ = ConnectOptions::new(cfg).quic::<(), (), remoc::codec::Default>(client_quic).await;
+}
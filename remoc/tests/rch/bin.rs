@@ -4,8 +4,12 @@ use rand::{Rng, RngExt};
 #[cfg(feature = "js")]
 use wasm_bindgen_test::wasm_bindgen_test;
 
-use crate::loop_channel;
-use remoc::{chmux::Received, exec, rch::bin};
+use crate::{loop_channel, loop_channel_with_cfg};
+use remoc::{
+    chmux::{self, Priority, Received},
+    exec,
+    rch::bin,
+};
 
 #[cfg_attr(not(feature = "js"), tokio::test)]
 #[cfg_attr(feature = "js", wasm_bindgen_test)]
@@ -303,3 +307,35 @@ async fn double_forward() {
 
     reply_task.await.unwrap();
 }
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn kind_priority() {
+    crate::init();
+    let mut cfg = chmux::Cfg::default();
+    cfg.channel_priorities.bin = Some(Priority::Low);
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel_with_cfg::<(bin::Sender, bin::Receiver)>(cfg).await;
+
+    println!("Sending remote bin channel sender and receiver");
+    let (tx1, rx1) = bin::channel();
+    let (tx2, rx2) = bin::channel();
+    a_tx.send((tx1, rx2)).await.unwrap();
+    let (tx1, rx2) = b_rx.recv().await.unwrap().unwrap();
+
+    let (tx1, rx1, tx2, rx2) =
+        tokio::join!(tx1.into_inner(), rx1.into_inner(), tx2.into_inner(), rx2.into_inner());
+    assert_eq!(tx1.unwrap().priority(), Priority::Low);
+    assert_eq!(rx1.unwrap().priority(), Priority::Low);
+    assert_eq!(tx2.unwrap().priority(), Priority::Low);
+    assert_eq!(rx2.unwrap().priority(), Priority::Low);
+
+    println!("Sending remote bin channel over connection with default configuration");
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<bin::Sender>().await;
+    let (tx, rx) = bin::channel();
+    a_tx.send(tx).await.unwrap();
+    let tx = b_rx.recv().await.unwrap().unwrap();
+
+    let (tx, rx) = tokio::join!(tx.into_inner(), rx.into_inner());
+    assert_eq!(tx.unwrap().priority(), Priority::Normal);
+    assert_eq!(rx.unwrap().priority(), Priority::Normal);
+}
@@ -0,0 +1,150 @@
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    ConnectError, HelloError,
+    chmux::{HandshakeChannel, PeerIdentity},
+    exec,
+    rch::mpsc,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Hello {
+    version: u32,
+    features: Vec<String>,
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn hello() {
+    crate::init();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let a_hello = Hello { version: 2, features: vec!["compress".to_string(), "stream".to_string()] };
+    let b_hello = Hello { version: 1, features: vec!["stream".to_string()] };
+
+    let (a, b) = tokio::join!(
+        remoc::ConnectOptions::new(Default::default())
+            .hello(a_hello.clone())
+            .framed::<_, _, _, _, u32, String, remoc::codec::Default>(a_tx, a_rx),
+        remoc::ConnectOptions::new(Default::default())
+            .hello(b_hello.clone())
+            .framed::<_, _, _, _, String, u32, remoc::codec::Default>(b_tx, b_rx),
+    );
+    let (a_conn, mut a_base_tx, mut a_base_rx, a_remote_hello) = a.unwrap();
+    let (b_conn, mut b_base_tx, mut b_base_rx, b_remote_hello) = b.unwrap();
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    println!("A received hello: {a_remote_hello:?}");
+    println!("B received hello: {b_remote_hello:?}");
+    assert_eq!(a_remote_hello, b_hello);
+    assert_eq!(b_remote_hello, a_hello);
+
+    println!("Using base channel");
+    a_base_tx.send(123).await.unwrap();
+    assert_eq!(b_base_rx.recv().await.unwrap(), Some(123));
+    b_base_tx.send("reply".to_string()).await.unwrap();
+    assert_eq!(a_base_rx.recv().await.unwrap(), Some("reply".to_string()));
+}
+
+fn name_handshake(name: &'static str) -> impl remoc::chmux::Handshake {
+    move |mut channel: HandshakeChannel| async move {
+        channel.send(name).await?;
+        let remote_name = channel.recv().await?;
+        Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn hello_with_handshake() {
+    crate::init();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let (a, b) = tokio::join!(
+        remoc::ConnectOptions::new(Default::default())
+            .handshake(name_handshake("a"))
+            .hello(1u32)
+            .framed::<_, _, _, _, u32, u32, remoc::codec::Default>(a_tx, a_rx),
+        remoc::ConnectOptions::new(Default::default())
+            .hello(2u32)
+            .handshake(name_handshake("b"))
+            .framed::<_, _, _, _, u32, u32, remoc::codec::Default>(b_tx, b_rx),
+    );
+    let (a_conn, mut a_base_tx, _a_base_rx, a_remote_hello) = a.unwrap();
+    let (b_conn, _b_base_tx, mut b_base_rx, b_remote_hello) = b.unwrap();
+    assert_eq!(a_conn.peer_identity().unwrap().as_str(), "b");
+    assert_eq!(b_conn.peer_identity().unwrap().as_str(), "a");
+    assert_eq!(a_remote_hello, 2);
+    assert_eq!(b_remote_hello, 1);
+    exec::spawn(a_conn);
+    exec::spawn(b_conn);
+
+    println!("Using base channel");
+    a_base_tx.send(123).await.unwrap();
+    assert_eq!(b_base_rx.recv().await.unwrap(), Some(123));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn hello_with_channel() {
+    crate::init();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let (events_tx, mut events_rx) = mpsc::channel::<String, remoc::codec::Default>(1);
+
+    // Each connection must be spawned as soon as it is established, since receiving
+    // the channel contained in the hello requires the dispatcher of the remote endpoint.
+    let (a_remote_hello, b_remote_hello) = tokio::join!(
+        async {
+            let (conn, _, _, remote_hello) = remoc::ConnectOptions::new(Default::default())
+                .hello(Some(events_tx))
+                .framed::<_, _, _, _, (), (), remoc::codec::Default>(a_tx, a_rx)
+                .await
+                .unwrap();
+            exec::spawn(conn);
+            remote_hello
+        },
+        async {
+            let (conn, _, _, remote_hello) = remoc::ConnectOptions::new(Default::default())
+                .hello(None::<mpsc::Sender<String>>)
+                .framed::<_, _, _, _, (), (), remoc::codec::Default>(b_tx, b_rx)
+                .await
+                .unwrap();
+            exec::spawn(conn);
+            remote_hello
+        },
+    );
+    assert!(a_remote_hello.is_none());
+
+    println!("Sending over channel received in hello");
+    let events_tx = b_remote_hello.unwrap();
+    events_tx.send("event".to_string()).await.unwrap();
+    assert_eq!(events_rx.recv().await.unwrap(), Some("event".to_string()));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn hello_mismatch() {
+    crate::init();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let (a, b) = tokio::join!(
+        remoc::ConnectOptions::new(Default::default())
+            .hello(Hello { version: 1, features: Vec::new() })
+            .framed::<_, _, _, _, (), (), remoc::codec::Default>(a_tx, a_rx),
+        remoc::ConnectOptions::new(Default::default())
+            .hello("hello".to_string())
+            .framed::<_, _, _, _, (), (), remoc::codec::Default>(b_tx, b_rx),
+    );
+
+    let Err(a_err) = a else { panic!("connection with mismatched hello succeeded") };
+    println!("A error: {a_err}");
+    assert!(matches!(a_err, ConnectError::Hello(HelloError::Recv(_))));
+    drop(b);
+}
@@ -1,5 +1,6 @@
 mod bin;
 mod broadcast;
+mod hello;
 mod io;
 mod lr;
 mod mpsc;
@@ -0,0 +1,151 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::{
+    rch::mpsc,
+    registry::{Registry, RegistryClient, RegistryError, RegistryEvent},
+    rfn::{CallError, RFn},
+};
+
+type Negate = RFn<(i16,), Result<i16, CallError>>;
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn lookup() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RegistryClient>().await;
+
+    let registry = Registry::new();
+    let negate: Negate = RFn::new_1(|arg: i16| async move { Ok(-arg) });
+    assert!(!registry.register("negate", negate));
+    assert!(!registry.register("numbers", vec![1u8, 2, 3]));
+
+    println!("Sending registry client");
+    a_tx.send(registry.client()).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    let mut names = client.names().await.unwrap();
+    names.sort();
+    assert_eq!(names, vec!["negate", "numbers"]);
+
+    println!("Looking up remote function");
+    let negate: Negate = client.lookup("negate").await.unwrap();
+    assert_eq!(negate.call(123).await.unwrap(), -123);
+
+    let numbers: Vec<u8> = client.lookup("numbers").await.unwrap();
+    assert_eq!(numbers, vec![1, 2, 3]);
+
+    match client.lookup::<Vec<u8>>("missing").await {
+        Err(RegistryError::NotFound(name)) => assert_eq!(name, "missing"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    match client.lookup::<String>("numbers").await {
+        Err(RegistryError::TypeMismatch { name, registered, requested }) => {
+            assert_eq!(name, "numbers");
+            assert_eq!(registered, std::any::type_name::<Vec<u8>>());
+            assert_eq!(requested, std::any::type_name::<String>());
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    println!("Unregistering");
+    assert!(registry.unregister("numbers"));
+    assert!(!registry.unregister("numbers"));
+    assert!(matches!(client.lookup::<Vec<u8>>("numbers").await, Err(RegistryError::NotFound(_))));
+
+    println!("Dropping registry");
+    drop(registry);
+    assert!(client.lookup::<Negate>("negate").await.is_err());
+    assert_eq!(negate.call(5).await.unwrap(), -5);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn watch() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RegistryClient>().await;
+
+    let registry = Registry::new();
+    registry.register("first", 1u32);
+
+    a_tx.send(registry.client()).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Watching registry");
+    let mut events = client.watch().await.unwrap();
+    let tag = "u32".to_string();
+    assert_eq!(
+        events.recv().await.unwrap(),
+        Some(RegistryEvent::Added { name: "first".to_string(), tag: tag.clone() })
+    );
+
+    let waiter = client.clone();
+    let wait = remoc::exec::spawn(async move { waiter.wait::<mpsc::Sender<String>>("channel").await });
+
+    registry.register("second", 2u32);
+    assert_eq!(
+        events.recv().await.unwrap(),
+        Some(RegistryEvent::Added { name: "second".to_string(), tag: tag.clone() })
+    );
+
+    println!("Replacing service");
+    assert!(registry.register("second", 3u32));
+    assert_eq!(events.recv().await.unwrap(), Some(RegistryEvent::Removed { name: "second".to_string() }));
+    assert_eq!(
+        events.recv().await.unwrap(),
+        Some(RegistryEvent::Added { name: "second".to_string(), tag: tag.clone() })
+    );
+
+    registry.unregister("first");
+    assert_eq!(events.recv().await.unwrap(), Some(RegistryEvent::Removed { name: "first".to_string() }));
+
+    println!("Registering channel");
+    let (tx, mut rx) = mpsc::channel::<String, remoc::codec::Default>(1);
+    registry.register("channel", tx);
+    assert!(matches!(events.recv().await.unwrap(), Some(RegistryEvent::Added { name, .. }) if name == "channel"));
+
+    let tx = wait.await.unwrap().unwrap();
+    tx.send("hello".to_string()).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some("hello".to_string()));
+
+    println!("Dropping registry");
+    drop(registry);
+    assert_eq!(events.recv().await.unwrap(), None);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn watch_coalesced() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RegistryClient>().await;
+
+    let registry = Registry::new();
+    a_tx.send(registry.client()).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Watching registry");
+    let mut events = client.watch().await.unwrap();
+
+    println!("Changing registry many times without receiving events");
+    for i in 0..1000u32 {
+        registry.register(format!("service{}", i % 10), i);
+        registry.unregister(&format!("service{}", (i + 5) % 10));
+    }
+    let mut names = registry.names();
+    names.sort();
+
+    println!("Receiving events");
+    let mut registered = std::collections::BTreeSet::new();
+    let mut received = 0;
+    while registered.iter().cloned().collect::<Vec<String>>() != names {
+        match events.recv().await.unwrap().unwrap() {
+            RegistryEvent::Added { name, .. } => assert!(registered.insert(name)),
+            RegistryEvent::Removed { name } => assert!(registered.remove(&name)),
+        }
+        received += 1;
+    }
+    println!("Received {received} events");
+    assert!(received < 2000);
+}
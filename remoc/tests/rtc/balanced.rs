@@ -0,0 +1,160 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use remoc::{
+    prelude::*,
+    rtc::{BalancePolicy, Balanced, CallError, Client},
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Worker {
+    async fn id(&self) -> Result<u32, CallError>;
+
+    async fn block(&self) -> Result<(), CallError>;
+}
+
+pub struct WorkerObj {
+    id: u32,
+    gate: Arc<Semaphore>,
+}
+
+impl WorkerObj {
+    fn new(id: u32) -> Self {
+        Self { id, gate: Arc::new(Semaphore::new(0)) }
+    }
+}
+
+impl Worker for WorkerObj {
+    async fn id(&self) -> Result<u32, CallError> {
+        Ok(self.id)
+    }
+
+    async fn block(&self) -> Result<(), CallError> {
+        self.gate.acquire().await.unwrap().forget();
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn balanced() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<WorkerClient>().await;
+
+    println!("Spawning workers");
+    let mut stop_txs = Vec::new();
+    for id in 0..3 {
+        let (server, client) =
+            WorkerServerShared::<_, remoc::codec::Default>::new(Arc::new(WorkerObj::new(id)), 1);
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        remoc::exec::spawn(async move {
+            tokio::select! {
+                res = server.serve(true) => res.unwrap(),
+                _ = stop_rx => (),
+            }
+        });
+        stop_txs.push(stop_tx);
+        a_tx.send(client).await.unwrap();
+    }
+
+    println!("Creating round robin pool");
+    let pool = Balanced::new(BalancePolicy::RoundRobin);
+    for _ in 0..3 {
+        pool.push(b_rx.recv().await.unwrap().unwrap());
+    }
+    assert_eq!(pool.len(), 3);
+
+    let mut ids = Vec::new();
+    for _ in 0..6 {
+        ids.push(pool.get().unwrap().id().await.unwrap());
+    }
+    assert_eq!(ids, vec![0, 1, 2, 0, 1, 2]);
+
+    println!("Stopping worker 1");
+    let clients = pool.clients();
+    stop_txs.remove(1);
+    clients[1].closed().await;
+
+    let mut ids = Vec::new();
+    for _ in 0..4 {
+        ids.push(pool.get().unwrap().id().await.unwrap());
+    }
+    ids.sort();
+    assert_eq!(ids, vec![0, 0, 2, 2]);
+    assert_eq!(pool.len(), 2);
+
+    println!("Creating least loaded pool");
+    let least: Balanced<_> = Balanced::new(BalancePolicy::LeastLoaded);
+    for client in pool.clients() {
+        least.push(client);
+    }
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        ids.push(least.get().unwrap().id().await.unwrap());
+    }
+    ids.sort();
+    assert_eq!(ids, vec![0, 2]);
+
+    println!("Stopping all workers");
+    stop_txs.clear();
+    for client in &clients {
+        client.closed().await;
+    }
+    assert!(pool.get().is_none());
+    assert!(pool.is_empty());
+    assert!(least.get().is_none());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn least_loaded() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<WorkerClient>().await;
+
+    println!("Spawning workers");
+    let mut gates = Vec::new();
+    for id in 0..2 {
+        let obj = WorkerObj::new(id);
+        gates.push(obj.gate.clone());
+        let (server, client) = WorkerServerShared::<_, remoc::codec::Default>::new(Arc::new(obj), 1);
+        remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+        a_tx.send(client).await.unwrap();
+    }
+
+    println!("Creating least loaded pool");
+    let pool = Balanced::new(BalancePolicy::LeastLoaded);
+    for _ in 0..2 {
+        pool.push(b_rx.recv().await.unwrap().unwrap());
+    }
+
+    println!("Making outstanding call on worker 0");
+    let busy = pool.get().unwrap();
+    assert_eq!(busy.id().await.unwrap(), 0);
+    let blocked = remoc::exec::spawn(async move { busy.block().await });
+
+    let mut ids = Vec::new();
+    for _ in 0..4 {
+        ids.push(pool.get().unwrap().id().await.unwrap());
+    }
+    assert_eq!(ids, vec![1, 1, 1, 1]);
+
+    println!("Completing outstanding call");
+    gates[0].add_permits(1);
+    blocked.await.unwrap().unwrap();
+
+    let mut ids = Vec::new();
+    for _ in 0..4 {
+        ids.push(pool.get().unwrap().id().await.unwrap());
+    }
+    ids.sort();
+    assert_eq!(ids, vec![0, 0, 1, 1]);
+}
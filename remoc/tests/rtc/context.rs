@@ -0,0 +1,98 @@
+use futures::{future::join, stream::StreamExt};
+use std::net::SocketAddr;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    chmux::{HandshakeChannel, PeerIdentity},
+    prelude::*,
+    rtc::{CallContext, CallError},
+};
+
+#[rtc::remote]
+pub trait Audit {
+    async fn caller(&self) -> Result<Option<String>, CallError>;
+    async fn tenant(&self) -> Result<Option<String>, CallError>;
+    async fn addr(&self) -> Result<Option<SocketAddr>, CallError>;
+}
+
+pub struct AuditObj;
+
+impl Audit for AuditObj {
+    async fn caller(&self) -> Result<Option<String>, CallError> {
+        let ctx = CallContext::current().unwrap();
+        Ok(ctx.peer_identity().map(|id| id.to_string()))
+    }
+
+    async fn tenant(&self) -> Result<Option<String>, CallError> {
+        let ctx = CallContext::current().unwrap();
+        Ok(ctx.peer_info().and_then(|peer_info| peer_info.metadata("tenant")))
+    }
+
+    async fn addr(&self) -> Result<Option<SocketAddr>, CallError> {
+        let ctx = CallContext::current().unwrap();
+        Ok(ctx.peer_info().and_then(|peer_info| peer_info.addr()))
+    }
+}
+
+fn name_handshake(name: &'static str) -> impl remoc::chmux::Handshake {
+    move |mut channel: HandshakeChannel| async move {
+        channel.send(name).await?;
+        let remote_name = channel.recv().await?;
+        Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn context() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+
+    let cfg = remoc::Cfg::default();
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        remoc::ConnectOptions::new(cfg.clone()).handshake(name_handshake("server")).framed(a_tx, a_rx),
+        remoc::ConnectOptions::new(cfg).handshake(name_handshake("client")).framed(b_tx, b_rx),
+    )
+    .await;
+    let (a_conn, mut a_tx, _a_rx): (_, rch::base::Sender<AuditClient>, rch::base::Receiver<()>) = a.unwrap();
+    let (b_conn, _b_tx, mut b_rx): (_, rch::base::Sender<()>, rch::base::Receiver<AuditClient>) = b.unwrap();
+
+    let addr: SocketAddr = "192.0.2.1:9870".parse().unwrap();
+    let peer_info = a_conn.peer_info();
+    peer_info.set_addr(addr);
+    peer_info.set_metadata("tenant", "acme");
+    remoc::exec::spawn(a_conn);
+    remoc::exec::spawn(b_conn);
+
+    println!("Spawning audit server");
+    let (server, client) = AuditServerShared::<_, remoc::codec::Default>::new(std::sync::Arc::new(AuditObj), 1);
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending audit client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    assert_eq!(client.caller().await.unwrap().as_deref(), Some("client"));
+    assert_eq!(client.tenant().await.unwrap().as_deref(), Some("acme"));
+    assert_eq!(client.addr().await.unwrap(), Some(addr));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn context_local() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+
+    let (server, client) = AuditServerShared::<_, remoc::codec::Default>::new(std::sync::Arc::new(AuditObj), 1);
+    remoc::exec::spawn(async move { server.serve(false).await.unwrap() });
+
+    assert_eq!(client.caller().await.unwrap(), None);
+    assert_eq!(client.tenant().await.unwrap(), None);
+    assert!(CallContext::current().is_none());
+}
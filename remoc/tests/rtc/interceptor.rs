@@ -0,0 +1,227 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use futures::future::{BoxFuture, FutureExt};
+use std::sync::{Arc, Mutex};
+
+use remoc::{
+    codec::{self, Codec},
+    prelude::*,
+    rtc::{CallError, CallInfo, CallOutcome, Interceptor, Next},
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> Result<String, CallError>;
+
+    async fn fail(&self) -> Result<(), CallError>;
+}
+
+pub struct GreeterObj;
+
+impl Greeter for GreeterObj {
+    async fn greet(&self, name: String) -> Result<String, CallError> {
+        Ok(format!("Hello {name}"))
+    }
+
+    async fn fail(&self) -> Result<(), CallError> {
+        Err(CallError::RemoteForward)
+    }
+}
+
+/// Records calls and their outcome.
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Interceptor for Recorder {
+    fn intercept<'a>(
+        &'a self, call: &'a CallInfo, next: Next<'a>,
+    ) -> BoxFuture<'a, Result<CallOutcome, CallError>> {
+        async move {
+            self.log.lock().unwrap().push(format!(
+                "{} before {}::{}",
+                self.name,
+                call.trait_name(),
+                call.method()
+            ));
+            assert!(call.request().is_none());
+            let outcome = next.run().await?;
+            assert!(outcome.result().is_none());
+            self.log.lock().unwrap().push(format!("{} after ok={}", self.name, outcome.is_ok()));
+            Ok(outcome)
+        }
+        .boxed()
+    }
+}
+
+/// Answers greetings of cached names and records the serialized results of other calls.
+struct Cache {
+    results: Arc<Mutex<Vec<Result<String, CallError>>>>,
+}
+
+impl Interceptor for Cache {
+    fn inspects_payload(&self) -> bool {
+        true
+    }
+
+    fn intercept<'a>(
+        &'a self, call: &'a CallInfo, next: Next<'a>,
+    ) -> BoxFuture<'a, Result<CallOutcome, CallError>> {
+        async move {
+            if call.method() != "greet" {
+                return next.run().await;
+            }
+
+            let (name,): (String,) = codec::Default::deserialize(call.request().unwrap()).unwrap();
+            if name == "cached" {
+                return CallOutcome::reply::<codec::Default, _, CallError>(&Ok("Hello from cache".to_string()));
+            }
+
+            let outcome = next.run().await?;
+            let result = codec::Default::deserialize(outcome.result().unwrap()).unwrap();
+            self.results.lock().unwrap().push(result);
+            Ok(outcome)
+        }
+        .boxed()
+    }
+}
+
+/// Rejects calls without a token in the metadata.
+struct Auth;
+
+impl Interceptor for Auth {
+    fn intercept<'a>(
+        &'a self, call: &'a CallInfo, next: Next<'a>,
+    ) -> BoxFuture<'a, Result<CallOutcome, CallError>> {
+        async move {
+            if !call.context().metadata().contains_key("token") {
+                return Err(CallError::Dropped);
+            }
+            next.run().await
+        }
+        .boxed()
+    }
+}
+
+/// Returns the outcome of the first call for all further calls without executing them.
+#[derive(Default)]
+struct Stale {
+    outcome: Mutex<Option<CallOutcome>>,
+}
+
+impl Interceptor for Stale {
+    fn intercept<'a>(
+        &'a self, _call: &'a CallInfo, next: Next<'a>,
+    ) -> BoxFuture<'a, Result<CallOutcome, CallError>> {
+        async move {
+            if let Some(outcome) = self.outcome.lock().unwrap().clone() {
+                return Ok(outcome);
+            }
+
+            let outcome = next.run().await?;
+            *self.outcome.lock().unwrap() = Some(outcome.clone());
+            Ok(outcome)
+        }
+        .boxed()
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn interceptor() {
+    use remoc::rtc::{ConfigurableClient, InterceptableServer, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<GreeterClient>().await;
+
+    println!("Spawning greeter server");
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let (mut server, client) = GreeterServerShared::<_, remoc::codec::Default>::new(Arc::new(GreeterObj), 1);
+    server.add_interceptor(Recorder { name: "outer", log: log.clone() });
+    server.add_interceptor(Auth);
+    server.add_interceptor(Recorder { name: "inner", log: log.clone() });
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending greeter client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Unauthorized call");
+    let res = client.greet("remoc".to_string()).await;
+    assert!(matches!(res, Err(CallError::Dropped)), "{res:?}");
+    assert_eq!(*log.lock().unwrap(), vec!["outer before Greeter::greet".to_string()]);
+    log.lock().unwrap().clear();
+
+    println!("Authorized calls");
+    let client = client.with_metadata("token", "secret");
+    assert_eq!(client.greet("remoc".to_string()).await.unwrap(), "Hello remoc");
+    assert!(matches!(client.fail().await, Err(CallError::RemoteForward)));
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "outer before Greeter::greet",
+            "inner before Greeter::greet",
+            "inner after ok=true",
+            "outer after ok=true",
+            "outer before Greeter::fail",
+            "inner before Greeter::fail",
+            "inner after ok=false",
+            "outer after ok=false",
+        ]
+    );
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn interceptor_reply() {
+    use remoc::rtc::{InterceptableServer, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<GreeterClient>().await;
+
+    println!("Spawning greeter server");
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let (mut server, client) = GreeterServerShared::<_, remoc::codec::Default>::new(Arc::new(GreeterObj), 1);
+    server.add_interceptor(Cache { results: results.clone() });
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending greeter client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Calls");
+    assert_eq!(client.greet("cached".to_string()).await.unwrap(), "Hello from cache");
+    assert_eq!(client.greet("remoc".to_string()).await.unwrap(), "Hello remoc");
+    assert!(matches!(client.fail().await, Err(CallError::RemoteForward)));
+
+    let results = results.lock().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].as_ref().unwrap(), "Hello remoc");
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn interceptor_without_reply() {
+    use remoc::rtc::{InterceptableServer, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<GreeterClient>().await;
+
+    println!("Spawning greeter server");
+    let (mut server, client) = GreeterServerShared::<_, remoc::codec::Default>::new(Arc::new(GreeterObj), 1);
+    server.add_interceptor(Stale::default());
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending greeter client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Calls");
+    assert_eq!(client.greet("remoc".to_string()).await.unwrap(), "Hello remoc");
+    let res = client.greet("remoc".to_string()).await;
+    assert!(matches!(res, Err(CallError::Intercepted)), "{res:?}");
+}
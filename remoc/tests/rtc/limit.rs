@@ -0,0 +1,168 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use futures::{FutureExt, future::BoxFuture};
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use remoc::{
+    prelude::*,
+    rtc::{CallError, DispatchDecision, LimitBehavior, LimitableServer, MonitorableServer, Req, ServerMonitor},
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Worker {
+    async fn work(&self, ms: u64) -> Result<usize, CallError>;
+}
+
+#[derive(Default)]
+pub struct WorkerObj {
+    running: AtomicUsize,
+    max_running: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+impl Worker for WorkerObj {
+    async fn work(&self, ms: u64) -> Result<usize, CallError> {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        remoc::exec::time::sleep(Duration::from_millis(ms)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        self.completed.fetch_add(1, Ordering::SeqCst);
+        Ok(running)
+    }
+}
+
+/// Monitor that tracks the maximum number of requests received by the server
+/// whose calls have not completed yet.
+struct InFlightMonitor {
+    obj: Arc<WorkerObj>,
+    received: usize,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl<V, R, M> ServerMonitor<V, R, M> for InFlightMonitor {
+    fn pre_dispatch<'a>(
+        &mut self, req: &'a Result<Option<Req<V, R, M>>, rch::mpsc::RecvError>,
+    ) -> BoxFuture<'a, DispatchDecision> {
+        if let Ok(Some(_)) = req {
+            self.received += 1;
+            let in_flight = self.received - self.obj.completed.load(Ordering::SeqCst);
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        }
+        futures::future::ready(DispatchDecision::Handle).boxed()
+    }
+}
+
+async fn serve(behavior: LimitBehavior) -> (Arc<WorkerObj>, WorkerClient) {
+    use remoc::rtc::ServerShared;
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<WorkerClient>().await;
+
+    let obj = Arc::new(WorkerObj::default());
+    let (mut server, client) = WorkerServerShared::<_, remoc::codec::Default>::new(obj.clone(), 16);
+    assert_eq!(server.max_concurrency(), None);
+    assert_eq!(server.limit_behavior(), LimitBehavior::Queue);
+    server.set_max_concurrency(NonZeroUsize::new(2));
+    server.set_limit_behavior(behavior);
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    a_tx.send(client).await.unwrap();
+    (obj, b_rx.recv().await.unwrap().unwrap())
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn queue() {
+    crate::init();
+    let (obj, client) = serve(LimitBehavior::Queue).await;
+
+    println!("Calling worker concurrently");
+    let calls = (0..6).map(|_| client.work(100));
+    let results = futures::future::join_all(calls).await;
+    assert!(results.into_iter().all(|res| res.is_ok()));
+    assert_eq!(obj.max_running.load(Ordering::SeqCst), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn reject() {
+    crate::init();
+    let (obj, client) = serve(LimitBehavior::Reject).await;
+
+    println!("Calling worker concurrently");
+    let calls = (0..6).map(|_| client.work(300));
+    let results = futures::future::join_all(calls).await;
+    let rejected = results.iter().filter(|res| matches!(res, Err(CallError::Overloaded))).count();
+    println!("{rejected} calls rejected");
+    assert_eq!(rejected, 4);
+    assert_eq!(obj.max_running.load(Ordering::SeqCst), 2);
+
+    println!("Calling worker after calls completed");
+    client.work(0).await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn queue_flood() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<WorkerClient>().await;
+
+    println!("Spawning worker server with monitor");
+    let obj = Arc::new(WorkerObj::default());
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let (mut server, client) = WorkerServerShared::<_, remoc::codec::Default>::new(obj.clone(), 16);
+    server.set_max_concurrency(NonZeroUsize::new(2));
+    server.set_monitor(InFlightMonitor { obj: obj.clone(), received: 0, max_in_flight: max_in_flight.clone() });
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Flooding worker with calls");
+    let calls = (0..200).map(|_| client.work(1));
+    let results = futures::future::join_all(calls).await;
+    assert!(results.into_iter().all(|res| res.is_ok()));
+    assert_eq!(obj.completed.load(Ordering::SeqCst), 200);
+
+    // Besides the running calls, only the request waiting for a permit
+    // has been taken from the request channel.
+    let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+    println!("Maximum calls in flight: {max_in_flight}");
+    assert!(max_in_flight <= 3, "{max_in_flight} calls in flight");
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn reject_flood() {
+    crate::init();
+    let (obj, client) = serve(LimitBehavior::Reject).await;
+
+    println!("Flooding worker with calls");
+    let calls = (0..200).map(|_| client.work(20));
+    let results = futures::future::join_all(calls).await;
+    let rejected = results.iter().filter(|res| matches!(res, Err(CallError::Overloaded))).count();
+    let completed = results.iter().filter(|res| res.is_ok()).count();
+    println!("{completed} calls completed, {rejected} calls rejected");
+    assert_eq!(completed + rejected, 200);
+    assert!(rejected > 0, "no calls rejected");
+    assert_eq!(obj.completed.load(Ordering::SeqCst), completed);
+
+    // Only calls holding a permit are executed by spawned tasks.
+    let max_running = obj.max_running.load(Ordering::SeqCst);
+    println!("Maximum calls running: {max_running}");
+    assert!(max_running <= 2, "{max_running} calls running");
+
+    println!("Calling worker after flood");
+    client.work(0).await.unwrap();
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use futures::{FutureExt, future::BoxFuture};
+use std::sync::{Arc, Mutex};
+
+use remoc::{
+    prelude::*,
+    rtc::{CallContext, CallError, DispatchDecision, MonitorableServer, Req, ReqMetadata, ServerMonitor},
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Tracer {
+    async fn trace_id(&self) -> Result<Option<String>, CallError>;
+}
+
+pub struct TracerObj;
+
+impl Tracer for TracerObj {
+    async fn trace_id(&self) -> Result<Option<String>, CallError> {
+        let ctx = CallContext::current().unwrap();
+        Ok(ctx.metadata().get("trace-id").cloned())
+    }
+}
+
+/// Monitor that records the authentication token of each request.
+struct TokenMonitor {
+    tokens: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl<V, R, M> ServerMonitor<V, R, M> for TokenMonitor
+where
+    V: ReqMetadata,
+    R: ReqMetadata,
+    M: ReqMetadata,
+{
+    fn pre_dispatch<'a>(
+        &mut self, req: &'a Result<Option<Req<V, R, M>>, rch::mpsc::RecvError>,
+    ) -> BoxFuture<'a, DispatchDecision> {
+        if let Ok(Some(req)) = req {
+            self.tokens.lock().unwrap().push(req.metadata().get("token").cloned());
+        }
+        futures::future::ready(DispatchDecision::Handle).boxed()
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn metadata() {
+    use remoc::rtc::{ConfigurableClient, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<TracerClient>().await;
+
+    println!("Spawning tracer server");
+    let tokens = Arc::new(Mutex::new(Vec::new()));
+    let (mut server, client) = TracerServerShared::<_, remoc::codec::Default>::new(Arc::new(TracerObj), 1);
+    server.set_monitor(TokenMonitor { tokens: tokens.clone() });
+    let server_task = remoc::exec::spawn(async move { server.serve(false).await.unwrap() });
+
+    println!("Sending tracer client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    assert_eq!(client.trace_id().await.unwrap(), None);
+
+    let traced = client.clone().with_metadata("trace-id", "1234").with_metadata("token", "secret");
+    assert_eq!(traced.trace_id().await.unwrap().as_deref(), Some("1234"));
+    assert_eq!(traced.metadata().len(), 2);
+
+    assert_eq!(client.trace_id().await.unwrap(), None);
+
+    drop((client, traced));
+    server_task.await.unwrap();
+
+    assert_eq!(*tokens.lock().unwrap(), vec![None, Some("secret".to_string()), None]);
+}
+
+/// Client implemented by hand that does not support modifying call options.
+struct ManualClient;
+
+impl rtc::Client for ManualClient {
+    fn capacity(&self) -> usize {
+        0
+    }
+
+    fn closed(&self) -> rtc::Closed {
+        rtc::Closed::new(async {})
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn max_request_size(&self) -> usize {
+        0
+    }
+
+    fn set_max_request_size(&mut self, _max_request_size: usize) {}
+
+    fn max_reply_size(&self) -> usize {
+        0
+    }
+
+    fn set_max_reply_size(&mut self, _max_reply_size: usize) {}
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn manual_client_call_options() {
+    use rtc::Client;
+
+    let client = ManualClient;
+    assert_eq!(client.call_options(), &rtc::CallOptions::new());
+    assert!(client.metadata().is_empty());
+}
@@ -5,17 +5,27 @@ mod assoc_multiple;
 mod assoc_qualified;
 mod assoc_simple;
 mod async_trait;
+mod balanced;
+mod context;
 mod default;
 mod errors;
 mod generics;
 mod generics_non_clone;
+mod interceptor;
+mod limit;
+mod metadata;
 mod monitor;
 mod readonly;
+mod retry;
 mod simple;
 mod simple_clone;
 mod simple_req;
 mod simple_req_stream;
 mod simple_rpit;
+mod streaming;
+mod timeout;
+#[cfg(feature = "tower")]
+mod tower;
 mod value;
 mod variants;
 
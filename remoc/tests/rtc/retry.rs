@@ -0,0 +1,266 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use remoc::{
+    prelude::*,
+    rtc::{CallError, RetryPolicy},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Store {
+    #[idempotent]
+    async fn get(&self, key: String) -> Result<u32, CallError>;
+
+    async fn increment(&mut self, key: String) -> Result<u32, CallError>;
+}
+
+#[rtc::remote(Server(ReqReceiver))]
+pub trait Ping {
+    #[idempotent]
+    async fn ping(&self, arg: Flaky) -> Result<(), CallError>;
+}
+
+/// Number of remaining deserializations of [Flaky] that should fail.
+static FLAKY_FAILURES: AtomicU32 = AtomicU32::new(0);
+
+/// Argument whose deserialization on the server fails [FLAKY_FAILURES] times.
+///
+/// This causes the server to reject the reply channel of the request.
+#[derive(Clone, Default)]
+pub struct Flaky {
+    attempts: Arc<AtomicU32>,
+}
+
+impl Serialize for Flaky {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for Flaky {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <()>::deserialize(deserializer)?;
+        if FLAKY_FAILURES.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+            return Err(de::Error::custom("flaky argument"));
+        }
+        Ok(Self::default())
+    }
+}
+
+pub struct StoreObj {
+    value: Arc<AtomicU32>,
+}
+
+impl Store for StoreObj {
+    async fn get(&self, _key: String) -> Result<u32, CallError> {
+        Ok(self.value.load(Ordering::SeqCst))
+    }
+
+    async fn increment(&mut self, _key: String) -> Result<u32, CallError> {
+        Ok(self.value.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn backoff() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(300),
+    };
+    assert_eq!(policy.backoff(1), Some(Duration::from_millis(100)));
+    assert_eq!(policy.backoff(2), Some(Duration::from_millis(200)));
+    assert_eq!(policy.backoff(3), Some(Duration::from_millis(300)));
+    assert_eq!(policy.backoff(4), Some(Duration::from_millis(300)));
+    assert_eq!(policy.backoff(5), None);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn retry() {
+    use remoc::rtc::{Client, ConfigurableClient, ServerRefMut};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<StoreClient>().await;
+
+    println!("Spawning store server");
+    let value = Arc::new(AtomicU32::new(0));
+    let mut obj = StoreObj { value: value.clone() };
+    let (server, client) = StoreServerRefMut::<_, remoc::codec::Default>::new(&mut obj, 1);
+
+    println!("Sending store client");
+    a_tx.send(client).await.unwrap();
+
+    let client_task = async move {
+        let client = b_rx.recv().await.unwrap().unwrap();
+        assert_eq!(client.retry_policy(), None);
+
+        let mut client = client.with_retry_policy(RetryPolicy::default());
+        assert_eq!(client.retry_policy(), Some(&RetryPolicy::default()));
+
+        let key = "counter".to_string();
+        assert_eq!(client.get(key.clone()).await.unwrap(), 0);
+        assert_eq!(client.increment(key.clone()).await.unwrap(), 1);
+        assert_eq!(client.get(key).await.unwrap(), 1);
+    };
+
+    let ((), res) = tokio::join!(client_task, server.serve());
+    res.unwrap();
+    assert_eq!(value.load(Ordering::SeqCst), 1);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn retry_failed_send() {
+    use remoc::rtc::{ConfigurableClient, ReqReceiver};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<PingClient>().await;
+
+    println!("Creating ping request receiver");
+    let (mut req_rx, client) = PingReqReceiver::new(1);
+
+    println!("Sending ping client");
+    a_tx.send(client).await.unwrap();
+
+    let client_task = async move {
+        const TIMEOUT: Duration = Duration::from_millis(300);
+        const BACKOFF: Duration = Duration::from_millis(200);
+        let policy = RetryPolicy { max_attempts: 3, initial_backoff: BACKOFF, max_backoff: BACKOFF };
+        let mut client =
+            b_rx.recv().await.unwrap().unwrap().with_retry_policy(policy.clone()).with_timeout(TIMEOUT);
+
+        println!("Failing first two attempts");
+        FLAKY_FAILURES.store(2, Ordering::SeqCst);
+        let arg = Flaky::default();
+        let start = remoc::exec::time::Instant::now();
+        client.ping(arg.clone()).await.unwrap();
+        assert_eq!(arg.attempts.load(Ordering::SeqCst), 3);
+
+        // Each attempt is given the full timeout, thus the call succeeds
+        // although the total delay between the attempts exceeds it.
+        let elapsed = start.elapsed();
+        println!("Call took {elapsed:?}");
+        assert!(elapsed > TIMEOUT, "{elapsed:?}");
+
+        println!("Failing all attempts");
+        FLAKY_FAILURES.store(u32::MAX, Ordering::SeqCst);
+        let arg = Flaky::default();
+        let res = client.ping(arg.clone()).await;
+        assert!(matches!(res, Err(CallError::RemoteConnect(_))), "{res:?}");
+        assert_eq!(arg.attempts.load(Ordering::SeqCst), policy.max_attempts);
+
+        println!("Not retrying without policy");
+        FLAKY_FAILURES.store(1, Ordering::SeqCst);
+        client.set_retry_policy(None);
+        let arg = Flaky::default();
+        let res = client.ping(arg.clone()).await;
+        assert!(matches!(res, Err(CallError::RemoteConnect(_))), "{res:?}");
+        assert_eq!(arg.attempts.load(Ordering::SeqCst), 1);
+    };
+
+    let server_task = async move {
+        loop {
+            match req_rx.recv().await {
+                Ok(Some(PingReq::Ping { __reply_tx, .. })) => {
+                    let _ = __reply_tx.send(Ok(()));
+                }
+                Ok(Some(_)) => (),
+                Ok(None) => break,
+                Err(err) if !err.is_final() => println!("Receiving request failed: {err}"),
+                Err(err) => panic!("receiving request failed: {err}"),
+            }
+        }
+    };
+
+    tokio::join!(client_task, server_task);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn retry_dropped_reply() {
+    use remoc::rtc::{ConfigurableClient, ReqReceiver};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<PingClient>().await;
+
+    println!("Creating ping request receiver");
+    let (mut req_rx, client) = PingReqReceiver::new(1);
+
+    println!("Sending ping client");
+    a_tx.send(client).await.unwrap();
+
+    let policy =
+        RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_millis(10), ..Default::default() };
+    let client = b_rx.recv().await.unwrap().unwrap().with_retry_policy(policy);
+
+    let client_task = async move {
+        println!("Calling ping, first reply is dropped");
+        client.ping(Flaky::default()).await.unwrap();
+    };
+
+    let server_task = async move {
+        let mut requests = 0;
+        while let Some(req) = req_rx.recv().await.unwrap() {
+            requests += 1;
+            match req {
+                PingReq::Ping { __reply_tx, .. } if requests > 1 => {
+                    let _ = __reply_tx.send(Ok(()));
+                }
+                _ => println!("Dropping reply sender"),
+            }
+        }
+        requests
+    };
+
+    let ((), requests) = tokio::join!(client_task, server_task);
+    assert_eq!(requests, 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn no_retry_when_closed() {
+    use remoc::rtc::{Client, ConfigurableClient, ReqReceiver};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<PingClient>().await;
+
+    println!("Creating ping request receiver");
+    let (req_rx, client) = PingReqReceiver::new(1);
+
+    println!("Sending ping client");
+    a_tx.send(client).await.unwrap();
+
+    const BACKOFF: Duration = Duration::from_secs(5);
+    let policy = RetryPolicy { max_attempts: 3, initial_backoff: BACKOFF, max_backoff: BACKOFF };
+    let client = b_rx.recv().await.unwrap().unwrap().with_retry_policy(policy);
+
+    println!("Dropping request receiver");
+    drop(req_rx);
+    client.closed().await;
+
+    println!("Calling ping");
+    let start = remoc::exec::time::Instant::now();
+    let res = client.ping(Flaky::default()).await;
+    assert!(res.is_err(), "{res:?}");
+    assert!(start.elapsed() < BACKOFF, "call was retried");
+}
@@ -0,0 +1,108 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use futures::{Stream, StreamExt, stream};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use remoc::{prelude::*, rtc::CallError};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Counter {
+    fn count(&self, n: u32) -> impl Stream<Item = Result<u32, CallError>> + Send;
+
+    async fn sum(&self, values: impl Stream<Item = u32> + Send) -> Result<u32, CallError>;
+
+    fn double(&self, values: impl Stream<Item = u32> + Send)
+    -> impl Stream<Item = Result<u32, CallError>> + Send;
+}
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+pub struct CounterObj {
+    dropped: Arc<AtomicBool>,
+}
+
+impl Counter for CounterObj {
+    fn count(&self, n: u32) -> impl Stream<Item = Result<u32, CallError>> + Send {
+        let flag = DropFlag(self.dropped.clone());
+        stream::unfold((0, flag), move |(i, flag)| async move {
+            if i == n {
+                return None;
+            }
+            remoc::exec::time::sleep(Duration::from_millis(10)).await;
+            Some((Ok(i), (i + 1, flag)))
+        })
+    }
+
+    async fn sum(&self, values: impl Stream<Item = u32> + Send) -> Result<u32, CallError> {
+        Ok(values.fold(0, |sum, value| async move { sum + value }).await)
+    }
+
+    fn double(
+        &self, values: impl Stream<Item = u32> + Send,
+    ) -> impl Stream<Item = Result<u32, CallError>> + Send {
+        values.map(|value| Ok(2 * value))
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn streaming() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<CounterClient>().await;
+
+    println!("Spawning counter server");
+    let dropped = Arc::new(AtomicBool::new(false));
+    let obj = CounterObj { dropped: dropped.clone() };
+    let (server, client) = CounterServerShared::<_, remoc::codec::Default>::new(Arc::new(obj), 1);
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending counter client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Receiving complete stream");
+    let values: Vec<_> = client.count(5).map(Result::unwrap).collect().await;
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+
+    println!("Dropping stream early");
+    dropped.store(false, Ordering::SeqCst);
+    let values: Vec<_> = client.count(1_000_000).take(3).map(Result::unwrap).collect().await;
+    assert_eq!(values, vec![0, 1, 2]);
+    remoc::exec::time::sleep(Duration::from_millis(200)).await;
+    assert!(dropped.load(Ordering::SeqCst));
+
+    println!("Sending stream argument");
+    let sum = client.sum(stream::iter(1..=100)).await.unwrap();
+    assert_eq!(sum, 5050);
+
+    println!("Bidirectional streaming");
+    let values: Vec<_> = client.double(stream::iter(1..=5)).map(Result::unwrap).collect().await;
+    assert_eq!(values, vec![2, 4, 6, 8, 10]);
+
+    println!("Bidirectional streaming in lockstep");
+    let (values_tx, values_rx) = futures::channel::mpsc::unbounded();
+    let mut doubled = std::pin::pin!(client.double(values_rx));
+    for value in 0..3 {
+        values_tx.unbounded_send(value).unwrap();
+        assert_eq!(doubled.next().await.unwrap().unwrap(), 2 * value);
+    }
+    drop(values_tx);
+    assert!(doubled.next().await.is_none());
+}
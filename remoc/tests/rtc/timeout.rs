@@ -0,0 +1,144 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use remoc::{
+    prelude::*,
+    rtc::{CallContext, CallError},
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Sleeper {
+    async fn sleep(&self, ms: u64) -> Result<Option<Duration>, CallError>;
+
+    #[timeout = "100ms"]
+    async fn quick(&self, ms: u64) -> Result<(), CallError>;
+
+    #[no_cancel]
+    async fn finish(&self, ms: u64) -> Result<(), CallError>;
+}
+
+pub struct SleeperObj {
+    finished: Arc<AtomicBool>,
+}
+
+impl Sleeper for SleeperObj {
+    async fn sleep(&self, ms: u64) -> Result<Option<Duration>, CallError> {
+        let remaining = CallContext::current().unwrap().remaining();
+        remoc::exec::time::sleep(Duration::from_millis(ms)).await;
+        Ok(remaining)
+    }
+
+    async fn quick(&self, ms: u64) -> Result<(), CallError> {
+        remoc::exec::time::sleep(Duration::from_millis(ms)).await;
+        Ok(())
+    }
+
+    async fn finish(&self, ms: u64) -> Result<(), CallError> {
+        remoc::exec::time::sleep(Duration::from_millis(ms)).await;
+        self.finished.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn timeout() {
+    use remoc::rtc::{ConfigurableClient, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<SleeperClient>().await;
+
+    println!("Spawning sleeper server");
+    let finished = Arc::new(AtomicBool::new(false));
+    let obj = SleeperObj { finished: finished.clone() };
+    let (server, client) = SleeperServerShared::<_, remoc::codec::Default>::new(Arc::new(obj), 1);
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending sleeper client");
+    a_tx.send(client).await.unwrap();
+    let mut client = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(client.timeout(), None);
+
+    println!("Call without timeout");
+    assert_eq!(client.sleep(200).await.unwrap(), None);
+
+    println!("Call with per-call timeout");
+    let res = client.clone().with_timeout(Duration::from_millis(100)).sleep(10_000).await;
+    assert!(matches!(res, Err(CallError::Timeout)), "{res:?}");
+
+    println!("Remaining time on server");
+    let remaining = client.clone().with_timeout(Duration::from_secs(10)).sleep(0).await.unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(10) && remaining > Duration::from_secs(5), "{remaining:?}");
+
+    println!("Call with method timeout");
+    let res = client.quick(10_000).await;
+    assert!(matches!(res, Err(CallError::Timeout)), "{res:?}");
+    client.quick(0).await.unwrap();
+
+    println!("Client timeout overrides method timeout");
+    client.set_timeout(Some(Duration::from_secs(10)));
+    client.quick(200).await.unwrap();
+
+    println!("Call with no_cancel method");
+    client.set_timeout(Some(Duration::from_millis(100)));
+    let res = client.finish(300).await;
+    assert!(matches!(res, Err(CallError::Timeout)), "{res:?}");
+    assert!(!finished.load(Ordering::SeqCst));
+    remoc::exec::time::sleep(Duration::from_millis(400)).await;
+    assert!(finished.load(Ordering::SeqCst));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn queued_timeout() {
+    use remoc::rtc::{ConfigurableClient, LimitableServer, ServerShared};
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<SleeperClient>().await;
+
+    println!("Spawning sleeper server with concurrency limit");
+    let finished = Arc::new(AtomicBool::new(false));
+    let obj = SleeperObj { finished: finished.clone() };
+    let (mut server, client) = SleeperServerShared::<_, remoc::codec::Default>::new(Arc::new(obj), 16);
+    server.set_max_concurrency(NonZeroUsize::new(1));
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+
+    println!("Sending sleeper client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Queueing call behind running call");
+    let running = client.sleep(400);
+    let queued = async {
+        remoc::exec::time::sleep(Duration::from_millis(50)).await;
+        client.clone().with_timeout(Duration::from_millis(200)).finish(0).await
+    };
+    let (running, queued) = tokio::join!(running, queued);
+    assert_eq!(running.unwrap(), None);
+    assert!(matches!(queued, Err(CallError::Timeout)), "{queued:?}");
+
+    println!("Queued call is not executed after its deadline");
+    remoc::exec::time::sleep(Duration::from_millis(100)).await;
+    assert!(!finished.load(Ordering::SeqCst));
+
+    println!("Remaining time includes time spent queued");
+    let running = client.sleep(300);
+    let queued = async {
+        remoc::exec::time::sleep(Duration::from_millis(50)).await;
+        client.clone().with_timeout(Duration::from_secs(1)).sleep(0).await
+    };
+    let (_, queued) = tokio::join!(running, queued);
+    let remaining = queued.unwrap().unwrap();
+    assert!(remaining < Duration::from_millis(800), "{remaining:?}");
+}
@@ -0,0 +1,86 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use std::{
+    convert::Infallible,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+use tower::{ServiceBuilder, ServiceExt, service_fn};
+
+use remoc::{
+    prelude::*,
+    rtc::{
+        CallError,
+        tower::{ClientService, serve},
+    },
+};
+
+use crate::loop_channel;
+
+#[rtc::remote]
+pub trait Adder {
+    async fn value(&self) -> Result<u32, CallError>;
+
+    async fn add(&self, n: u32) -> Result<u32, CallError>;
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn tower() {
+    use remoc::rtc::ReqReceiver;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<AdderClient>().await;
+
+    println!("Serving adder using a tower service");
+    let value = Arc::new(AtomicU32::new(0));
+    let (req_rx, client) = AdderReqReceiver::<remoc::codec::Default>::new(1);
+    let service = service_fn({
+        let value = value.clone();
+        move |req: AdderReq| {
+            let value = value.clone();
+            async move {
+                match req {
+                    AdderReq::Value { __reply_tx } => {
+                        let _ = __reply_tx.send(Ok(value.load(Ordering::SeqCst)));
+                    }
+                    AdderReq::Add { __reply_tx, n } => {
+                        remoc::exec::time::sleep(Duration::from_millis(u64::from(n))).await;
+                        let _ = __reply_tx.send(Ok(value.fetch_add(n, Ordering::SeqCst) + n));
+                    }
+                    _ => (),
+                }
+                Ok::<_, Infallible>(())
+            }
+        }
+    });
+    let server = remoc::exec::spawn(serve(req_rx, service));
+
+    println!("Sending adder client");
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Calling through client service");
+    let svc = ClientService::new(client);
+    let res = svc.clone().oneshot(AdderCall::Add { n: 2 }).await.unwrap();
+    assert!(matches!(res, AdderReply::Add(Ok(2))));
+    let res = svc.clone().oneshot(AdderCall::Value {}).await.unwrap();
+    assert!(matches!(res, AdderReply::Value(Ok(2))));
+
+    println!("Calling with timeout layer");
+    let timed = ServiceBuilder::new().timeout(Duration::from_millis(100)).service(svc.clone());
+    let res = timed.clone().oneshot(AdderCall::Add { n: 1 }).await.unwrap();
+    assert!(matches!(res, AdderReply::Add(Ok(3))));
+    let res = timed.clone().oneshot(AdderCall::Add { n: 500 }).await;
+    assert!(res.is_err());
+
+    println!("Dropping client");
+    drop(timed);
+    drop(svc);
+    server.await.unwrap().unwrap();
+    assert_eq!(value.load(Ordering::SeqCst), 503);
+}
@@ -33,6 +33,24 @@ mod robs;
 #[cfg(feature = "rtc")]
 mod rtc;
 
+#[cfg(feature = "registry")]
+mod registry;
+
+#[cfg(all(feature = "trace-context", feature = "full"))]
+mod trace;
+
+#[cfg(all(feature = "tls", not(target_family = "wasm")))]
+mod tls;
+
+#[cfg(feature = "noise")]
+mod noise;
+
+#[cfg(all(feature = "quic", not(target_family = "wasm")))]
+mod quic;
+
+#[cfg(all(feature = "websocket", not(target_family = "wasm")))]
+mod websocket;
+
 static INIT: Once = Once::new();
 
 pub fn init() {
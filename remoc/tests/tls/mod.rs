@@ -0,0 +1,298 @@
+use futures::future::BoxFuture;
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, IsCa, KeyPair};
+use std::{
+    net::{Ipv4Addr, Shutdown},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    net::{
+        TcpListener, TcpStream,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+    sync::{Mutex, mpsc},
+};
+
+use remoc::{
+    ConnectOptions,
+    chmux::{ChMuxError, HandshakeChannel, PeerIdentity},
+    rch::base,
+    rustls::{
+        self, RootCertStore,
+        pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName},
+        server::WebPkiClientVerifier,
+    },
+};
+
+/// Locally generated certificate authority with certificates for server and client.
+struct Certs {
+    ca: CertificateDer<'static>,
+    server: (CertificateDer<'static>, PrivateKeyDer<'static>),
+    client: (CertificateDer<'static>, PrivateKeyDer<'static>),
+}
+
+impl Certs {
+    fn generate() -> Self {
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = CertifiedIssuer::self_signed(ca_params, KeyPair::generate().unwrap()).unwrap();
+
+        let issue = |name: &str| {
+            let key = KeyPair::generate().unwrap();
+            let cert = CertificateParams::new(vec![name.to_string()]).unwrap().signed_by(&key, &ca).unwrap();
+            (cert.der().clone(), PrivatePkcs8KeyDer::from(key.serialize_der()).into())
+        };
+
+        Self { ca: ca.der().clone(), server: issue("localhost"), client: issue("client") }
+    }
+
+    fn roots(&self) -> Arc<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        roots.add(self.ca.clone()).unwrap();
+        Arc::new(roots)
+    }
+}
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, server) = tokio::join!(TcpStream::connect(addr), listener.accept());
+    (client.unwrap(), server.unwrap().0)
+}
+
+fn client_cfg(certs: &Certs) -> Arc<rustls::ClientConfig> {
+    Arc::new(rustls::ClientConfig::builder().with_root_certificates(certs.roots()).with_no_client_auth())
+}
+
+fn server_cfg(certs: &Certs) -> Arc<rustls::ServerConfig> {
+    Arc::new(
+        rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![certs.server.0.clone()], certs.server.1.clone_key())
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn tls() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let client_cfg = rustls::ClientConfig::builder().with_root_certificates(certs.roots()).with_no_client_auth();
+    let server_cfg = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![certs.server.0.clone()], certs.server.1.clone_key())
+        .unwrap();
+
+    let (client_socket, server_socket) = tcp_pair().await;
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let (client, server) = tokio::join!(
+        remoc::Connect::tls_client(Default::default(), Arc::new(client_cfg), server_name, client_socket),
+        remoc::Connect::tls_server(Default::default(), Arc::new(server_cfg), server_socket),
+    );
+    let (client_conn, mut client_tx, _client_rx): (_, base::Sender<String>, base::Receiver<()>) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx): (_, base::Sender<()>, base::Receiver<String>) = server.unwrap();
+
+    let client_certs = client_conn.peer_info().certificates();
+    assert_eq!(client_certs.len(), 1);
+    assert_eq!(&client_certs[0][..], &certs.server.0[..]);
+    assert!(server_conn.peer_info().certificates().is_empty());
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    println!("Sending over TLS");
+    client_tx.send("Hello TLS".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello TLS".to_string()));
+}
+
+#[tokio::test]
+async fn tls_client_auth() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let client_cfg = rustls::ClientConfig::builder()
+        .with_root_certificates(certs.roots())
+        .with_client_auth_cert(vec![certs.client.0.clone()], certs.client.1.clone_key())
+        .unwrap();
+    let server_cfg = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(WebPkiClientVerifier::builder(certs.roots()).build().unwrap())
+        .with_single_cert(vec![certs.server.0.clone()], certs.server.1.clone_key())
+        .unwrap();
+
+    let (client_socket, server_socket) = tcp_pair().await;
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let (client, server) = tokio::join!(
+        remoc::Connect::tls_client(Default::default(), Arc::new(client_cfg), server_name, client_socket),
+        remoc::Connect::tls_server(Default::default(), Arc::new(server_cfg), server_socket),
+    );
+    let (client_conn, _client_tx, mut client_rx): (_, base::Sender<()>, base::Receiver<u32>) = client.unwrap();
+    let (server_conn, mut server_tx, _server_rx): (_, base::Sender<u32>, base::Receiver<()>) = server.unwrap();
+
+    let server_certs = server_conn.peer_info().certificates();
+    assert_eq!(server_certs.len(), 1);
+    assert_eq!(&server_certs[0][..], &certs.client.0[..]);
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    println!("Sending over mutually authenticated TLS");
+    server_tx.send(123).await.unwrap();
+    assert_eq!(client_rx.recv().await.unwrap(), Some(123));
+}
+
+#[tokio::test]
+async fn tls_untrusted_server() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let other_certs = Certs::generate();
+    let client_cfg =
+        rustls::ClientConfig::builder().with_root_certificates(other_certs.roots()).with_no_client_auth();
+    let server_cfg = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![certs.server.0.clone()], certs.server.1.clone_key())
+        .unwrap();
+
+    let (client_socket, server_socket) = tcp_pair().await;
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let (client, server) = tokio::join!(
+        remoc::Connect::tls_client::<_, (), (), remoc::codec::Default>(
+            Default::default(),
+            Arc::new(client_cfg),
+            server_name,
+            client_socket
+        ),
+        remoc::Connect::tls_server::<_, (), (), remoc::codec::Default>(
+            Default::default(),
+            Arc::new(server_cfg),
+            server_socket
+        ),
+    );
+
+    let Err(err) = client else { panic!("client connected to untrusted server") };
+    println!("Client error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(remoc::chmux::ChMuxError::StreamError(_))));
+    assert!(server.is_err());
+}
+
+#[tokio::test]
+async fn tls_with_options() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let handshake = |name: &'static str| {
+        move |mut channel: HandshakeChannel| async move {
+            channel.send(name).await?;
+            let remote_name = channel.recv().await?;
+            Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+        }
+    };
+
+    let (client_socket, server_socket) = tcp_pair().await;
+    let (client_input, client_output) = client_socket.into_split();
+    let (server_input, server_output) = server_socket.into_split();
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let (client, server) = tokio::join!(
+        ConnectOptions::new(Default::default())
+            .tls_client(client_cfg(&certs), server_name)
+            .handshake(handshake("client"))
+            .hello(1u32)
+            .io::<_, _, String, (), remoc::codec::Default>(client_input, client_output),
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("server"))
+            .hello(2u32)
+            .tls_server(server_cfg(&certs))
+            .io::<_, _, (), String, remoc::codec::Default>(server_input, server_output),
+    );
+    let (client_conn, mut client_tx, _client_rx, client_remote_hello) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx, server_remote_hello) = server.unwrap();
+
+    assert_eq!(client_conn.peer_identity().unwrap().as_str(), "server");
+    assert_eq!(server_conn.peer_identity().unwrap().as_str(), "client");
+    assert_eq!(client_remote_hello, 2);
+    assert_eq!(server_remote_hello, 1);
+    assert_eq!(&client_conn.peer_info().certificates()[0][..], &certs.server.0[..]);
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    println!("Sending over TLS");
+    client_tx.send("Hello TLS".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello TLS".to_string()));
+}
+
+#[tokio::test]
+async fn tls_timeout() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let cfg = remoc::Cfg { connection_timeout: Some(Duration::from_millis(500)), ..Default::default() };
+
+    // The remote endpoint never answers the TLS handshake.
+    let (socket, _remote) = tokio::io::duplex(65_536);
+    let (input, output) = tokio::io::split(socket);
+    let res = ConnectOptions::new(cfg)
+        .tls_client(client_cfg(&certs), ServerName::try_from("localhost").unwrap())
+        .io::<_, _, (), (), remoc::codec::Default>(input, output)
+        .await;
+
+    let Err(err) = res else { panic!("TLS handshake without remote endpoint succeeded") };
+    println!("Error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(ChMuxError::Timeout)));
+}
+
+type Halves = (OwnedReadHalf, OwnedWriteHalf);
+
+/// Returns a reconnect function that takes new transports from a channel.
+fn reconnector(rx: mpsc::UnboundedReceiver<Halves>) -> impl FnMut() -> BoxFuture<'static, Option<Halves>> {
+    let rx = Arc::new(Mutex::new(rx));
+    move || {
+        let rx = rx.clone();
+        Box::pin(async move { rx.lock().await.recv().await })
+    }
+}
+
+#[tokio::test]
+async fn tls_reconnect() {
+    crate::init();
+
+    let certs = Certs::generate();
+    let cfg = remoc::Cfg { connection_timeout: Some(Duration::from_secs(1)), ..Default::default() };
+
+    let (client_socket, server_socket) = tcp_pair().await;
+    let breaker = client_socket.into_std().unwrap();
+    let client_socket = TcpStream::from_std(breaker.try_clone().unwrap()).unwrap();
+    let (client_input, client_output) = client_socket.into_split();
+    let (server_input, server_output) = server_socket.into_split();
+
+    let (client_reconnect_tx, client_reconnect_rx) = mpsc::unbounded_channel();
+    let (server_reconnect_tx, server_reconnect_rx) = mpsc::unbounded_channel();
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let (client, server) = tokio::join!(
+        ConnectOptions::new(cfg.clone())
+            .tls_client(client_cfg(&certs), server_name)
+            .reconnect(reconnector(client_reconnect_rx))
+            .io::<_, _, String, (), remoc::codec::Default>(client_input, client_output),
+        ConnectOptions::new(cfg)
+            .tls_server(server_cfg(&certs))
+            .reconnect(reconnector(server_reconnect_rx))
+            .io::<_, _, (), String, remoc::codec::Default>(server_input, server_output),
+    );
+    let (client_conn, mut client_tx, _client_rx) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx) = server.unwrap();
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    client_tx.send("before".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("before".to_string()));
+
+    println!("Breaking TCP connection");
+    breaker.shutdown(Shutdown::Both).unwrap();
+    let (client_socket, server_socket) = tcp_pair().await;
+    client_reconnect_tx.send(client_socket.into_split()).unwrap();
+    server_reconnect_tx.send(server_socket.into_split()).unwrap();
+
+    client_tx.send("after".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("after".to_string()));
+}
@@ -0,0 +1,132 @@
+use opentelemetry::trace::{TraceContextExt, TracerProvider};
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::SdkTracerProvider};
+use std::sync::Arc;
+use tracing::{Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use remoc::{prelude::*, rtc::CallError, trace::TraceContext};
+
+use crate::loop_channel;
+
+/// Installs an OpenTelemetry enabled subscriber for the current thread.
+fn otel_subscriber() -> tracing::subscriber::DefaultGuard {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    let tracer = SdkTracerProvider::builder().build().tracer("remoc");
+    let subscriber = tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    tracing::subscriber::set_default(subscriber)
+}
+
+fn trace_id(span: &Span) -> String {
+    span.context().span().span_context().trace_id().to_string()
+}
+
+#[rtc::remote]
+pub trait Traced {
+    async fn trace_id(&self) -> Result<String, CallError>;
+}
+
+pub struct TracedObj;
+
+impl Traced for TracedObj {
+    async fn trace_id(&self) -> Result<String, CallError> {
+        Ok(trace_id(&Span::current()))
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn context() {
+    crate::init();
+    let _guard = otel_subscriber();
+
+    let span = tracing::info_span!("sender");
+    let ctx = TraceContext::of(&span);
+    assert!(!ctx.is_empty());
+    let child = ctx.child_span();
+    assert_eq!(trace_id(&child), trace_id(&span));
+
+    assert!(TraceContext::current().is_empty());
+    assert!(TraceContext::default().child_span().is_none());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn negotiated() {
+    use futures::StreamExt;
+
+    crate::init();
+
+    let cfg = remoc::Cfg::default();
+    crate::loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = tokio::join!(
+        remoc::Connect::framed::<_, _, (), (), remoc::codec::Default>(cfg.clone(), a_tx, a_rx),
+        remoc::Connect::framed::<_, _, (), (), remoc::codec::Default>(cfg, b_tx, b_rx),
+    );
+    let (a_conn, _, _) = a.unwrap();
+    let (b_conn, _, _) = b.unwrap();
+
+    assert!(a_conn.peer_info().trace_context());
+    assert!(b_conn.peer_info().trace_context());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn mpsc() {
+    crate::init();
+    let _guard = otel_subscriber();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<rch::mpsc::Receiver<u32>>().await;
+
+    let (tx, rx) = rch::mpsc::channel(1);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let span = tracing::info_span!("sender");
+    tx.send(1).instrument(span.clone()).await.unwrap();
+    tx.send(2).await.unwrap();
+
+    assert_eq!(rx.recv().await.unwrap(), Some(1));
+    assert_ne!(trace_id(&span), trace_id(&Span::none()));
+    assert_eq!(trace_id(rx.span()), trace_id(&span));
+
+    assert_eq!(rx.recv().await.unwrap(), Some(2));
+    assert!(rx.span().is_none());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn rfn() {
+    crate::init();
+    let _guard = otel_subscriber();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<rfn::RFn<(), Result<String, rfn::CallError>>>().await;
+
+    let rfn = rfn::RFn::new_0(|| async move { Ok(trace_id(&Span::current())) });
+    a_tx.send(rfn).await.unwrap();
+    let rfn = b_rx.recv().await.unwrap().unwrap();
+
+    let span = tracing::info_span!("caller");
+    let remote_id = rfn.call().instrument(span.clone()).await.unwrap();
+    assert_eq!(remote_id, trace_id(&span));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn rtc() {
+    use remoc::rtc::ServerShared;
+
+    crate::init();
+    let _guard = otel_subscriber();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<TracedClient>().await;
+
+    let (server, client) = TracedServerShared::<_, remoc::codec::Default>::new(Arc::new(TracedObj), 1);
+    remoc::exec::spawn(async move { server.serve(true).await.unwrap() });
+    a_tx.send(client).await.unwrap();
+    let client = b_rx.recv().await.unwrap().unwrap();
+
+    let span = tracing::info_span!("caller");
+    let remote_id = client.trace_id().instrument(span.clone()).await.unwrap();
+    assert_eq!(remote_id, trace_id(&span));
+}
@@ -0,0 +1,186 @@
+use futures::{SinkExt, future::BoxFuture};
+use std::{
+    net::{Ipv4Addr, Shutdown},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, mpsc},
+};
+use tokio_tungstenite::{
+    WebSocketStream,
+    tungstenite::{Message, protocol::WebSocketConfig},
+};
+
+use remoc::{
+    ConnectOptions,
+    chmux::{HandshakeChannel, PeerIdentity},
+    rch::base,
+};
+
+async fn websocket_pair() -> (WebSocketStream<TcpStream>, WebSocketStream<TcpStream>) {
+    let (client, server, _) = websocket_pair_with_breaker(remoc::Cfg::default().websocket_config()).await;
+    (client, server)
+}
+
+/// Returns a connected WebSocket pair and a handle to the client TCP socket for breaking the connection.
+async fn websocket_pair_with_breaker(
+    config: WebSocketConfig,
+) -> (WebSocketStream<TcpStream>, WebSocketStream<TcpStream>, std::net::TcpStream) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, server) = tokio::join!(
+        async {
+            let socket = TcpStream::connect(addr).await.unwrap().into_std().unwrap();
+            let breaker = socket.try_clone().unwrap();
+            let socket = TcpStream::from_std(socket).unwrap();
+            let (client, _) =
+                tokio_tungstenite::client_async_with_config(format!("ws://{addr}/"), socket, Some(config))
+                    .await
+                    .unwrap();
+            (client, breaker)
+        },
+        async {
+            let (socket, _) = listener.accept().await.unwrap();
+            tokio_tungstenite::accept_async_with_config(socket, Some(config)).await.unwrap()
+        },
+    );
+    let (client, breaker) = client;
+    (client, server, breaker)
+}
+
+#[tokio::test]
+async fn websocket() {
+    crate::init();
+
+    let (client_ws, server_ws) = websocket_pair().await;
+    let (client, server) = tokio::join!(
+        remoc::Connect::websocket(Default::default(), client_ws),
+        remoc::Connect::websocket(Default::default(), server_ws),
+    );
+    let (client_conn, mut client_tx, mut client_rx): (_, base::Sender<String>, base::Receiver<u32>) =
+        client.unwrap();
+    let (server_conn, mut server_tx, mut server_rx): (_, base::Sender<u32>, base::Receiver<String>) =
+        server.unwrap();
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    println!("Sending over WebSocket");
+    client_tx.send("Hello WebSocket".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello WebSocket".to_string()));
+
+    server_tx.send(123).await.unwrap();
+    assert_eq!(client_rx.recv().await.unwrap(), Some(123));
+}
+
+#[tokio::test]
+async fn websocket_text_message() {
+    crate::init();
+
+    let (mut client_ws, server_ws) = websocket_pair().await;
+    client_ws.send(Message::text("not binary")).await.unwrap();
+
+    let server =
+        remoc::Connect::websocket::<_, (), (), remoc::codec::Default>(Default::default(), server_ws).await;
+    let Err(err) = server else { panic!("server accepted text message") };
+    println!("Server error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(remoc::chmux::ChMuxError::StreamError(_))));
+}
+
+#[tokio::test]
+async fn websocket_unlimited() {
+    crate::init();
+
+    let (_client_ws, server_ws, _) = websocket_pair_with_breaker(WebSocketConfig::default()).await;
+    let server =
+        remoc::Connect::websocket::<_, (), (), remoc::codec::Default>(Default::default(), server_ws).await;
+    let Err(err) = server else { panic!("server accepted WebSocket without size limits") };
+    println!("Server error: {err}");
+    assert!(matches!(err, remoc::ConnectError::ChMux(remoc::chmux::ChMuxError::StreamError(_))));
+}
+
+#[tokio::test]
+async fn websocket_with_options() {
+    crate::init();
+
+    let handshake = |name: &'static str| {
+        move |mut channel: HandshakeChannel| async move {
+            channel.send(name).await?;
+            let remote_name = channel.recv().await?;
+            Ok(PeerIdentity::new(String::from_utf8_lossy(&remote_name)))
+        }
+    };
+
+    let (client_ws, server_ws) = websocket_pair().await;
+    let (client, server) = tokio::join!(
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("client"))
+            .hello(1u32)
+            .websocket::<_, String, (), remoc::codec::Default>(client_ws),
+        ConnectOptions::new(Default::default())
+            .handshake(handshake("server"))
+            .hello(2u32)
+            .websocket::<_, (), String, remoc::codec::Default>(server_ws),
+    );
+    let (client_conn, mut client_tx, _client_rx, client_remote_hello) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx, server_remote_hello) = server.unwrap();
+
+    assert_eq!(client_conn.peer_identity().unwrap().as_str(), "server");
+    assert_eq!(server_conn.peer_identity().unwrap().as_str(), "client");
+    assert_eq!(client_remote_hello, 2);
+    assert_eq!(server_remote_hello, 1);
+
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    client_tx.send("Hello WebSocket".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("Hello WebSocket".to_string()));
+}
+
+type Ws = WebSocketStream<TcpStream>;
+
+/// Returns a reconnect function that takes new WebSocket streams from a channel.
+fn reconnector(rx: mpsc::UnboundedReceiver<Ws>) -> impl FnMut() -> BoxFuture<'static, Option<Ws>> {
+    let rx = Arc::new(Mutex::new(rx));
+    move || {
+        let rx = rx.clone();
+        Box::pin(async move { rx.lock().await.recv().await })
+    }
+}
+
+#[tokio::test]
+async fn websocket_reconnect() {
+    crate::init();
+
+    let cfg = remoc::Cfg { connection_timeout: Some(Duration::from_secs(1)), ..Default::default() };
+
+    let (client_ws, server_ws, breaker) = websocket_pair_with_breaker(cfg.websocket_config()).await;
+    let (client_reconnect_tx, client_reconnect_rx) = mpsc::unbounded_channel();
+    let (server_reconnect_tx, server_reconnect_rx) = mpsc::unbounded_channel();
+    let (client, server) = tokio::join!(
+        ConnectOptions::new(cfg.clone())
+            .reconnect(reconnector(client_reconnect_rx))
+            .websocket::<_, String, (), remoc::codec::Default>(client_ws),
+        ConnectOptions::new(cfg)
+            .reconnect(reconnector(server_reconnect_rx))
+            .websocket::<_, (), String, remoc::codec::Default>(server_ws),
+    );
+    let (client_conn, mut client_tx, _client_rx) = client.unwrap();
+    let (server_conn, _server_tx, mut server_rx) = server.unwrap();
+    tokio::spawn(client_conn);
+    tokio::spawn(server_conn);
+
+    client_tx.send("before".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("before".to_string()));
+
+    println!("Breaking TCP connection");
+    breaker.shutdown(Shutdown::Both).unwrap();
+    let (client_ws, server_ws) = websocket_pair().await;
+    client_reconnect_tx.send(client_ws).unwrap();
+    server_reconnect_tx.send(server_ws).unwrap();
+
+    client_tx.send("after".to_string()).await.unwrap();
+    assert_eq!(server_rx.recv().await.unwrap(), Some("after".to_string()));
+}
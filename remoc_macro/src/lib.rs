@@ -25,6 +25,7 @@ pub fn remote(args: proc_macro::TokenStream, input: proc_macro::TokenStream) ->
         Err(msg) => quote! { ::std::compile_error!(#msg); },
     };
     let client = trait_def.client();
+    let calls = trait_def.calls();
 
     #[allow(clippy::let_and_return)]
     let output = proc_macro::TokenStream::from(quote! {
@@ -32,6 +33,7 @@ pub fn remote(args: proc_macro::TokenStream, input: proc_macro::TokenStream) ->
         #request_enums
         #servers
         #client
+        #calls
     });
 
     // println!("{}", &output);
@@ -1,10 +1,10 @@
 //! Method parsing and generation.
 
 use proc_macro2::TokenStream;
-use quote::{TokenStreamExt, quote};
+use quote::{TokenStreamExt, format_ident, quote};
 use syn::{
-    Attribute, Block, FnArg, GenericArgument, Generics, Ident, Pat, PatType, Path, PathArguments, ReturnType,
-    Stmt, Token, Type, TypeParamBound, braced, parenthesized,
+    Attribute, Block, Expr, ExprLit, FnArg, GenericArgument, Generics, Ident, Lit, Meta, Pat, PatType, Path,
+    PathArguments, ReturnType, Stmt, Token, Type, TypeParamBound, braced, parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
@@ -13,7 +13,7 @@ use syn::{
 
 use crate::{
     assoc_type::{AssocType, remove_self_type},
-    util::{attribute_tokens, to_pascal_case},
+    util::{attribute_tokens, parse_duration_millis, to_pascal_case},
 };
 
 /// Self reference of method.
@@ -36,6 +36,8 @@ pub struct NamedArg {
     pub ident: Ident,
     /// Type.
     pub ty: Type,
+    /// Item type, if the argument is a stream.
+    pub stream: Option<Type>,
 }
 
 impl NamedArg {
@@ -46,7 +48,29 @@ impl NamedArg {
         } else {
             return Err(syn::Error::new(pat_type.pat.span(), "expected identifier"));
         };
-        Ok(Self { attrs: pat_type.attrs.clone(), ident, ty: (*pat_type.ty).clone() })
+
+        // Check for `impl Stream<Item = T> + Send` argument.
+        let mut stream = None;
+        if let Type::ImplTrait(impl_trait) = &*pat_type.ty {
+            let mut has_send = false;
+            for bound in &impl_trait.bounds {
+                match bound {
+                    TypeParamBound::Trait(tb) if is_send(&tb.path) => has_send = true,
+                    TypeParamBound::Trait(tb) if stream_item_type(&tb.path).is_some() => {
+                        stream = stream_item_type(&tb.path).cloned()
+                    }
+                    _ => (),
+                }
+            }
+            if stream.is_none() || !has_send {
+                return Err(syn::Error::new(
+                    pat_type.ty.span(),
+                    "'impl' arguments must be of the form 'impl Stream<Item = _> + Send'",
+                ));
+            }
+        }
+
+        Ok(Self { attrs: pat_type.attrs.clone(), ident, ty: (*pat_type.ty).clone(), stream })
     }
 }
 
@@ -62,11 +86,19 @@ pub struct TraitMethod {
     /// Arguments.
     pub args: Vec<NamedArg>,
     /// Return type.
+    ///
+    /// For streaming methods this is the item type of the returned stream.
     pub ret_ty: Type,
-    /// Trait bounds when return type is `impl Future + ...`
+    /// Whether the method returns `impl Stream + ...`.
+    pub stream: bool,
+    /// Trait bounds when return type is `impl Future + ...` or `impl Stream + ...`
     pub bounds: Punctuated<TypeParamBound, Token![+]>,
     /// Whether method should be cancelled, if client sends hangup message.
     pub cancel: bool,
+    /// Default timeout of method in milliseconds.
+    pub timeout: Option<u64>,
+    /// Whether failed calls may be retried by the client.
+    pub idempotent: bool,
     /// Method body.
     pub body: Option<Vec<Stmt>>,
 }
@@ -94,6 +126,24 @@ fn future_output_type(path: &Path) -> Option<&Type> {
     None
 }
 
+/// The item type of a `Stream<Item = ...>`, `futures::Stream<Item = ...>` or equivalent.
+fn stream_item_type(path: &Path) -> Option<&Type> {
+    let last = path.segments.last()?;
+    if last.ident != "Stream" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &last.arguments else { return None };
+    for arg in &args.args {
+        let GenericArgument::AssocType(ty) = arg else { continue };
+        if ty.ident == "Item" {
+            return Some(&ty.ty);
+        }
+    }
+
+    None
+}
+
 /// Whether the path is `Send` or equivalent.
 fn is_send(path: &Path) -> bool {
     match (path.segments.get(0), path.segments.get(1), path.segments.get(2)) {
@@ -135,6 +185,36 @@ impl TraitMethod {
             true
         });
 
+        // Check for timeout attribute.
+        let mut timeout = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("timeout")) {
+            let Meta::NameValue(nv) = &attr.meta else {
+                return Err(syn::Error::new(attr.span(), "expected #[timeout = \"...\"]"));
+            };
+            let Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) = &nv.value else {
+                return Err(syn::Error::new(nv.value.span(), "expected string literal"));
+            };
+            timeout = Some(parse_duration_millis(&lit.value()).ok_or_else(|| {
+                syn::Error::new(
+                    lit.span(),
+                    "invalid duration, expected for example \"500ms\", \"5s\", \"2m\" or \"1h\"",
+                )
+            })?);
+        }
+        attrs.retain(|attr| !attr.path().is_ident("timeout"));
+
+        // Check for idempotent attribute.
+        let mut idempotent = false;
+        attrs.retain(|attr| {
+            if let Some(attr) = attr.path().get_ident()
+                && *attr == "idempotent"
+            {
+                idempotent = true;
+                return false;
+            }
+            true
+        });
+
         // Parse generics.
         let generics = input.parse::<Generics>()?;
         if generics.lt_token.is_some() {
@@ -175,13 +255,15 @@ impl TraitMethod {
             ReturnType::Type(_, ty) => {
                 if is_async {
                     // async fn name() -> Result<_>
-                    Some((*ty, true, Punctuated::new()))
+                    Some((*ty, false, true, Punctuated::new()))
                 } else {
                     // fn name() -> impl Future<Output = Result<_>> + Send
+                    // fn name() -> impl Stream<Item = Result<_>> + Send
                     match *ty {
                         Type::ImplTrait(impl_trait) => {
                             let mut others: Punctuated<TypeParamBound, Token![+]> = Punctuated::new();
                             let mut output = None;
+                            let mut stream = false;
                             let mut has_send = false;
 
                             for bound in impl_trait.bounds {
@@ -190,11 +272,15 @@ impl TraitMethod {
                                     TypeParamBound::Trait(tb) if future_output_type(&tb.path).is_some() => {
                                         output = future_output_type(&tb.path).cloned()
                                     }
+                                    TypeParamBound::Trait(tb) if stream_item_type(&tb.path).is_some() => {
+                                        output = stream_item_type(&tb.path).cloned();
+                                        stream = true;
+                                    }
                                     _ => others.push(bound),
                                 }
                             }
 
-                            output.map(|output| (output, has_send, others))
+                            output.map(|output| (output, stream, has_send, others))
                         }
                         _ => None,
                     }
@@ -202,12 +288,42 @@ impl TraitMethod {
             }
             ReturnType::Default => None,
         };
-        let Some((ret_ty, true, bounds)) = ret_ty else {
-            return Err(
-                input.error("'async fn' methods must return 'Result<_>' and 'fn' methods must return 'impl Future<Output = Result<_>> + Send'")
-            );
+        let Some((ret_ty, stream, true, bounds)) = ret_ty else {
+            return Err(input.error(
+                "'async fn' methods must return 'Result<_>' and 'fn' methods must return \
+                 'impl Future<Output = Result<_>> + Send' or 'impl Stream<Item = Result<_>> + Send'",
+            ));
         };
 
+        // Check restrictions of streaming methods.
+        if stream {
+            if !cancel {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "streaming methods do not support the 'no_cancel' attribute",
+                ));
+            }
+            if timeout.is_some() {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "streaming methods do not support the 'timeout' attribute",
+                ));
+            }
+        }
+
+        // Check restrictions of idempotent methods.
+        if idempotent {
+            if self_ref == SelfRef::Value {
+                return Err(syn::Error::new(ident.span(), "idempotent methods must not take self by value"));
+            }
+            if stream {
+                return Err(syn::Error::new(ident.span(), "streaming methods cannot be idempotent"));
+            }
+            if args.iter().any(|arg| arg.stream.is_some()) {
+                return Err(syn::Error::new(ident.span(), "methods with stream arguments cannot be idempotent"));
+            }
+        }
+
         // Parse default body.
         let body = if input.peek(token::Brace) {
             let content;
@@ -218,7 +334,7 @@ impl TraitMethod {
             None
         };
 
-        Ok(Self { attrs, ident, self_ref, args, ret_ty, bounds, cancel, body })
+        Ok(Self { attrs, ident, self_ref, args, ret_ty, stream, bounds, cancel, timeout, idempotent, body })
     }
 }
 
@@ -240,7 +356,7 @@ impl TraitMethod {
         args.append_all(self_ref);
 
         // Request arguments.
-        for NamedArg { attrs: _, ident, ty } in &self.args {
+        for NamedArg { ident, ty, .. } in &self.args {
             args.append_all(quote! { #ident : #ty , });
         }
 
@@ -249,7 +365,7 @@ impl TraitMethod {
             Some(stmts) => {
                 let mut body = quote! {};
                 body.append_all(stmts);
-                if impl_future {
+                if impl_future && !self.stream {
                     quote! { { async move { #body } } }
                 } else {
                     quote! { { #body } }
@@ -258,13 +374,15 @@ impl TraitMethod {
             None => quote! { ; },
         };
 
-        let sig = if impl_future {
-            let bounds = if self.bounds.is_empty() {
-                quote! {}
-            } else {
-                let bounds = &self.bounds;
-                quote! { + #bounds }
-            };
+        let bounds = if self.bounds.is_empty() {
+            quote! {}
+        } else {
+            let bounds = &self.bounds;
+            quote! { + #bounds }
+        };
+        let sig = if self.stream {
+            quote! { #attrs fn #ident ( #args ) -> impl ::remoc::rtc::Stream<Item = #ret_ty> + ::std::marker::Send #bounds }
+        } else if impl_future {
             quote! { #attrs fn #ident ( #args ) -> impl ::std::future::Future<Output = #ret_ty> + ::std::marker::Send #bounds }
         } else {
             quote! { #attrs async fn #ident ( #args ) -> #ret_ty }
@@ -277,18 +395,41 @@ impl TraitMethod {
     }
 
     /// Entry within request enum.
-    pub fn request_enum_entry(&self, assoc: &[AssocType]) -> TokenStream {
+    ///
+    /// If `context` is true, the fields holding the request metadata and timeout and capturing
+    /// the `CallContext` during deserialization are included.
+    pub fn request_enum_entry(&self, assoc: &[AssocType], context: bool) -> TokenStream {
         let ident = to_pascal_case(&self.ident);
         let ret_ty = remove_self_type(&self.ret_ty, assoc);
 
-        let mut entries = quote! {
-            #[doc="Reply channel for sending the result of the method invocation.\n\n"]
-            #[doc="The channel is closed when the calling async method is cancelled "]
-            #[doc="or a connection error occurs."]
-            __reply_tx: ::remoc::rch::oneshot::Sender<#ret_ty, Codec>,
+        let mut entries = if self.stream {
+            quote! {
+                #[doc="Reply channel for sending the items of the returned stream.\n\n"]
+                #[doc="The channel is closed when the client drops the stream "]
+                #[doc="or a connection error occurs."]
+                __reply_tx: ::remoc::rch::mpsc::Sender<#ret_ty, Codec>,
+            }
+        } else {
+            quote! {
+                #[doc="Reply channel for sending the result of the method invocation.\n\n"]
+                #[doc="The channel is closed when the calling async method is cancelled "]
+                #[doc="or a connection error occurs."]
+                __reply_tx: ::remoc::rch::oneshot::Sender<#ret_ty, Codec>,
+            }
         };
 
-        for NamedArg { attrs, ident, ty } in &self.args {
+        if context {
+            entries.append_all(quote! {
+                #[serde(default)]
+                __metadata: ::remoc::rtc::Metadata,
+                #[serde(default)]
+                __timeout: ::remoc::rtc::Deadline,
+                #[serde(skip, default = "::remoc::rtc::CallContext::received")]
+                __call_context: ::remoc::rtc::CallContext,
+            });
+        }
+
+        for NamedArg { attrs, ident, ty, stream } in &self.args {
             if !attrs.iter().any(|attr| attr.path().is_ident("doc")) {
                 entries.append_all(quote! {
                     #[doc = concat!(stringify!(#ident), " parameter")]
@@ -296,24 +437,102 @@ impl TraitMethod {
             }
 
             let attrs = attribute_tokens(attrs);
-            let ty = remove_self_type(ty, assoc);
+            let ty = match stream {
+                Some(item) => {
+                    let item = remove_self_type(item, assoc);
+                    syn::parse_quote! { ::remoc::rch::mpsc::Receiver<#item, Codec> }
+                }
+                None => remove_self_type(ty, assoc),
+            };
             entries.append_all(quote! {
                 #attrs
                 #ident : #ty ,
             });
         }
 
-        let docs_attrs = attribute_tokens(
+        let docs_attrs = self.doc_attrs();
+        quote! { #docs_attrs #ident {#entries} , }
+    }
+
+    /// Whether the method can be represented within the call enum.
+    pub fn is_callable(&self) -> bool {
+        !self.stream && self.args.iter().all(|arg| arg.stream.is_none())
+    }
+
+    /// Documentation attributes of the method.
+    fn doc_attrs(&self) -> TokenStream {
+        attribute_tokens(
             &self
                 .attrs
                 .iter()
                 .filter(|attr| matches!(attr.path().get_ident(), Some(ident) if *ident == "doc"))
                 .cloned()
                 .collect::<Vec<_>>(),
-        );
+        )
+    }
+
+    /// Entry within call enum.
+    pub fn call_enum_entry(&self, assoc: &[AssocType]) -> TokenStream {
+        let ident = to_pascal_case(&self.ident);
+
+        let mut entries = quote! {};
+        for NamedArg { attrs, ident, ty, .. } in &self.args {
+            if !attrs.iter().any(|attr| attr.path().is_ident("doc")) {
+                entries.append_all(quote! {
+                    #[doc = concat!(stringify!(#ident), " parameter")]
+                });
+            }
+
+            let attrs = attribute_tokens(
+                &attrs.iter().filter(|attr| attr.path().is_ident("doc")).cloned().collect::<Vec<_>>(),
+            );
+            let ty = remove_self_type(ty, assoc);
+            entries.append_all(quote! {
+                #attrs
+                #ident : #ty ,
+            });
+        }
+
+        let docs_attrs = self.doc_attrs();
         quote! { #docs_attrs #ident {#entries} , }
     }
 
+    /// Entry within reply enum.
+    pub fn reply_enum_entry(&self, assoc: &[AssocType]) -> TokenStream {
+        let ident = to_pascal_case(&self.ident);
+        let ret_ty = remove_self_type(&self.ret_ty, assoc);
+        let docs_attrs = self.doc_attrs();
+        quote! { #docs_attrs #ident (#ret_ty) , }
+    }
+
+    /// Match clause performing the call on the client.
+    pub fn invoke_clause(&self, trait_path: &TokenStream, call: &Ident, reply: &Ident) -> TokenStream {
+        let ident = &self.ident;
+        let enum_ident = to_pascal_case(ident);
+
+        let mut entries = quote! {};
+        for NamedArg { ident: arg_ident, .. } in &self.args {
+            entries.append_all(quote! { #arg_ident, });
+        }
+
+        let this = match self.self_ref {
+            SelfRef::Value => quote! { self },
+            SelfRef::Ref => quote! { &self },
+            SelfRef::RefMut => quote! { &mut this },
+        };
+        let bind = match self.self_ref {
+            SelfRef::RefMut => quote! { let mut this = self; },
+            _ => quote! {},
+        };
+
+        quote! {
+            #call :: #enum_ident { #entries } => ::remoc::rtc::FutureExt::boxed(async move {
+                #bind
+                #reply :: #enum_ident (<Self as #trait_path>::#ident(#this, #entries).await)
+            }),
+        }
+    }
+
     /// Conversion clause for `impl From<#from_ty>`.
     pub fn impl_from_clause(&self, from_ty: &Ident) -> TokenStream {
         let ident = &self.ident;
@@ -325,45 +544,100 @@ impl TraitMethod {
             entries.append_all(quote! { #arg_ident, });
         }
 
-        let entry = quote! { #enum_ident {#entries} };
-        quote! { #from_ty :: #entry => Self :: #entry , }
+        quote! { #from_ty :: #enum_ident {#entries ..} => Self :: #enum_ident {#entries} , }
+    }
+
+    /// Match clause returning the request metadata.
+    pub fn metadata_clause(&self) -> TokenStream {
+        let enum_ident = to_pascal_case(&self.ident);
+        quote! { Self :: #enum_ident { __metadata, .. } => __metadata, }
+    }
+
+    /// Enum match discriminator and code for rejecting the request.
+    pub fn reject_clause(&self) -> TokenStream {
+        let enum_ident = to_pascal_case(&self.ident);
+        let send = if self.stream {
+            quote! { try_send }
+        } else {
+            quote! { send }
+        };
+        quote! {
+            Self :: #enum_ident { __reply_tx, .. } => {
+                let _ = __reply_tx.#send(::remoc::rtc::overloaded_reply());
+            },
+        }
     }
 
     /// Enum match discriminator and dispatch code.
-    pub fn dispatch_discriminator(&self) -> TokenStream {
+    pub fn dispatch_discriminator(&self, trait_ident: &Ident) -> TokenStream {
         let ident = &self.ident;
         let enum_ident = to_pascal_case(ident);
+        let trait_name = trait_ident.to_string();
+        let method_name = ident.to_string();
 
         // Build match and call argument lists.
-        let mut entries = quote! { __reply_tx, };
+        let mut entries = quote! { __reply_tx, __metadata, __timeout, __call_context, };
         let mut args = quote! {};
-        for NamedArg { ident: arg_ident, .. } in &self.args {
+        let mut request = quote! {};
+        for NamedArg { ident: arg_ident, stream, .. } in &self.args {
             entries.append_all(quote! { #arg_ident, });
-            args.append_all(quote! { #arg_ident, });
+            if stream.is_some() {
+                args.append_all(quote! { ::remoc::rtc::arg_stream(#arg_ident), });
+            } else {
+                args.append_all(quote! { #arg_ident, });
+                request.append_all(quote! { &#arg_ident, });
+            }
         }
 
         // Generate call code.
-        let call = if self.cancel {
+        let request = quote! { __interceptors.request::<Codec, _>(&(#request)) };
+        let invoke = quote! {
+            ::remoc::rtc::intercept::<Codec, _, _>(&__interceptors, #trait_name, #method_name, #request, async move {
+                __target.#ident(#args).await
+            })
+        };
+        let call = if self.stream {
             quote! {
+                let result = ::remoc::rtc::intercept::<Codec, _, _>(&__interceptors, #trait_name, #method_name, #request, async {
+                    ::remoc::rtc::forward_stream(__target.#ident(#args), __reply_tx.clone()).await;
+                    ::std::result::Result::<(), ::remoc::rtc::CallError>::Ok(())
+                }).await;
+                if let ::std::result::Result::Err(err) = result {
+                    let _ = __reply_tx.send(::remoc::rtc::error_reply(err)).await;
+                }
+            }
+        } else if self.cancel {
+            quote! {
+                if __timeout.is_expired() {
+                    ::remoc::rtc::send_reply(__reply_tx, &__err_tx, ::remoc::rtc::timeout_reply()).await;
+                    return;
+                }
                 ::remoc::rtc::select! {
                     biased;
                     () = __reply_tx.closed() => (),
-                    result = __target.#ident(#args) => {
+                    () = ::remoc::rtc::expired(__timeout) => {
+                        ::remoc::rtc::send_reply(__reply_tx, &__err_tx, ::remoc::rtc::timeout_reply()).await;
+                    }
+                    result = #invoke => {
                         ::remoc::rtc::send_reply(__reply_tx, &__err_tx, result).await;
                     }
                 }
             }
         } else {
             quote! {
-                let result = __target.#ident(#args).await;
+                if __timeout.is_expired() {
+                    ::remoc::rtc::send_reply(__reply_tx, &__err_tx, ::remoc::rtc::timeout_reply()).await;
+                    return;
+                }
+                let result = #invoke.await;
                 ::remoc::rtc::send_reply(__reply_tx, &__err_tx, result).await;
             }
         };
 
         // Generate match clause.
         quote! {
-            Self :: #enum_ident { #args __reply_tx } => {
-                async move { #call }.boxed()
+            Self :: #enum_ident { #entries } => {
+                __call_context.scope(__metadata, __timeout, async move { #call }).boxed()
             },
         }
     }
@@ -374,6 +648,10 @@ impl TraitMethod {
     ) -> TokenStream {
         let Self { ident, self_ref, .. } = self;
         let ret_ty = remove_self_type(&self.ret_ty, assoc);
+        let default_timeout = match self.timeout {
+            Some(ms) => quote! { ::std::option::Option::Some(::std::time::Duration::from_millis(#ms)) },
+            None => quote! { ::std::option::Option::None },
+        };
 
         // Self reference and request enum.
         let (self_ref, req_enum, req_type) = match self_ref {
@@ -386,20 +664,106 @@ impl TraitMethod {
         // Argument and request enum entry list.
         let mut args = quote! {};
         let mut entries = quote! {};
-        for NamedArg { ident, ty, .. } in &self.args {
+        let mut forward_setup = quote! {};
+        let mut forwards = quote! {};
+        let mut forwarding = quote! { ::std::future::ready(()) };
+        for NamedArg { ident, ty, stream, .. } in &self.args {
             let ty = remove_self_type(ty, assoc);
             args.append_all(quote! { #ident : #ty , });
-            entries.append_all(quote! { #ident , });
+            if stream.is_some() {
+                let tx = format_ident!("__{}_tx", ident);
+                let rx = format_ident!("__{}_rx", ident);
+                forward_setup.append_all(quote! {
+                    let (#tx, #rx) = ::remoc::rch::mpsc::channel(1);
+                });
+                forwards.append_all(quote! {
+                    ::remoc::rtc::FutureExt::boxed(::remoc::rtc::forward_stream(#ident, #tx)),
+                });
+                forwarding = quote! {
+                    ::remoc::rtc::join_forwarding(#forwarding, ::remoc::rtc::forward_stream(#ident, #tx))
+                };
+                entries.append_all(quote! { #ident: #rx , });
+            } else if self.idempotent {
+                entries.append_all(quote! { #ident: ::std::clone::Clone::clone(&#ident) , });
+            } else {
+                entries.append_all(quote! { #ident , });
+            }
+        }
+
+        if self.stream {
+            let bounds = if self.bounds.is_empty() {
+                quote! {}
+            } else {
+                let bounds = &self.bounds;
+                quote! { + #bounds }
+            };
+            return quote! {
+                fn #ident (#self_ref, #args) -> impl ::remoc::rtc::Stream<Item = #ret_ty> + ::std::marker::Send #bounds {
+                    #forward_setup
+                    let (mut reply_tx, reply_rx) = ::remoc::rch::mpsc::channel(1);
+                    reply_tx.set_max_item_size(self.max_reply_size);
+                    let req_value = #req_enum :: #req_case {
+                        __reply_tx: reply_tx,
+                        __metadata: self.call_options.metadata.clone(),
+                        __timeout: ::std::default::Default::default(),
+                        __call_context: ::std::default::Default::default(),
+                        #entries
+                    };
+                    let req = ::remoc::rtc::Req::#req_type(req_value);
+                    let req_tx = self.req_tx.clone();
+                    ::remoc::rtc::reply_stream(
+                        async move {
+                            req_tx.send(req).await.map_err(::remoc::rtc::CallError::from)?;
+                            Ok(())
+                        },
+                        reply_rx,
+                        #forwarding,
+                    )
+                }
+            };
+        }
+
+        let forward = if forwards.is_empty() {
+            quote! {}
+        } else {
+            quote! { let __call = ::remoc::rtc::with_forwarding(vec![#forwards], __call); }
+        };
+
+        // Single attempt of the call, evaluating to its future.
+        let attempt = quote! {
+            let (mut reply_tx, reply_rx) = ::remoc::rch::oneshot::channel();
+            reply_tx.set_max_item_size(self.max_reply_size);
+            let req_value = #req_enum :: #req_case {
+                __reply_tx: reply_tx,
+                __metadata: self.call_options.metadata.clone(),
+                __timeout: ::remoc::rtc::Deadline::after(__timeout),
+                __call_context: ::std::default::Default::default(),
+                #entries
+            };
+            let req = ::remoc::rtc::Req::#req_type(req_value);
+            let req_tx = &self.req_tx;
+            let __call = async move {
+                req_tx.send(req).await.map_err(::remoc::rtc::CallError::from)?;
+                reply_rx.await.map_err(::remoc::rtc::CallError::from)
+            };
+            #forward
+            ::remoc::rtc::with_timeout(__timeout, __call)
+        };
+
+        if self.idempotent {
+            return quote! {
+                async fn #ident (#self_ref, #args) -> #ret_ty {
+                    let __timeout = ::remoc::rtc::call_timeout(self.call_options.timeout.or(#default_timeout));
+                    ::remoc::rtc::call_idempotent(self.call_options.retry_policy.as_ref(), &self.req_tx, || { #attempt }).await
+                }
+            };
         }
 
         quote! {
             async fn #ident (#self_ref, #args) -> #ret_ty {
-                let (mut reply_tx, reply_rx) = ::remoc::rch::oneshot::channel();
-                reply_tx.set_max_item_size(self.max_reply_size);
-                let req_value = #req_enum :: #req_case { __reply_tx: reply_tx, #entries };
-                let req = ::remoc::rtc::Req::#req_type(req_value);
-                self.req_tx.send(req).await.map_err(::remoc::rtc::CallError::from)?;
-                let reply = reply_rx.await.map_err(::remoc::rtc::CallError::from)?;
+                let __timeout = ::remoc::rtc::call_timeout(self.call_options.timeout.or(#default_timeout));
+                #forward_setup
+                let reply = { #attempt }.await?;
                 reply
             }
         }
@@ -168,6 +168,9 @@ impl TraitDef {
             self.clone = true;
             Ok(())
         } else if meta.path.is_ident("async_trait") {
+            if self.methods.iter().any(|m| m.stream || m.args.iter().any(|arg| arg.stream.is_some())) {
+                return Err(meta.error("streaming methods are not supported with async_trait"));
+            }
             self.async_trait = true;
             Ok(())
         } else if meta.path.is_ident("server") || meta.path.is_ident("Server") {
@@ -497,26 +500,40 @@ impl TraitDef {
         let (mut value_entries, mut ref_entries, mut ref_mut_entries) = (quote! {}, quote! {}, quote! {});
         let (mut value_clauses, mut ref_clauses, mut ref_mut_clauses) = (quote! {}, quote! {}, quote! {});
         let (mut value_froms, mut ref_froms, mut ref_mut_froms) = (quote! {}, quote! {}, quote! {});
+        let (mut value_metadata, mut ref_metadata, mut ref_mut_metadata) = (quote! {}, quote! {}, quote! {});
+        let mut ref_rejects = quote! {};
         for md in &self.methods {
             match md.self_ref {
                 SelfRef::Value => {
-                    value_entries.append_all(md.request_enum_entry(assoc));
-                    value_clauses.append_all(md.dispatch_discriminator());
+                    value_entries.append_all(md.request_enum_entry(assoc, true));
+                    value_clauses.append_all(md.dispatch_discriminator(ident));
                     value_froms.append_all(md.impl_from_clause(&req_value));
+                    value_metadata.append_all(md.metadata_clause());
                 }
                 SelfRef::Ref => {
-                    ref_entries.append_all(md.request_enum_entry(assoc));
-                    ref_clauses.append_all(md.dispatch_discriminator());
+                    ref_entries.append_all(md.request_enum_entry(assoc, true));
+                    ref_clauses.append_all(md.dispatch_discriminator(ident));
                     ref_froms.append_all(md.impl_from_clause(&req_ref));
+                    ref_metadata.append_all(md.metadata_clause());
+                    ref_rejects.append_all(md.reject_clause());
                 }
                 SelfRef::RefMut => {
-                    ref_mut_entries.append_all(md.request_enum_entry(assoc));
-                    ref_mut_clauses.append_all(md.dispatch_discriminator());
+                    ref_mut_entries.append_all(md.request_enum_entry(assoc, true));
+                    ref_mut_clauses.append_all(md.dispatch_discriminator(ident));
                     ref_mut_froms.append_all(md.impl_from_clause(&req_ref_mut));
+                    ref_mut_metadata.append_all(md.metadata_clause());
                 }
             }
         }
 
+        // The public request enum does not capture the call context.
+        let mut all_entries = quote! {};
+        for self_ref in [SelfRef::Value, SelfRef::Ref, SelfRef::RefMut] {
+            for md in self.methods.iter().filter(|md| md.self_ref == self_ref) {
+                all_entries.append_all(md.request_enum_entry(assoc, false));
+            }
+        }
+
         let req_doc = format!(
             "Request generated by calling a method on [{}].\n\
             \n\
@@ -536,8 +553,18 @@ impl TraitDef {
                 __Phantom (::std::marker::PhantomData<(#ty_generics_list)>)
             }
 
+            impl #impl_generics_impl ::remoc::rtc::ReqMetadata for #req_value #impl_generics_ty #impl_generics_where {
+                fn metadata(&self) -> &::remoc::rtc::Metadata {
+                    match self {
+                        #value_metadata
+                        Self::__Phantom(_) => &::remoc::rtc::EMPTY_METADATA,
+                    }
+                }
+            }
+
             impl #impl_generics_impl #req_value #impl_generics_ty #impl_generics_where {
-                fn dispatch<Target>(self, __target: Target, __err_tx: ::remoc::rtc::ReplyErrorSender) ->
+                fn dispatch<Target>(self, __target: Target, __err_tx: ::remoc::rtc::ReplyErrorSender,
+                    __interceptors: ::remoc::rtc::Interceptors) ->
                      ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = ()> + ::std::marker::Send>>
                 where
                     Target: #trait_path_dispatch,
@@ -562,8 +589,18 @@ impl TraitDef {
                 __Phantom (::std::marker::PhantomData<(#ty_generics_list)>)
             }
 
+            impl #impl_generics_impl ::remoc::rtc::ReqMetadata for #req_ref #impl_generics_ty #impl_generics_where {
+                fn metadata(&self) -> &::remoc::rtc::Metadata {
+                    match self {
+                        #ref_metadata
+                        Self::__Phantom(_) => &::remoc::rtc::EMPTY_METADATA,
+                    }
+                }
+            }
+
             impl #impl_generics_impl #req_ref #impl_generics_ty #impl_generics_where {
-                fn dispatch<'target, Target>(self, __target: &'target Target, __err_tx: ::remoc::rtc::ReplyErrorSender) ->
+                fn dispatch<'target, Target>(self, __target: &'target Target, __err_tx: ::remoc::rtc::ReplyErrorSender,
+                    __interceptors: ::remoc::rtc::Interceptors) ->
                     ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = ()> + ::std::marker::Send + 'target>>
                 where
                     Target: #trait_path_dispatch,
@@ -575,6 +612,13 @@ impl TraitDef {
                         Self::__Phantom(_) => async move {}.boxed()
                     }
                 }
+
+                fn reject(self) {
+                    match self {
+                        #ref_rejects
+                        Self::__Phantom(_) => (),
+                    }
+                }
             }
 
             #[derive(::remoc::rtc::Serialize, ::remoc::rtc::Deserialize)]
@@ -588,8 +632,18 @@ impl TraitDef {
                 __Phantom (::std::marker::PhantomData<(#ty_generics_list)>)
             }
 
+            impl #impl_generics_impl ::remoc::rtc::ReqMetadata for #req_ref_mut #impl_generics_ty #impl_generics_where {
+                fn metadata(&self) -> &::remoc::rtc::Metadata {
+                    match self {
+                        #ref_mut_metadata
+                        Self::__Phantom(_) => &::remoc::rtc::EMPTY_METADATA,
+                    }
+                }
+            }
+
             impl #impl_generics_impl #req_ref_mut #impl_generics_ty #impl_generics_where {
-                fn dispatch<'target, Target>(self, __target: &'target mut Target, __err_tx: ::remoc::rtc::ReplyErrorSender) ->
+                fn dispatch<'target, Target>(self, __target: &'target mut Target, __err_tx: ::remoc::rtc::ReplyErrorSender,
+                    __interceptors: ::remoc::rtc::Interceptors) ->
                     ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = ()> + ::std::marker::Send + 'target>>
                 where
                     Target: #trait_path_dispatch,
@@ -609,9 +663,7 @@ impl TraitDef {
             #[serde(bound(serialize = #impl_generics_where_str))]
             #[serde(bound(deserialize = #impl_generics_where_str))]
             #vis enum #req_all #ty_generics_default_codec #ty_generics_where {
-                #value_entries
-                #ref_entries
-                #ref_mut_entries
+                #all_entries
                 #[doc(hidden)]
                 #[serde(skip)]
                 __Phantom (::std::marker::PhantomData<(#ty_generics_list)>),
@@ -704,19 +756,19 @@ impl TraitDef {
         let doc = format!("Server for [{}] taking the target object by value.", &ident);
 
         let dispatch_value = if self.is_taking_value() {
-            quote! { req.dispatch(target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
 
         let dispatch_ref = if self.is_taking_ref() {
-            quote! { req.dispatch(&target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(&target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
 
         let dispatch_ref_mut = if self.is_taking_ref_mut() {
-            quote! { req.dispatch(&mut target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(&mut target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
@@ -736,6 +788,7 @@ impl TraitDef {
                     Codec,
                 >,
                 monitor: ::std::boxed::Box<dyn ::remoc::rtc::ServerMonitor<#req_params>>,
+                interceptors: ::remoc::rtc::Interceptors,
             }
 
             impl #impl_generics_impl ::remoc::rtc::ServerBase for #server #impl_generics_ty #impl_generics_where
@@ -743,6 +796,13 @@ impl TraitDef {
                 type Client = #client #req_generics;
             }
 
+            impl #impl_generics_impl ::remoc::rtc::InterceptableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn add_interceptor(&mut self, interceptor: impl ::remoc::rtc::Interceptor + 'static) {
+                    self.interceptors.push(interceptor);
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::MonitorableServer for #server #impl_generics_ty #impl_generics_where
             {
                 type Value = #req_value #req_generics;
@@ -757,19 +817,21 @@ impl TraitDef {
             impl #impl_generics_impl ::remoc::rtc::Server <Target, Codec> for #server #impl_generics_ty #impl_generics_where
             {
                 fn new(target: Target, request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (
                         Self {
                             target,
                             req_rx,
                             monitor: ::std::boxed::Box::new(::remoc::rtc::DefaultServerMonitor),
+                            interceptors: ::std::default::Default::default(),
                         },
                         Self::Client::new(req_tx),
                     )
                 }
 
                 async fn serve(self) -> (::std::option::Option<Target>, ::std::result::Result<(), ::remoc::rtc::ServeError>) {
-                    let Self { mut target, mut req_rx, mut monitor } = self;
+                    let Self { mut target, mut req_rx, mut monitor, interceptors } = self;
                     let (err_tx, mut err_rx) = ::remoc::rtc::reply_error_channel();
 
                     let target_opt = loop {
@@ -777,6 +839,7 @@ impl TraitDef {
                             biased;
                             Some(err) = err_rx.recv() => return (Some(target), Err(err.into())),
                             req = req_rx.recv() => {
+                                let __span = ::remoc::rtc::received_span(&req_rx);
                                 ::remoc::rtc::server_monitor_pre_dispatch!(monitor, req, target);
                                 match req {
                                     Ok(Some(::remoc::rtc::Req::Value(req))) => {
@@ -836,7 +899,7 @@ impl TraitDef {
         let doc = format!("Server for [{}] taking the target object by reference.", &ident);
 
         let dispatch_ref = if self.is_taking_ref() {
-            quote! { req.dispatch(target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
@@ -856,6 +919,7 @@ impl TraitDef {
                     Codec,
                 >,
                 monitor: ::std::boxed::Box<dyn ::remoc::rtc::ServerMonitor<#req_params>>,
+                interceptors: ::remoc::rtc::Interceptors,
             }
 
             impl #impl_generics_impl ::remoc::rtc::ServerBase for #server #impl_generics_ty #impl_generics_where
@@ -863,6 +927,13 @@ impl TraitDef {
                 type Client = #client #req_generics;
             }
 
+            impl #impl_generics_impl ::remoc::rtc::InterceptableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn add_interceptor(&mut self, interceptor: impl ::remoc::rtc::Interceptor + 'static) {
+                    self.interceptors.push(interceptor);
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::MonitorableServer for #server #impl_generics_ty #impl_generics_where
             {
                 type Value = #req_value #req_generics;
@@ -877,19 +948,21 @@ impl TraitDef {
             impl #impl_generics_impl ::remoc::rtc::ServerRef <'target, Target, Codec> for #server #impl_generics_ty #impl_generics_where
             {
                 fn new(target: &'target Target, request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (
                         Self {
                             target,
                             req_rx,
                             monitor: ::std::boxed::Box::new(::remoc::rtc::DefaultServerMonitor),
+                            interceptors: ::std::default::Default::default(),
                         },
                         Self::Client::new(req_tx),
                     )
                 }
 
                 async fn serve(self) -> ::std::result::Result<(), ::remoc::rtc::ServeError> {
-                    let Self { target, mut req_rx, mut monitor } = self;
+                    let Self { target, mut req_rx, mut monitor, interceptors } = self;
                     let (err_tx, mut err_rx) = ::remoc::rtc::reply_error_channel();
 
                     let ret = loop {
@@ -897,6 +970,7 @@ impl TraitDef {
                             biased;
                             Some(err) = err_rx.recv() => return Err(err.into()),
                             req = req_rx.recv() => {
+                                let __span = ::remoc::rtc::received_span(&req_rx);
                                 ::remoc::rtc::server_monitor_pre_dispatch!(monitor, req);
                                 match req {
                                     Ok(Some(::remoc::rtc::Req::Ref(req))) => {
@@ -949,13 +1023,13 @@ impl TraitDef {
         let doc = format!("Server for [{}] taking the target object by mutable reference.", &ident);
 
         let dispatch_ref = if self.is_taking_ref() {
-            quote! { req.dispatch(target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
 
         let dispatch_ref_mut = if self.is_taking_ref_mut() {
-            quote! { req.dispatch(target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
@@ -975,6 +1049,7 @@ impl TraitDef {
                     Codec,
                 >,
                 monitor: ::std::boxed::Box<dyn ::remoc::rtc::ServerMonitor<#req_params>>,
+                interceptors: ::remoc::rtc::Interceptors,
             }
 
             impl #impl_generics_impl ::remoc::rtc::ServerBase for #server #impl_generics_ty #impl_generics_where
@@ -982,6 +1057,13 @@ impl TraitDef {
                 type Client = #client #req_generics;
             }
 
+            impl #impl_generics_impl ::remoc::rtc::InterceptableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn add_interceptor(&mut self, interceptor: impl ::remoc::rtc::Interceptor + 'static) {
+                    self.interceptors.push(interceptor);
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::MonitorableServer for #server #impl_generics_ty #impl_generics_where
             {
                 type Value = #req_value #req_generics;
@@ -996,19 +1078,21 @@ impl TraitDef {
             impl #impl_generics_impl ::remoc::rtc::ServerRefMut <'target, Target, Codec> for #server #impl_generics_ty #impl_generics_where
             {
                 fn new(target: &'target mut Target, request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (
                         Self {
                             target,
                             req_rx,
                             monitor: ::std::boxed::Box::new(::remoc::rtc::DefaultServerMonitor),
+                            interceptors: ::std::default::Default::default(),
                         },
                         Self::Client::new(req_tx),
                     )
                 }
 
                 async fn serve(self) -> ::std::result::Result<(), ::remoc::rtc::ServeError> {
-                    let Self { target, mut req_rx, mut monitor } = self;
+                    let Self { target, mut req_rx, mut monitor, interceptors } = self;
                     let (err_tx, mut err_rx) = ::remoc::rtc::reply_error_channel();
 
                     let ret = loop {
@@ -1016,6 +1100,7 @@ impl TraitDef {
                             biased;
                             Some(err) = err_rx.recv() => return Err(err.into()),
                             req = req_rx.recv() => {
+                                let __span = ::remoc::rtc::received_span(&req_rx);
                                 ::remoc::rtc::server_monitor_pre_dispatch!(monitor, req);
                                 match req {
                                     Ok(Some(::remoc::rtc::Req::Ref(req))) => {
@@ -1068,7 +1153,7 @@ impl TraitDef {
         let doc = format!("Server for [{}] taking the target object by shared reference.", &ident);
 
         let dispatch_ref = if self.is_taking_ref() {
-            quote! { req.dispatch(&*target, err_tx).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(&*target, err_tx, interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
@@ -1088,6 +1173,8 @@ impl TraitDef {
                     Codec,
                 >,
                 monitor: ::std::boxed::Box<dyn ::remoc::rtc::ServerMonitor<#req_params>>,
+                interceptors: ::remoc::rtc::Interceptors,
+                limiter: ::remoc::rtc::Limiter,
             }
 
             impl #impl_generics_impl ::remoc::rtc::ServerBase for #server #impl_generics_ty #impl_generics_where
@@ -1095,6 +1182,13 @@ impl TraitDef {
                 type Client = #client #req_generics;
             }
 
+            impl #impl_generics_impl ::remoc::rtc::InterceptableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn add_interceptor(&mut self, interceptor: impl ::remoc::rtc::Interceptor + 'static) {
+                    self.interceptors.push(interceptor);
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::MonitorableServer for #server #impl_generics_ty #impl_generics_where
             {
                 type Value = #req_value #req_generics;
@@ -1106,22 +1200,44 @@ impl TraitDef {
                 }
             }
 
+            impl #impl_generics_impl ::remoc::rtc::LimitableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn max_concurrency(&self) -> ::std::option::Option<::std::num::NonZeroUsize> {
+                    self.limiter.max_concurrency()
+                }
+
+                fn set_max_concurrency(&mut self, limit: ::std::option::Option<::std::num::NonZeroUsize>) {
+                    self.limiter.set_max_concurrency(limit)
+                }
+
+                fn limit_behavior(&self) -> ::remoc::rtc::LimitBehavior {
+                    self.limiter.behavior()
+                }
+
+                fn set_limit_behavior(&mut self, behavior: ::remoc::rtc::LimitBehavior) {
+                    self.limiter.set_behavior(behavior)
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::ServerShared <Target, Codec> for #server #impl_generics_ty #impl_generics_where
             {
                 fn new(target: ::std::sync::Arc<Target>, request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (
                         Self {
                             target,
                             req_rx,
                             monitor: ::std::boxed::Box::new(::remoc::rtc::DefaultServerMonitor),
+                            interceptors: ::std::default::Default::default(),
+                            limiter: ::std::default::Default::default(),
                         },
                         Self::Client::new(req_tx),
                     )
                 }
 
                 async fn serve(self, spawn: bool) -> ::std::result::Result<(), ::remoc::rtc::ServeError> {
-                    let Self { target, mut req_rx, mut monitor } = self;
+                    let Self { target, mut req_rx, mut monitor, interceptors, limiter } = self;
                     let (err_tx, mut err_rx) = ::remoc::rtc::reply_error_channel();
 
                     let ret = loop {
@@ -1129,14 +1245,23 @@ impl TraitDef {
                             biased;
                             Some(err) = err_rx.recv() => return Err(err.into()),
                             req = req_rx.recv() => {
+                                let __span = ::remoc::rtc::received_span(&req_rx);
                                 ::remoc::rtc::server_monitor_pre_dispatch!(monitor, req);
                                 match req {
                                     Ok(Some(::remoc::rtc::Req::Ref(req))) => {
                                         let err_tx = err_tx.clone();
                                         if spawn {
                                             use ::remoc::rtc::Instrument;
+                                            let interceptors = interceptors.clone();
+                                            let ::std::option::Option::Some(permit) = limiter.acquire().await else {
+                                                // Reply without spawning a task, so that rejected calls
+                                                // do not bypass the limit.
+                                                req.reject();
+                                                continue;
+                                            };
                                             let target = target.clone();
                                             ::remoc::rtc::spawn(async move {
+                                                let _permit = permit;
                                                 #dispatch_ref
                                             }.in_current_span());
                                         } else {
@@ -1187,13 +1312,13 @@ impl TraitDef {
         let doc = format!("Server for [{}] taking the target object by shared mutable reference.", &ident);
 
         let dispatch_ref = if self.is_taking_ref() {
-            quote! { req.dispatch(&*target, err_tx).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(&*target, err_tx, interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
 
         let dispatch_ref_mut = if self.is_taking_ref_mut() {
-            quote! { req.dispatch(&mut *target, err_tx.clone()).await; }
+            quote! { ::remoc::rtc::Instrument::instrument(req.dispatch(&mut *target, err_tx.clone(), interceptors.clone()), __span).await; }
         } else {
             quote! {}
         };
@@ -1213,6 +1338,8 @@ impl TraitDef {
                     Codec,
                 >,
                 monitor: ::std::boxed::Box<dyn ::remoc::rtc::ServerMonitor<#req_params>>,
+                interceptors: ::remoc::rtc::Interceptors,
+                limiter: ::remoc::rtc::Limiter,
             }
 
             impl #impl_generics_impl ::remoc::rtc::ServerBase for #server #impl_generics_ty #impl_generics_where
@@ -1220,6 +1347,13 @@ impl TraitDef {
                 type Client = #client #req_generics;
             }
 
+            impl #impl_generics_impl ::remoc::rtc::InterceptableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn add_interceptor(&mut self, interceptor: impl ::remoc::rtc::Interceptor + 'static) {
+                    self.interceptors.push(interceptor);
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::MonitorableServer for #server #impl_generics_ty #impl_generics_where
             {
                 type Value = #req_value #req_generics;
@@ -1231,22 +1365,44 @@ impl TraitDef {
                 }
             }
 
+            impl #impl_generics_impl ::remoc::rtc::LimitableServer for #server #impl_generics_ty #impl_generics_where
+            {
+                fn max_concurrency(&self) -> ::std::option::Option<::std::num::NonZeroUsize> {
+                    self.limiter.max_concurrency()
+                }
+
+                fn set_max_concurrency(&mut self, limit: ::std::option::Option<::std::num::NonZeroUsize>) {
+                    self.limiter.set_max_concurrency(limit)
+                }
+
+                fn limit_behavior(&self) -> ::remoc::rtc::LimitBehavior {
+                    self.limiter.behavior()
+                }
+
+                fn set_limit_behavior(&mut self, behavior: ::remoc::rtc::LimitBehavior) {
+                    self.limiter.set_behavior(behavior)
+                }
+            }
+
             impl #impl_generics_impl ::remoc::rtc::ServerSharedMut <Target, Codec> for #server #impl_generics_ty #impl_generics_where
             {
                 fn new(target: ::std::sync::Arc<::remoc::rtc::LocalRwLock<Target>>, request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (
                         Self {
                             target,
                             req_rx,
                             monitor: ::std::boxed::Box::new(::remoc::rtc::DefaultServerMonitor),
+                            interceptors: ::std::default::Default::default(),
+                            limiter: ::std::default::Default::default(),
                         },
                         Self::Client::new(req_tx),
                     )
                 }
 
                 async fn serve(self, spawn: bool) -> ::std::result::Result<(), ::remoc::rtc::ServeError> {
-                    let Self { target, mut req_rx, mut monitor } = self;
+                    let Self { target, mut req_rx, mut monitor, interceptors, limiter } = self;
                     let (err_tx, mut err_rx) = ::remoc::rtc::reply_error_channel();
 
                     let ret = loop {
@@ -1254,14 +1410,23 @@ impl TraitDef {
                             biased;
                             Some(err) = err_rx.recv() => return Err(err.into()),
                             req = req_rx.recv() => {
+                                let __span = ::remoc::rtc::received_span(&req_rx);
                                 ::remoc::rtc::server_monitor_pre_dispatch!(monitor, req);
                                 match req {
                                     Ok(Some(::remoc::rtc::Req::Ref(req))) => {
                                         let err_tx = err_tx.clone();
                                         if spawn {
                                             use ::remoc::rtc::Instrument;
+                                            let interceptors = interceptors.clone();
+                                            let ::std::option::Option::Some(permit) = limiter.acquire().await else {
+                                                // Reply without spawning a task, so that rejected calls
+                                                // do not bypass the limit.
+                                                req.reject();
+                                                continue;
+                                            };
                                             let target = target.clone().read_owned().await;
                                             ::remoc::rtc::spawn(async move {
+                                                let _permit = permit;
                                                 #dispatch_ref
                                             }.in_current_span());
                                         } else {
@@ -1339,7 +1504,8 @@ impl TraitDef {
                 type Req = #req_all #req_generics;
 
                 fn new(request_buffer: usize) -> (Self, Self::Client) {
-                    let (req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    let (mut req_tx, req_rx) = ::remoc::rch::mpsc::channel(request_buffer);
+                    req_tx.set_rtc();
                     (Self { req_rx }, Self::Client::new(req_tx))
                 }
 
@@ -1429,6 +1595,106 @@ impl TraitDef {
         Ok(servers)
     }
 
+    /// Call and reply enums with implementation of `Callable` for the client.
+    pub fn calls(&self) -> TokenStream {
+        let Self { vis, ident, .. } = self;
+        let assoc = &self.assoc_types;
+        let client_ident = self.client_ident();
+        let call_ident = format_ident!("{}Call", ident);
+        let reply_ident = format_ident!("{}Reply", ident);
+
+        let (ty_generics, _) = self.generics(GenericsArgs {
+            with_target: false,
+            with_codec: false,
+            with_codec_default: false,
+            with_lifetime: false,
+            with_send: false,
+            with_sync: false,
+            with_static: false,
+            with_assoc_types: true,
+        });
+        let ty_generics_where = &ty_generics.where_clause;
+        let ty_generics_list = &ty_generics.params;
+
+        let (_, mut impl_generics) = self.generics(GenericsArgs {
+            with_target: false,
+            with_codec: true,
+            with_codec_default: false,
+            with_lifetime: false,
+            with_send: false,
+            with_sync: false,
+            with_static: false,
+            with_assoc_types: true,
+        });
+        let call_args = self.req_args_bare(false);
+        let wc: WhereClause = syn::parse2(quote! {
+            where
+                Self: ::std::marker::Send + 'static,
+                #call_ident #call_args: ::std::marker::Send + 'static,
+                #reply_ident #call_args: ::std::marker::Send + 'static
+        })
+        .unwrap();
+        impl_generics.make_where_clause().predicates.extend(wc.predicates);
+        let (impl_generics_impl, impl_generics_ty, impl_generics_where) = impl_generics.split_for_impl();
+
+        let trait_args = self.trait_generic_arg_tokens();
+        let trait_path = if trait_args.is_empty() {
+            quote! { #ident }
+        } else {
+            quote! { #ident < #(#trait_args),* > }
+        };
+
+        let (mut call_entries, mut reply_entries, mut invoke_clauses) = (quote! {}, quote! {}, quote! {});
+        for md in self.methods.iter().filter(|md| md.is_callable()) {
+            call_entries.append_all(md.call_enum_entry(assoc));
+            reply_entries.append_all(md.reply_enum_entry(assoc));
+            invoke_clauses.append_all(md.invoke_clause(&trait_path, &call_ident, &reply_ident));
+        }
+
+        let call_doc = format!(
+            "Call of a method on [{ident}] represented as a value.\n\
+            \n\
+            Performed on a client using [Callable::invoke](::remoc::rtc::Callable::invoke)."
+        );
+        let reply_doc = format!(
+            "Result of a method call on [{ident}] represented as a value.\n\
+            \n\
+            When matching on this, use a wildcard match `_` to ignore all unknown variants."
+        );
+
+        quote! {
+            #[doc=#call_doc]
+            #[allow(dead_code)]
+            #vis enum #call_ident #ty_generics #ty_generics_where {
+                #call_entries
+                #[doc(hidden)]
+                __Phantom (::std::marker::PhantomData<(#ty_generics_list)>)
+            }
+
+            #[doc=#reply_doc]
+            #[allow(dead_code)]
+            #vis enum #reply_ident #ty_generics #ty_generics_where {
+                #reply_entries
+                #[doc(hidden)]
+                __Phantom (::std::marker::PhantomData<(#ty_generics_list)>)
+            }
+
+            impl #impl_generics_impl ::remoc::rtc::Callable for #client_ident #impl_generics_ty #impl_generics_where {
+                type Call = #call_ident #call_args;
+                type Reply = #reply_ident #call_args;
+
+                fn invoke(self, call: Self::Call) -> ::remoc::rtc::BoxFuture<'static, Self::Reply> {
+                    match call {
+                        #invoke_clauses
+                        #call_ident::__Phantom(phantom) => {
+                            ::remoc::rtc::FutureExt::boxed(::std::future::ready(#reply_ident::__Phantom(phantom)))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// The client proxy.
     pub fn client(&self) -> TokenStream {
         let Self { vis, ident, attrs, generics, .. } = self;
@@ -1482,6 +1748,7 @@ impl TraitDef {
                         Self {
                             req_tx: self.req_tx.clone(),
                             max_reply_size: self.max_reply_size,
+                            call_options: self.call_options.clone(),
                             drop_tx: self.drop_tx.clone(),
                         }
                     }
@@ -1512,6 +1779,8 @@ impl TraitDef {
                 #[serde(default = "::remoc::rtc::missing_max_reply_size", with = "::remoc::rtc::serde_max_reply_size")]
                 max_reply_size: usize,
                 #[serde(skip)]
+                call_options: ::remoc::rtc::CallOptions,
+                #[serde(skip)]
                 #[serde(default = "::remoc::rtc::empty_client_drop_tx")]
                 drop_tx: ::remoc::rtc::local_broadcast::Sender<()>,
             }
@@ -1527,6 +1796,7 @@ impl TraitDef {
                     Self {
                         req_tx,
                         max_reply_size: ::remoc::rch::DEFAULT_MAX_ITEM_SIZE,
+                        call_options: ::remoc::rtc::CallOptions::new(),
                         drop_tx: ::remoc::rtc::empty_client_drop_tx(),
                     }
                 }
@@ -1567,6 +1837,16 @@ impl TraitDef {
                 fn set_max_reply_size(&mut self, max_reply_size: usize) {
                     self.max_reply_size = max_reply_size
                 }
+
+                fn call_options(&self) -> &::remoc::rtc::CallOptions {
+                    &self.call_options
+                }
+            }
+
+            impl #impl_generics_impl ::remoc::rtc::ConfigurableClient for #client_ident #impl_generics_ty #impl_generics_where {
+                fn call_options_mut(&mut self) -> &mut ::remoc::rtc::CallOptions {
+                    &mut self.call_options
+                }
             }
 
             #async_trait
@@ -30,3 +30,19 @@ pub fn attribute_tokens(attrs: &[Attribute]) -> TokenStream {
     }
     tokens
 }
+
+/// Parses a duration such as `500ms`, `5s`, `2m` or `1h` into milliseconds.
+pub fn parse_duration_millis(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value.parse().ok()?;
+    let factor = match unit.trim() {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return None,
+    };
+    value.checked_mul(factor)
+}